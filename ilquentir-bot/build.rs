@@ -0,0 +1,3 @@
+fn main() {
+    ilquentir_messages::build::prepare_templates().expect("failed to prepare message templates");
+}
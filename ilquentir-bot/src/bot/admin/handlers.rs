@@ -0,0 +1,2 @@
+mod command;
+pub use command::*;
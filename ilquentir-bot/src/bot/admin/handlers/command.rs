@@ -0,0 +1,365 @@
+use color_eyre::Result;
+use sqlx::PgPool;
+use teloxide::types::Message;
+use tracing::{info, warn};
+
+use ilquentir_config::Config;
+use ilquentir_messages::{md_message, message, Locale, Markup};
+use time::{macros::format_description, OffsetDateTime};
+
+use ilquentir_models::{
+    PgTransaction, PollKind, PollOption, PollOptionCategory, UserProfile, UserSource,
+};
+
+use crate::bot::{commands::AdminCommand, helpers::send_long_message, Bot};
+
+/// Admin commands manage the daily events catalog only
+const POLL_KIND: PollKind = PollKind::DailyEvents;
+/// Used instead of category slug to move option out of any category
+const NO_CATEGORY: &str = "-";
+/// Admin replies aren't translated, as well as the usage hints
+const LOCALE: Locale = Locale::Ru;
+
+#[tracing::instrument(skip(bot, pool, config), err)]
+pub async fn handle_admin_command(
+    bot: Bot,
+    pool: PgPool,
+    config: Config,
+    msg: Message,
+    command: AdminCommand,
+) -> Result<()> {
+    let user_tg_id = msg.from().map(|user| user.id.0 as i64);
+
+    if !user_tg_id.map_or(false, |user_tg_id| config.is_admin(user_tg_id)) {
+        warn!(?user_tg_id, "admin command from non-admin user, ignoring");
+
+        return Ok(());
+    }
+
+    let mut txn = pool.begin().await?;
+
+    let reply = match command {
+        AdminCommand::Catalog => catalog(&mut txn).await?,
+        AdminCommand::CatalogAdd(args) => match split_args::<2>(&args) {
+            Some([category, text]) => catalog_add(&mut txn, category, text).await?,
+            None => usage("catalog_add", "<категория> <текст>"),
+        },
+        AdminCommand::CatalogRename(args) => match split_args::<3>(&args) {
+            Some([id, language, text]) => match id.parse() {
+                Ok(id) => catalog_rename(&mut txn, id, language, text).await?,
+                Err(_) => usage("catalog_rename", "<id> <язык> <текст>"),
+            },
+            None => usage("catalog_rename", "<id> <язык> <текст>"),
+        },
+        AdminCommand::CatalogMove(args) => match split_args::<2>(&args) {
+            Some([id, position]) => match (id.parse(), position.parse()) {
+                (Ok(id), Ok(position)) => {
+                    let moved = PollOption::set_position(&mut txn, id, position).await?;
+
+                    found_or_not(moved, id)
+                }
+                _ => usage("catalog_move", "<id> <позиция>"),
+            },
+            None => usage("catalog_move", "<id> <позиция>"),
+        },
+        AdminCommand::CatalogCategory(args) => match split_args::<2>(&args) {
+            Some([id, category]) => match id.parse() {
+                Ok(id) => catalog_category(&mut txn, id, category).await?,
+                Err(_) => usage("catalog_category", "<id> <категория>"),
+            },
+            None => usage("catalog_category", "<id> <категория>"),
+        },
+        AdminCommand::CatalogHide(args) => match args.trim().parse() {
+            Ok(id) => found_or_not(PollOption::set_active(&mut txn, id, false).await?, id),
+            Err(_) => usage("catalog_hide", "<id>"),
+        },
+        AdminCommand::CatalogShow(args) => match args.trim().parse() {
+            Ok(id) => found_or_not(PollOption::set_active(&mut txn, id, true).await?, id),
+            Err(_) => usage("catalog_show", "<id>"),
+        },
+        AdminCommand::CatalogRetire(args) => match args.trim().parse() {
+            Ok(id) => found_or_not(PollOption::retire_catalog(&mut txn, id).await?, id),
+            Err(_) => usage("catalog_retire", "<id>"),
+        },
+        AdminCommand::CategoryAdd(args) => match split_args::<2>(&args) {
+            Some([slug, label]) => category_add(&mut txn, slug, label).await?,
+            None => usage("category_add", "<slug> <название>"),
+        },
+        AdminCommand::CategoryRename(args) => match split_args::<3>(&args) {
+            Some([slug, language, label]) => {
+                category_rename(&mut txn, slug, language, label).await?
+            }
+            None => usage("category_rename", "<slug> <язык> <название>"),
+        },
+        AdminCommand::Sources => sources(&mut txn).await?,
+        AdminCommand::User(args) => match args.trim() {
+            "" => usage("user", "<@username или id>"),
+            query => find_user(&mut txn, query).await?,
+        },
+    };
+
+    txn.commit().await?;
+    info!(?user_tg_id, "admin command handled");
+
+    send_long_message(&bot, msg.chat.id, &reply).await?;
+
+    Ok(())
+}
+
+#[tracing::instrument(skip(txn), err)]
+async fn catalog(txn: &mut PgTransaction<'_>) -> Result<String> {
+    let categories = PollOptionCategory::get_all(&mut *txn, POLL_KIND).await?;
+    let options = PollOption::get_catalog_for_admin(&mut *txn, POLL_KIND).await?;
+
+    let options = options.iter().map(|option| {
+        let category = categories
+            .iter()
+            .find(|category| Some(category.id) == option.category_id)
+            .map_or(NO_CATEGORY, |category| &category.slug);
+        let item = Markup::new().code(format!("#{}", option.id)).text(format!(
+            " [{}] {category}: {}",
+            option.position, option.text
+        ));
+
+        if option.active {
+            item
+        } else {
+            item.italic(" (скрыто)")
+        }
+    });
+    let categories = categories
+        .iter()
+        .map(|category| format!("{} ({})", category.slug, category.label))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    Ok(Markup::new()
+        .text(message!(LOCALE, "admin/catalog.md"))
+        .line()
+        .list(options)
+        .line()
+        .line()
+        .text(message!(
+            LOCALE,
+            "admin/catalog_categories.md",
+            categories = categories
+        ))
+        .markdown_v2())
+}
+
+#[tracing::instrument(skip(txn), err)]
+async fn catalog_add(txn: &mut PgTransaction<'_>, category: &str, text: &str) -> Result<String> {
+    let category_id = match find_category(&mut *txn, category).await? {
+        Ok(category_id) => category_id,
+        Err(reply) => return Ok(reply),
+    };
+
+    if text.chars().count() > PollOption::MAX_TEXT_LEN {
+        return Ok(usage("catalog_add", "<категория> <текст до 100 символов>"));
+    }
+
+    Ok(
+        match PollOption::create_catalog(txn, POLL_KIND, category_id, text).await? {
+            Some(option) => {
+                md_message!(LOCALE, "admin/done.md", result = format!("#{}", option.id))
+            }
+            None => md_message!(LOCALE, "admin/duplicate.md", what = text),
+        },
+    )
+}
+
+#[tracing::instrument(skip(txn), err)]
+async fn catalog_rename(
+    txn: &mut PgTransaction<'_>,
+    option_id: i64,
+    language: &str,
+    text: &str,
+) -> Result<String> {
+    if !PollOption::LANGUAGES.contains(&language) || text.chars().count() > PollOption::MAX_TEXT_LEN
+    {
+        return Ok(usage(
+            "catalog_rename",
+            "<id> <ru|en> <текст до 100 символов>",
+        ));
+    }
+
+    let renamed = PollOption::rename_catalog(txn, option_id, language, text).await?;
+
+    Ok(found_or_not(renamed, option_id))
+}
+
+#[tracing::instrument(skip(txn), err)]
+async fn catalog_category(
+    txn: &mut PgTransaction<'_>,
+    option_id: i64,
+    category: &str,
+) -> Result<String> {
+    let category_id = match find_category(&mut *txn, category).await? {
+        Ok(category_id) => category_id,
+        Err(reply) => return Ok(reply),
+    };
+
+    let updated = PollOption::set_category(txn, option_id, category_id).await?;
+
+    Ok(found_or_not(updated, option_id))
+}
+
+#[tracing::instrument(skip(txn), err)]
+async fn category_add(txn: &mut PgTransaction<'_>, slug: &str, label: &str) -> Result<String> {
+    if slug.len() > PollOptionCategory::MAX_SLUG_LEN
+        || label.chars().count() > PollOptionCategory::MAX_LABEL_LEN
+    {
+        return Ok(usage(
+            "category_add",
+            "<slug до 50 символов> <название до 100 символов>",
+        ));
+    }
+
+    Ok(
+        match PollOptionCategory::create(txn, POLL_KIND, slug, label).await? {
+            Some(category) => md_message!(LOCALE, "admin/done.md", result = category.slug),
+            None => md_message!(LOCALE, "admin/duplicate.md", what = slug),
+        },
+    )
+}
+
+#[tracing::instrument(skip(txn), err)]
+async fn category_rename(
+    txn: &mut PgTransaction<'_>,
+    slug: &str,
+    language: &str,
+    label: &str,
+) -> Result<String> {
+    if !PollOption::LANGUAGES.contains(&language)
+        || label.chars().count() > PollOptionCategory::MAX_LABEL_LEN
+    {
+        return Ok(usage(
+            "category_rename",
+            "<slug> <ru|en> <название до 100 символов>",
+        ));
+    }
+
+    let category = if let Some(category) =
+        PollOptionCategory::get_by_slug(&mut *txn, POLL_KIND, slug).await?
+    {
+        category
+    } else {
+        return Ok(md_message!(LOCALE, "admin/not_found.md", what = slug));
+    };
+
+    PollOptionCategory::set_label(txn, category.id, language, label).await?;
+
+    Ok(md_message!(LOCALE, "admin/done.md", result = slug))
+}
+
+#[tracing::instrument(skip(txn), err)]
+async fn sources(txn: &mut PgTransaction<'_>) -> Result<String> {
+    let sources = UserSource::stats(txn).await?;
+    let sources = sources.iter().map(|stats| {
+        Markup::new().code(&stats.source).text(format!(
+            ": {signups} регистраций, {active} активны, {retained} из {week_old} вернулись через неделю",
+            signups = stats.signups,
+            active = stats.active,
+            retained = stats.retained,
+            week_old = stats.week_old,
+        ))
+    });
+
+    Ok(Markup::new()
+        .text(message!(LOCALE, "admin/sources.md"))
+        .line()
+        .list(sources)
+        .line()
+        .line()
+        .italic(message!(LOCALE, "admin/sources_note.md"))
+        .markdown_v2())
+}
+
+/// Looks the user up by Telegram id or by username, with or without `@`
+#[tracing::instrument(skip(txn), err)]
+async fn find_user(txn: &mut PgTransaction<'_>, query: &str) -> Result<String> {
+    let profiles = match query.parse() {
+        Ok(tg_id) => UserProfile::get(txn, tg_id).await?.into_iter().collect(),
+        Err(_) => UserProfile::find_by_username(txn, query.trim_start_matches('@')).await?,
+    };
+
+    if profiles.is_empty() {
+        return Ok(md_message!(LOCALE, "admin/not_found.md", what = query));
+    }
+
+    let users = profiles.iter().map(|profile| {
+        Markup::new()
+            .code(format!("#{}", profile.tg_id))
+            .text(format!(
+                " @{username} {first_name}, язык: {language}, {status}, с {created}, последний раз: {last_seen}",
+                username = profile.username.as_deref().unwrap_or("-"),
+                first_name = profile.first_name.as_deref().unwrap_or("-"),
+                language = profile.language_code.as_deref().unwrap_or("-"),
+                status = if profile.active { "активен" } else { "остановил бота" },
+                created = format_date(profile.date_created),
+                last_seen = profile.date_last_seen.map_or_else(|| "-".to_owned(), format_date),
+            ))
+    });
+
+    Ok(Markup::new()
+        .text(message!(LOCALE, "admin/users.md"))
+        .line()
+        .list(users)
+        .markdown_v2())
+}
+
+fn format_date(date: OffsetDateTime) -> String {
+    date.format(format_description!(
+        "[year]-[month]-[day] [hour]:[minute] UTC"
+    ))
+    .unwrap_or_default()
+}
+
+/// Resolves category slug into its id, or into a reply, if there is no such category
+async fn find_category(
+    txn: &mut PgTransaction<'_>,
+    slug: &str,
+) -> Result<Result<Option<i64>, String>> {
+    if slug == NO_CATEGORY {
+        return Ok(Ok(None));
+    }
+
+    Ok(
+        match PollOptionCategory::get_by_slug(txn, POLL_KIND, slug).await? {
+            Some(category) => Ok(Some(category.id)),
+            None => Err(md_message!(LOCALE, "admin/not_found.md", what = slug)),
+        },
+    )
+}
+
+/// Splits arguments by whitespace into exactly `N` non-empty parts, the last one takes the rest
+fn split_args<const N: usize>(args: &str) -> Option<[&str; N]> {
+    let mut result = [""; N];
+    let mut rest = args.trim();
+
+    for part in result.iter_mut().take(N - 1) {
+        let (head, tail) = rest.split_once(char::is_whitespace)?;
+
+        *part = head;
+        rest = tail.trim_start();
+    }
+
+    if rest.is_empty() {
+        return None;
+    }
+    result[N - 1] = rest;
+
+    Some(result)
+}
+
+fn usage(command: &str, usage: &str) -> String {
+    md_message!(LOCALE, "admin/usage.md", command = command, usage = usage)
+}
+
+fn found_or_not(found: bool, option_id: i64) -> String {
+    if found {
+        md_message!(LOCALE, "admin/done.md", result = format!("#{option_id}"))
+    } else {
+        md_message!(LOCALE, "admin/not_found.md", what = format!("#{option_id}"))
+    }
+}
@@ -0,0 +1,3 @@
+mod handlers;
+
+pub use handlers::*;
@@ -1,83 +1,89 @@
-use indexmap::IndexMap;
-use teloxide::types::InlineKeyboardButton;
-
-#[derive(Debug, Clone, Copy, strum::EnumString, strum::IntoStaticStr)]
-#[strum(serialize_all = "snake_case")]
-pub enum Scope {
-    DailyEvents,
-    PromoDailyEvents,
-}
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+use teloxide::{
+    payloads::AnswerCallbackQuerySetters,
+    requests::Requester,
+    types::{CallbackQuery, InlineKeyboardButton},
+};
+use tracing::info;
 
-impl Scope {
-    pub fn to_str(self) -> &'static str {
-        match self {
-            Self::PromoDailyEvents => "promo_daily",
-            _ => self.into(),
-        }
-    }
+use ilquentir_messages::{message, Locale};
+use ilquentir_models::PollKind;
 
-    pub fn from_payload(data: &str) -> Option<Self> {
-        // TODO: use strum parsing
-        match data.split_once(':')?.0 {
-            "daily_events" => Some(Self::DailyEvents),
-            "promo_daily" => Some(Self::PromoDailyEvents),
-            _ => {
-                warn!(data, "payload with unknown scope");
+use crate::bot::{
+    daily_events, how_was_your_day, inline_poll, language, mood_check_in, mood_scale, onboarding,
+    settings, setup_schedule, Bot,
+};
 
-                None
-            }
-        }
-    }
-}
+/// Version of the payload format, stored in the first byte of every payload.
+///
+/// Must be bumped on any incompatible change of [`CallbackData`]: reordering or removing
+/// variants or changing their fields. New variants can be safely added to the end.
+pub const PAYLOAD_VERSION: u8 = 1;
+/// Telegram limits callback data to 64 bytes
+const MAX_PAYLOAD_LEN: usize = 64;
 
-#[derive(Debug, Clone)]
-pub(super) struct CallbackButtonData {
-    pub value: String,
-    payload: String,
-    #[allow(dead_code)]
-    scope: Scope,
+/// Data attached to the inline keyboard button, routed to the handler by its variant
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CallbackData {
+    DailyEvents(daily_events::SettingsAction),
+    PromoDailyEvents(daily_events::PromoAction),
+    PersonalDailyEvents(daily_events::PersonalAction),
+    PollAnswer(inline_poll::keyboard::PollAnswerAction),
+    AnswerMode(PollKind),
+    AnswerNote(how_was_your_day::keyboard::NoteAction),
+    MoodCheckIn(mood_check_in::CheckInAction),
+    MoodCheckInSettings(mood_check_in::SettingsAction),
+    MoodScale(mood_scale::ScaleAction),
+    Settings(settings::MenuAction),
+    Schedule(setup_schedule::TimePickerAction),
+    Onboarding(onboarding::OnboardingAction),
+    Language(language::LanguageAction),
 }
 
-impl CallbackButtonData {
-    pub fn new(data: impl AsRef<str>, scope: Scope) -> Self {
-        Self {
-            value: data.as_ref().to_string(),
-            payload: format_payload(stable_hash::fast_stable_hash(&data.as_ref()), scope),
-            scope,
-        }
-    }
+impl CallbackData {
+    /// Serializes data into version byte + postcard, encoded with url-safe base64.
+    ///
+    /// Every variant fits into Telegram's limit even with the longest fields,
+    /// which is checked by the tests.
+    pub fn encode(&self) -> String {
+        let mut bytes = vec![PAYLOAD_VERSION];
+        bytes.extend(postcard::to_allocvec(self).expect("callback data is always serializable"));
 
-    pub fn create_button(&self, button_text: impl AsRef<str>) -> InlineKeyboardButton {
-        InlineKeyboardButton::callback(button_text.as_ref(), &self.payload)
+        let payload = URL_SAFE_NO_PAD.encode(bytes);
+        debug_assert!(payload.len() <= MAX_PAYLOAD_LEN, "{self:?} is too long");
+
+        payload
     }
 
-    pub fn value(&self) -> &String {
-        &self.value
+    /// Parses payload, returns `None` for malformed payloads and payloads of other versions
+    /// (i.e. buttons of the messages, sent before the format has changed)
+    pub fn decode(payload: &str) -> Option<Self> {
+        let bytes = URL_SAFE_NO_PAD.decode(payload).ok()?;
+
+        match bytes.split_first()? {
+            (&PAYLOAD_VERSION, data) => postcard::from_bytes(data).ok(),
+            _ => None,
+        }
     }
 
-    pub fn matches(&self, payload: impl AsRef<str>) -> bool {
-        self.payload == payload.as_ref()
+    pub fn create_button(&self, button_text: impl AsRef<str>) -> InlineKeyboardButton {
+        InlineKeyboardButton::callback(button_text.as_ref(), self.encode())
     }
 }
 
-pub(super) fn make_callback_data(
-    options: impl Iterator<Item = impl AsRef<str>>,
-    scope: Scope,
-) -> IndexMap<String, CallbackButtonData> {
-    options
-        .map(|option| {
-            let data = CallbackButtonData::new(option, scope);
-
-            (data.value.clone(), data)
-        })
-        .collect()
-}
+/// Tells user that the button can't be handled anymore, e.g. it references removed option
+/// or comes from an old message
+#[tracing::instrument(skip(bot, callback), err)]
+pub async fn answer_stale(bot: &Bot, callback: &CallbackQuery, locale: Locale) -> Result<()> {
+    info!(payload = ?callback.data, "got stale button");
 
-pub(super) fn format_payload(hash: u128, scope: Scope) -> String {
-    let res = format!("{scope}:{hash}", scope = scope.to_str(), hash = hash);
-    debug_assert!(res.len() <= 64);
+    bot.answer_callback_query(&callback.id)
+        .text(message!(locale, "callbacks/stale.md"))
+        .await?;
 
-    res
+    Ok(())
 }
 
 macro_rules! buttons_row {
@@ -87,4 +93,121 @@ macro_rules! buttons_row {
 }
 
 pub(super) use buttons_row;
-use tracing::warn;
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use time::macros::time;
+
+    use super::*;
+
+    /// Every action with the longest possible fields, varints are the longest for `MIN`
+    /// of signed integers and `MAX` of unsigned ones
+    fn worst_cases() -> Vec<CallbackData> {
+        use daily_events::{PersonalAction, PromoAction};
+        use inline_poll::keyboard::PollAnswerAction;
+        use mood_check_in::CheckInAction;
+
+        let poll_id = i64::MIN;
+        let latest = time!(23:59:59.999_999_999);
+
+        let mut data = vec![
+            CallbackData::PromoDailyEvents(PromoAction::Yes),
+            CallbackData::PersonalDailyEvents(PersonalAction::Rename(i64::MIN)),
+            CallbackData::PersonalDailyEvents(PersonalAction::Remove(i64::MIN)),
+            CallbackData::PollAnswer(PollAnswerAction::Option {
+                poll_id,
+                option_idx: usize::MAX,
+            }),
+            CallbackData::PollAnswer(PollAnswerAction::Done { poll_id }),
+            CallbackData::AnswerMode(PollKind::MoodCheckIn),
+            CallbackData::AnswerNote(how_was_your_day::keyboard::NoteAction::Skip { poll_id }),
+            CallbackData::MoodCheckIn(CheckInAction::Rate {
+                poll_id,
+                dimension: usize::MAX,
+                value: i32::MIN,
+            }),
+            CallbackData::MoodCheckIn(CheckInAction::Label {
+                poll_id,
+                dimension: usize::MAX,
+            }),
+            CallbackData::MoodCheckInSettings(mood_check_in::SettingsAction::Toggle(i64::MIN)),
+            CallbackData::MoodScale(mood_scale::ScaleAction::Points(i32::MIN)),
+            CallbackData::MoodScale(mood_scale::ScaleAction::Label(usize::MAX)),
+            CallbackData::Settings(settings::MenuAction::StopConfirmed),
+            CallbackData::Schedule(setup_schedule::TimePickerAction::Pick(latest)),
+            CallbackData::Schedule(setup_schedule::TimePickerAction::Save(latest)),
+            CallbackData::Onboarding(onboarding::OnboardingAction::Timezone(usize::MAX)),
+            CallbackData::Onboarding(onboarding::OnboardingAction::Time(latest)),
+            CallbackData::Onboarding(onboarding::OnboardingAction::Toggle(PollKind::MoodCheckIn)),
+            CallbackData::Language(language::LanguageAction::Pick(usize::MAX)),
+        ];
+        data.extend(
+            [
+                daily_events::SettingsAction::Toggle(i64::MIN),
+                daily_events::SettingsAction::Page(usize::MAX),
+                daily_events::SettingsAction::None(usize::MAX),
+                daily_events::SettingsAction::All(usize::MAX),
+            ]
+            .map(CallbackData::DailyEvents),
+        );
+
+        data
+    }
+
+    #[test]
+    fn worst_case_fits() {
+        // match is here to be sure that it's impossible
+        // to add new variant without testing it :)
+        let variants: BTreeSet<_> = worst_cases()
+            .iter()
+            .map(|data| match data {
+                CallbackData::DailyEvents(_) => 0,
+                CallbackData::PromoDailyEvents(_) => 1,
+                CallbackData::PersonalDailyEvents(_) => 2,
+                CallbackData::PollAnswer(_) => 3,
+                CallbackData::AnswerMode(_) => 4,
+                CallbackData::AnswerNote(_) => 5,
+                CallbackData::MoodCheckIn(_) => 6,
+                CallbackData::MoodCheckInSettings(_) => 7,
+                CallbackData::MoodScale(_) => 8,
+                CallbackData::Settings(_) => 9,
+                CallbackData::Schedule(_) => 10,
+                CallbackData::Onboarding(_) => 11,
+                CallbackData::Language(_) => 12,
+            })
+            .collect();
+        assert_eq!(variants, (0..13).collect());
+
+        for data in worst_cases() {
+            let payload = data.encode();
+
+            assert!(
+                payload.len() <= MAX_PAYLOAD_LEN,
+                "{data:?} takes {} bytes",
+                payload.len()
+            );
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        for data in worst_cases() {
+            assert_eq!(CallbackData::decode(&data.encode()), Some(data));
+        }
+    }
+
+    #[test]
+    fn stale_payloads() {
+        // payload format, used before versioning
+        assert_eq!(CallbackData::decode("daily_events:1234567890"), None);
+        assert_eq!(CallbackData::decode(""), None);
+
+        let mut bytes = URL_SAFE_NO_PAD
+            .decode(CallbackData::AnswerMode(PollKind::DailyEvents).encode())
+            .unwrap();
+        bytes[0] = PAYLOAD_VERSION + 1;
+        assert_eq!(CallbackData::decode(&URL_SAFE_NO_PAD.encode(bytes)), None);
+    }
+}
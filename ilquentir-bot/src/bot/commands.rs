@@ -1,18 +1,129 @@
-use teloxide::utils::command::BotCommands;
+use teloxide::{types::BotCommand, utils::command::BotCommands};
+
+use ilquentir_messages::Locale;
 
 #[derive(Debug, Clone, BotCommands)]
 #[command(rename_rule = "snake_case")]
 pub enum Command {
+    /// Carries the payload of the `t.me/<bot>?start=<payload>` deep link, if any
     #[command(description = "Запустить Ильквентир")]
-    Start,
+    Start(String),
     #[command(description = "Что такое Ильквентир, зачем и как? :)")]
     Help,
+    #[command(description = "Все настройки в одном меню")]
+    Settings,
     #[command(description = "Настроить опрос про ежедневные события")]
     DailyEventsSettings,
+    #[command(description = "Включить или выключить вопрос дня о благодарности")]
+    GratitudeJournal,
+    #[command(description = "Настроить шкалу опроса о том, как прошёл день")]
+    MoodScale,
+    #[command(description = "Настроить чек-ин состояния по нескольким шкалам")]
+    MoodCheckIn,
+    #[command(description = "Выбрать, как отвечать на опросы: опросом Telegram или кнопками")]
+    AnswerMode,
     #[command(description = "Настроить, во сколько будет приходить опрос")]
     SetupSchedule,
     #[command(description = "Интерактивный график с моими данными")]
     GetStat,
+    #[command(description = "Личная ссылка, чтобы пригласить друзей")]
+    Invite,
+    #[command(description = "Выбрать язык / Choose the language")]
+    Language,
     #[command(description = "Выключить Ильквентир (не будет приходить стата и опросы)")]
     Stop,
 }
+
+/// Descriptions of [`Command`] in English, the default ones are in Russian
+const EN_DESCRIPTIONS: [(&str, &str); 13] = [
+    ("/start", "Start Ilquentir"),
+    ("/help", "What is Ilquentir, why and how? :)"),
+    ("/settings", "All the settings in one menu"),
+    ("/daily_events_settings", "Set up the daily events poll"),
+    (
+        "/gratitude_journal",
+        "Turn the gratitude question of the day on or off",
+    ),
+    (
+        "/mood_scale",
+        "Set up the scale of the how was your day poll",
+    ),
+    ("/mood_check_in", "Set up the check-in on several scales"),
+    (
+        "/answer_mode",
+        "Choose how to answer the polls: Telegram polls or buttons",
+    ),
+    ("/setup_schedule", "Choose when the polls are sent"),
+    ("/get_stat", "Interactive chart of my data"),
+    ("/invite", "Personal link to invite friends"),
+    ("/language", "Choose the language / Выбрать язык"),
+    ("/stop", "Turn Ilquentir off (no more stats and polls)"),
+];
+
+/// Commands for the bot menu with descriptions in the given language
+pub fn localized(locale: Locale) -> Vec<BotCommand> {
+    let mut commands = Command::bot_commands();
+
+    if locale == Locale::En {
+        for command in &mut commands {
+            if let Some((_, description)) = EN_DESCRIPTIONS
+                .iter()
+                .find(|(name, _)| *name == command.command)
+            {
+                command.description = (*description).to_owned();
+            }
+        }
+    }
+
+    commands
+}
+
+/// Commands for managing the bot, available only to users from `ADMIN_TG_IDS`.
+///
+/// They aren't registered in the bot menu.
+#[derive(Debug, Clone, BotCommands)]
+#[command(rename_rule = "snake_case")]
+pub enum AdminCommand {
+    #[command(description = "Каталог ежедневных событий")]
+    Catalog,
+    #[command(description = "Добавить событие: <категория> <текст>")]
+    CatalogAdd(String),
+    #[command(description = "Изменить текст события: <id> <язык> <текст>")]
+    CatalogRename(String),
+    #[command(description = "Изменить позицию события: <id> <позиция>")]
+    CatalogMove(String),
+    #[command(description = "Изменить категорию события: <id> <категория>")]
+    CatalogCategory(String),
+    #[command(description = "Скрыть событие: <id>")]
+    CatalogHide(String),
+    #[command(description = "Показать скрытое событие: <id>")]
+    CatalogShow(String),
+    #[command(description = "Убрать событие из каталога навсегда: <id>")]
+    CatalogRetire(String),
+    #[command(description = "Добавить категорию: <slug> <название>")]
+    CategoryAdd(String),
+    #[command(description = "Изменить название категории: <slug> <язык> <название>")]
+    CategoryRename(String),
+    #[command(description = "Регистрации и удержание по источникам")]
+    Sources,
+    #[command(description = "Найти пользователя: <@username или id>")]
+    User(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_command_is_translated() {
+        for command in Command::bot_commands() {
+            assert!(
+                EN_DESCRIPTIONS
+                    .iter()
+                    .any(|(name, _)| *name == command.command),
+                "{} has no English description",
+                command.command
+            );
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use color_eyre::Result;
+use sqlx::PgPool;
+use time::OffsetDateTime;
+use time_tz::OffsetDateTimeExt;
+use tracing::warn;
+
+use ilquentir_messages::{message, Locale, RenderContext, UserValues, Value};
+use ilquentir_models::{AnswerStats, PgTransaction, User, UserProfile};
+
+use super::setup_schedule::user_timezone;
+
+/// Values about the user, which every template can use,
+/// see [`CONTEXT_VARIABLES`](ilquentir_messages::CONTEXT_VARIABLES)
+#[derive(Debug, Clone)]
+pub struct UserContext {
+    pub locale: Locale,
+    /// First name from the Telegram profile, or a generic address, if it's unknown
+    pub name: String,
+    pub stats: AnswerStats,
+    /// Current time in the user's timezone
+    pub local_time: OffsetDateTime,
+    /// Days since the user has started the bot, `0` on the first day
+    pub days_since_start: i64,
+}
+
+impl UserContext {
+    /// Loads the context of the user, who gets a message with context variables.
+    ///
+    /// Context is loaded only by the handlers, which send such messages, and if it fails,
+    /// the message is still sent, with the values of a user, who has just started the bot.
+    #[tracing::instrument(skip(pool))]
+    pub async fn load_or_default(pool: &PgPool, user_tg_id: i64, locale: Locale) -> Self {
+        let context = async {
+            let mut txn = pool.begin().await?;
+
+            Self::load(&mut txn, user_tg_id, locale).await
+        }
+        .await;
+
+        context.unwrap_or_else(|error| {
+            warn!(
+                user_tg_id,
+                ?error,
+                "failed to load user context, using defaults"
+            );
+
+            Self::new_user(locale)
+        })
+    }
+
+    #[tracing::instrument(skip(txn), err)]
+    async fn load(txn: &mut PgTransaction<'_>, user_tg_id: i64, locale: Locale) -> Result<Self> {
+        let profile = UserProfile::get(&mut *txn, user_tg_id).await?;
+        let timezone = user_timezone(&mut *txn, user_tg_id).await?;
+        let local_time = OffsetDateTime::now_utc().to_timezone(timezone);
+
+        let stats = User::answer_stats(
+            &mut *txn,
+            user_tg_id,
+            local_time.offset().whole_seconds(),
+            local_time.date(),
+        )
+        .await?;
+        let days_since_start = profile.as_ref().map_or(0, |profile| {
+            let start = profile.date_created.to_offset(local_time.offset()).date();
+
+            (local_time.date() - start).whole_days()
+        });
+
+        Ok(Self {
+            locale,
+            name: profile
+                .and_then(|profile| profile.first_name)
+                .unwrap_or_else(|| message!(locale, "context/user_name.md")),
+            stats,
+            local_time,
+            days_since_start,
+        })
+    }
+
+    /// Context of a user, who has just started the bot and hasn't set anything up yet
+    fn new_user(locale: Locale) -> Self {
+        Self {
+            locale,
+            name: message!(locale, "context/user_name.md"),
+            stats: AnswerStats::default(),
+            local_time: OffsetDateTime::now_utc(),
+            days_since_start: 0,
+        }
+    }
+}
+
+impl RenderContext for UserContext {
+    fn locale(&self) -> Locale {
+        self.locale
+    }
+
+    fn value(&self, name: &str) -> Option<Value<'_>> {
+        match name {
+            "user_name" => Some(Value::Text(&self.name)),
+            "streak" => Some(Value::Integer(self.stats.streak)),
+            "answered_days" => Some(Value::Integer(self.stats.answered_days)),
+            "local_time" => Some(Value::Time(self.local_time.time())),
+            "days_since_start" => Some(Value::Integer(self.days_since_start)),
+            _ => None,
+        }
+    }
+}
+
+impl UserValues for UserContext {}
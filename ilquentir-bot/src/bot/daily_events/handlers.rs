@@ -6,3 +6,6 @@ pub use command::*;
 
 mod poll_update;
 pub use poll_update::*;
+
+mod message;
+pub use message::*;
@@ -1,24 +1,32 @@
 use color_eyre::{eyre::eyre, Result};
 use teloxide::{
-    payloads::{EditMessageReplyMarkupSetters, SendMessageSetters},
+    payloads::{EditMessageReplyMarkupSetters, EditMessageTextSetters, SendMessageSetters},
     requests::Requester,
-    types::CallbackQuery,
+    types::{CallbackQuery, ForceReply},
 };
-use tracing::warn;
+use tracing::info;
 
-use ilquentir_messages::md_message;
-use ilquentir_models::{PgTransaction, Poll, PollCustomOptions, PollKind};
+use ilquentir_messages::{md_message, message, Locale, Markup};
+use ilquentir_models::{
+    Dialogue, DialogueState, PersonalOptionEdit, PgTransaction, Poll, PollCustomOptions, PollKind,
+    PollOption,
+};
 
-use crate::bot::{helpers::send_poll, Bot};
+use crate::bot::{callbacks::answer_stale, helpers::send_poll, Bot};
 
-use super::super::{keyboard::user_daily_options, options};
+use super::super::{
+    keyboard::{user_daily_options, user_personal_options},
+    options::{PersonalAction, PromoAction, SettingsAction},
+    settings::Settings,
+};
 
 #[tracing::instrument(skip(bot, txn), err)]
 pub async fn handle_callback(
     bot: &Bot,
     txn: &mut PgTransaction<'_>,
     callback: &CallbackQuery,
-    payload: &str,
+    action: SettingsAction,
+    locale: Locale,
 ) -> Result<()> {
     const DAILY_EVENTS: PollKind = PollKind::DailyEvents;
 
@@ -27,79 +35,211 @@ pub async fn handle_callback(
         .message
         .as_ref()
         .ok_or_else(|| eyre!("payload with no message"))?;
-    let current = PollCustomOptions::get_for_user(&mut *txn, user_tg_id, DAILY_EVENTS).await?;
+    let settings = Settings::load(&mut *txn, user_tg_id, locale).await?;
+
+    let page = match action {
+        SettingsAction::Done => {
+            if settings.chosen.options.is_empty() {
+                Poll::disable_pending_for_user(&mut *txn, user_tg_id, DAILY_EVENTS).await?;
 
-    if options::DONE_BUTTON.matches(payload) {
-        if current.options.is_empty() {
-            Poll::disable_pending_for_user(&mut *txn, user_tg_id, DAILY_EVENTS).await?;
+                bot.edit_message_text(
+                    user_tg_id.to_string(),
+                    message.id,
+                    md_message!(locale, "daily_events/settings_empty.md"),
+                )
+                .await?;
+
+                return Ok(());
+            }
 
             bot.edit_message_text(
                 user_tg_id.to_string(),
                 message.id,
-                md_message!("daily_events/settings_empty.md"),
+                md_message!(locale, "daily_events/settings_done.md"),
             )
             .await?;
 
+            bot.answer_callback_query(&callback.id).await?;
+
+            let pending_daily_events_polls =
+                Poll::get_scheduled_for_user(&mut *txn, user_tg_id, DAILY_EVENTS).await?;
+
+            if pending_daily_events_polls.is_empty() {
+                let poll = Poll::create(&mut *txn, user_tg_id, DAILY_EVENTS, None).await?;
+                send_poll(bot, &mut *txn, poll).await?;
+            }
+
             return Ok(());
         }
+        SettingsAction::AddPersonal => {
+            ask_for_personal_option(bot, txn, user_tg_id, None, locale).await?;
+            bot.answer_callback_query(&callback.id).await?;
 
-        bot.edit_message_text(
-            user_tg_id.to_string(),
-            message.id,
-            md_message!("daily_events/settings_done.md"),
-        )
-        .await?;
+            return Ok(());
+        }
+        SettingsAction::ManagePersonal => {
+            let keyboard = user_personal_options(txn, user_tg_id, locale).await?;
 
-        bot.answer_callback_query(&callback.id).await?;
+            bot.send_message(
+                user_tg_id.to_string(),
+                md_message!(locale, "daily_events/personal.md"),
+            )
+            .reply_markup(keyboard)
+            .await?;
+            bot.answer_callback_query(&callback.id).await?;
 
-        let pending_daily_events_polls =
-            Poll::get_scheduled_for_user(&mut *txn, user_tg_id, DAILY_EVENTS).await?;
+            return Ok(());
+        }
+        SettingsAction::PageTitle => {
+            bot.answer_callback_query(&callback.id).await?;
 
-        if pending_daily_events_polls.is_empty() {
-            let poll = Poll::create(&mut *txn, user_tg_id, DAILY_EVENTS, None).await?;
-            send_poll(bot, &mut *txn, poll).await?;
+            return Ok(());
         }
+        SettingsAction::Page(page) => page,
+        SettingsAction::All(page) => {
+            let to_update: Vec<_> = settings
+                .options()
+                .filter(|o| !settings.chosen.contains(o.id))
+                .collect();
 
-        return Ok(());
-    }
+            if to_update.is_empty() {
+                bot.answer_callback_query(&callback.id).await?;
 
-    if options::ALL_BUTTON.matches(payload) {
-        let to_update: Vec<_> = options::ALL_OPTIONS
-            .values()
-            .filter(|o| !current.options.contains(o.value()))
-            .collect();
+                return Ok(());
+            }
+            for option in to_update {
+                // TODO: bulk insert
+                PollCustomOptions::toggle_option(&mut *txn, user_tg_id, DAILY_EVENTS, option.id)
+                    .await?;
+            }
 
-        if to_update.is_empty() {
-            bot.answer_callback_query(&callback.id).await?;
+            page
+        }
+        SettingsAction::None(page) => {
+            if settings.chosen.options.is_empty() {
+                bot.answer_callback_query(&callback.id).await?;
 
-            return Ok(());
+                return Ok(());
+            }
+
+            PollCustomOptions::clear_user_options(txn, user_tg_id, DAILY_EVENTS).await?;
+
+            page
         }
-        for option in to_update {
-            // TODO: bulk insert
-            PollCustomOptions::toggle_option(&mut *txn, user_tg_id, DAILY_EVENTS, option.value())
+        SettingsAction::Toggle(option_id) => {
+            let page = if let Some(page) = settings.page_of(option_id) {
+                page
+            } else {
+                // option was removed from the catalog after the keyboard was sent
+                return answer_stale(bot, callback, locale).await;
+            };
+
+            PollCustomOptions::toggle_option(&mut *txn, user_tg_id, DAILY_EVENTS, option_id)
                 .await?;
+
+            page
         }
-    } else if options::NONE_BUTTON.matches(payload) {
-        if current.options.is_empty() {
+    };
+
+    let settings = Settings::load(&mut *txn, user_tg_id, locale).await?;
+
+    bot.edit_message_text(user_tg_id.to_string(), message.id, settings.text())
+        .reply_markup(user_daily_options(&settings, page, locale))
+        .await?;
+    bot.answer_callback_query(&callback.id).await?;
+
+    Ok(())
+}
+
+#[tracing::instrument(skip(bot, txn), err)]
+pub async fn handle_personal_callback(
+    bot: &Bot,
+    txn: &mut PgTransaction<'_>,
+    callback: &CallbackQuery,
+    action: PersonalAction,
+    locale: Locale,
+) -> Result<()> {
+    let user_tg_id = callback.from.id.0 as i64;
+    let message = callback
+        .message
+        .as_ref()
+        .ok_or_else(|| eyre!("payload with no message"))?;
+
+    let option_id = match action {
+        PersonalAction::New => {
+            ask_for_personal_option(bot, txn, user_tg_id, None, locale).await?;
             bot.answer_callback_query(&callback.id).await?;
 
             return Ok(());
         }
+        PersonalAction::Rename(option_id) | PersonalAction::Remove(option_id) => option_id,
+    };
 
-        PollCustomOptions::clear_user_options(txn, user_tg_id, DAILY_EVENTS).await?;
-    } else if let Some(option) = options::ALL_OPTIONS.values().find(|o| o.matches(payload)) {
-        PollCustomOptions::toggle_option(&mut *txn, user_tg_id, DAILY_EVENTS, option.value())
-            .await?;
+    let personal = PollOption::get_personal(&mut *txn, user_tg_id, PollKind::DailyEvents).await?;
+    let option = if let Some(option) = personal.iter().find(|o| o.id == option_id) {
+        option
     } else {
-        warn!("got unknown payload");
+        return answer_stale(bot, callback, locale).await;
+    };
+
+    if let PersonalAction::Rename(_) = action {
+        ask_for_personal_option(bot, txn, user_tg_id, Some(option), locale).await?;
+    } else {
+        PollOption::retire_personal(&mut *txn, user_tg_id, option.id).await?;
+        info!(user_tg_id, option_id = option.id, "personal option removed");
+
+        let keyboard = user_personal_options(txn, user_tg_id, locale).await?;
+        bot.edit_message_reply_markup(user_tg_id.to_string(), message.id)
+            .reply_markup(keyboard)
+            .await?;
     }
 
-    let keyboard = user_daily_options(txn, user_tg_id).await?;
+    bot.answer_callback_query(&callback.id).await?;
+
+    Ok(())
+}
+
+/// Asks user for a text of the new option, or a new text of the existing one
+/// Question about the new text of the option, which is quoted literally
+fn rename_question(option: &PollOption, locale: Locale) -> String {
+    Markup::new()
+        .text(message!(
+            locale,
+            "daily_events/personal_rename.md",
+            option = option.text,
+            max_len = PollOption::MAX_TEXT_LEN
+        ))
+        .markdown_v2()
+}
+
+#[tracing::instrument(skip(bot, txn), err)]
+async fn ask_for_personal_option(
+    bot: &Bot,
+    txn: &mut PgTransaction<'_>,
+    user_tg_id: i64,
+    option: Option<&PollOption>,
+    locale: Locale,
+) -> Result<()> {
+    let text = if let Some(option) = option {
+        rename_question(option, locale)
+    } else {
+        md_message!(
+            locale,
+            "daily_events/personal_add.md",
+            max_len = PollOption::MAX_TEXT_LEN
+        )
+    };
 
-    bot.edit_message_reply_markup(user_tg_id.to_string(), message.id)
-        .reply_markup(keyboard)
+    let question = bot
+        .send_message(user_tg_id.to_string(), text)
+        .reply_markup(ForceReply::new())
         .await?;
-    bot.answer_callback_query(&callback.id).await?;
+
+    let state = DialogueState::PersonalOptionEdit(PersonalOptionEdit {
+        poll_kind: PollKind::DailyEvents,
+        option_id: option.map(|option| option.id),
+    });
+    Dialogue::start(txn, user_tg_id, state, question.id.0).await?;
 
     Ok(())
 }
@@ -109,31 +249,57 @@ pub async fn handle_promo_callback(
     bot: &Bot,
     txn: &mut PgTransaction<'_>,
     callback: &CallbackQuery,
-    payload: &str,
+    action: PromoAction,
+    locale: Locale,
 ) -> Result<()> {
     let user_tg_id = callback.from.id.0 as i64;
-    const POLL_KIND: PollKind = PollKind::DailyEvents;
 
-    if options::PROMO_NO_BUTTON.matches(payload) {
-        bot.send_message(
-            user_tg_id.to_string(),
-            md_message!("promo/daily_events_no.md"),
-        )
-        .await?;
-    } else if options::PROMO_YES_BUTTON.matches(payload) {
-        let keyboard = user_daily_options(txn, user_tg_id).await?;
+    match action {
+        PromoAction::No => {
+            bot.send_message(
+                user_tg_id.to_string(),
+                md_message!(locale, "promo/daily_events_no.md"),
+            )
+            .await?;
+        }
+        PromoAction::Yes => {
+            let settings = Settings::load(txn, user_tg_id, locale).await?;
 
-        bot.send_message(
-            user_tg_id.to_string(),
-            md_message!("daily_events/settings.md"),
-        )
-        .reply_markup(keyboard)
-        .await?;
-    } else {
-        warn!("got unknown payload");
+            bot.send_message(user_tg_id.to_string(), settings.text())
+                .reply_markup(user_daily_options(&settings, 0, locale))
+                .await?;
+        }
     }
 
     bot.answer_callback_query(&callback.id).await?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use ilquentir_messages::validate_markdown_v2;
+
+    use super::*;
+
+    #[test]
+    fn rename_question_quotes_option_literally() {
+        let option = PollOption {
+            id: 1,
+            poll_kind: PollKind::DailyEvents,
+            owner_tg_id: Some(1),
+            category_id: None,
+            position: 0,
+            is_default: false,
+            active: true,
+            text: "*работа* _дома_ [x](y)".to_owned(),
+        };
+
+        for locale in [Locale::Ru, Locale::En] {
+            let text = rename_question(&option, locale);
+
+            assert_eq!(validate_markdown_v2(&text), Ok(()));
+            assert!(text.contains(r"«\*работа\* \_дома\_ \[x\]\(y\)»"), "{text}");
+        }
+    }
+}
@@ -1,10 +1,14 @@
 use color_eyre::Result;
-use teloxide::{payloads::SendMessageSetters, requests::Requester, types::ChatId};
+use teloxide::{
+    payloads::SendMessageSetters,
+    requests::Requester,
+    types::{ChatId, InlineKeyboardMarkup},
+};
 
-use ilquentir_messages::md_message;
+use ilquentir_messages::Locale;
 use ilquentir_models::PgTransaction;
 
-use super::super::keyboard::user_daily_options;
+use super::super::{keyboard::user_daily_options, settings::Settings};
 
 use crate::bot::Bot;
 
@@ -13,12 +17,26 @@ pub async fn handle_settings_command(
     bot: &Bot,
     txn: &mut PgTransaction<'_>,
     chat_id: ChatId,
+    locale: Locale,
 ) -> Result<()> {
-    let keyboard = user_daily_options(txn, chat_id.0).await?;
+    let (text, keyboard) = settings_view(txn, chat_id.0, locale).await?;
 
-    bot.send_message(chat_id, md_message!("daily_events/settings.md"))
+    bot.send_message(chat_id, text)
         .reply_markup(keyboard)
         .await?;
 
     Ok(())
 }
+
+/// Settings text with the keyboard, opened on the first page
+#[tracing::instrument(skip(txn), err)]
+pub async fn settings_view(
+    txn: &mut PgTransaction<'_>,
+    chat_id: i64,
+    locale: Locale,
+) -> Result<(String, InlineKeyboardMarkup)> {
+    let settings = Settings::load(txn, chat_id, locale).await?;
+    let keyboard = user_daily_options(&settings, 0, locale);
+
+    Ok((settings.text(), keyboard))
+}
@@ -0,0 +1,81 @@
+use color_eyre::Result;
+use sqlx::PgPool;
+use teloxide::{
+    payloads::SendMessageSetters,
+    requests::Requester,
+    types::{MediaText, Message},
+};
+use tracing::info;
+
+use ilquentir_messages::{md_message, Locale};
+use ilquentir_models::{Dialogue, PersonalOptionEdit, PollOption};
+
+use crate::bot::Bot;
+
+use super::super::{keyboard::user_daily_options, settings::Settings};
+
+#[tracing::instrument(skip(bot, pool, msg_text), err)]
+pub async fn save_personal_option(
+    bot: Bot,
+    pool: PgPool,
+    msg: Message,
+    msg_text: MediaText,
+    dialogue: Dialogue,
+    edit: PersonalOptionEdit,
+    locale: Locale,
+) -> Result<()> {
+    let option = msg_text.text.trim();
+    let len = option.chars().count();
+
+    if len == 0 || len > PollOption::MAX_TEXT_LEN {
+        bot.send_message(
+            msg.chat.id,
+            md_message!(
+                locale,
+                "daily_events/personal_invalid.md",
+                max_len = PollOption::MAX_TEXT_LEN
+            ),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    let mut txn = pool.begin().await?;
+
+    let user_tg_id = dialogue.chat_tg_id;
+    let saved = match edit.option_id {
+        Some(option_id) => {
+            PollOption::rename_personal(&mut txn, user_tg_id, option_id, option).await?
+        }
+        None => PollOption::create_personal(&mut txn, user_tg_id, edit.poll_kind, option).await?,
+    };
+
+    let saved = if let Some(saved) = saved {
+        saved
+    } else {
+        bot.send_message(
+            msg.chat.id,
+            md_message!(locale, "daily_events/personal_duplicate.md"),
+        )
+        .await?;
+
+        return Ok(());
+    };
+    info!(user_tg_id, option_id = saved.id, "personal option saved");
+
+    dialogue.finish(&mut txn).await?;
+    let settings = Settings::load(&mut txn, user_tg_id, locale).await?;
+    let page = settings.page_of(saved.id).unwrap_or_default();
+
+    bot.send_message(
+        msg.chat.id,
+        md_message!(locale, "daily_events/personal_saved.md"),
+    )
+    .reply_markup(user_daily_options(&settings, page, locale))
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(())
+}
@@ -4,13 +4,13 @@ use color_eyre::Result;
 use sqlx::PgPool;
 use teloxide::{requests::Requester, types::ChatId};
 
-use ilquentir_messages::md_message;
+use ilquentir_messages::{md_message, Locale};
 use ilquentir_models::{Poll, PollKind, User};
 
 use crate::bot::{helpers::set_typing, Bot};
 
 #[tracing::instrument(skip(bot, pool), err)]
-pub async fn poll_answered(bot: &Bot, pool: &PgPool, poll: &Poll) -> Result<()> {
+pub async fn poll_answered(bot: &Bot, pool: &PgPool, poll: &Poll, locale: Locale) -> Result<()> {
     if User::count_answered_polls(
         &mut pool.begin().await?,
         poll.chat_tg_id,
@@ -24,7 +24,7 @@ pub async fn poll_answered(bot: &Bot, pool: &PgPool, poll: &Poll) -> Result<()>
         set_typing(bot, chat_id, Some(Duration::from_millis(200))).await?;
         bot.send_message(
             chat_id,
-            md_message!("daily_events/first_poll_answer_reaction.md"),
+            md_message!(locale, "daily_events/first_poll_answer_reaction.md"),
         )
         .await?;
     }
@@ -1,11 +1,12 @@
 use color_eyre::Result;
 use teloxide::types::InlineKeyboardMarkup;
 
-use ilquentir_models::{PgTransaction, PollCustomOptions, PollKind};
+use ilquentir_messages::{message, Locale};
+use ilquentir_models::{PgTransaction, PollKind, PollOption};
 
-use crate::bot::callbacks::buttons_row;
+use crate::bot::{callbacks::buttons_row, settings};
 
-use super::options;
+use super::{options, settings::Settings};
 
 fn format_option(option: &str, enabled: bool) -> String {
     format!("{} {option}", if enabled { ENABLED } else { DISABLED })
@@ -14,30 +15,106 @@ fn format_option(option: &str, enabled: bool) -> String {
 const ENABLED: char = '✅';
 const DISABLED: char = '⬜';
 
+/// Settings keyboard: options of the given page, page navigation and control rows
+pub(super) fn user_daily_options(
+    settings: &Settings,
+    page: usize,
+    locale: Locale,
+) -> InlineKeyboardMarkup {
+    let page = settings.page(page);
+    let pages_count = settings.pages.len();
+
+    let rendered_options = settings
+        .pages
+        .get(page)
+        .into_iter()
+        .flat_map(|current| &current.options)
+        .map(|option| {
+            buttons_row![[
+                format_option(&option.text, settings.chosen.contains(option.id)),
+                options::option_button(option.id)
+            ]]
+        });
+
+    let navigation = settings.pages.get(page).map(|current| {
+        let previous = (page + pages_count - 1) % pages_count;
+        let next = (page + 1) % pages_count;
+
+        buttons_row![
+            ["◀️", options::page_button(previous)],
+            [
+                format!("{} ({}/{pages_count})", current.title, page + 1),
+                options::PAGE_TITLE_BUTTON
+            ],
+            ["▶️", options::page_button(next)]
+        ]
+    });
+
+    InlineKeyboardMarkup::new(rendered_options.chain(navigation).chain([
+        buttons_row![
+            [
+                message!(locale, "daily_events/buttons/none.md"),
+                options::none_button(page)
+            ],
+            [
+                message!(locale, "daily_events/buttons/all.md"),
+                options::all_button(page)
+            ]
+        ],
+        buttons_row![
+            [
+                message!(locale, "daily_events/buttons/add_personal.md"),
+                options::ADD_PERSONAL_BUTTON
+            ],
+            [
+                message!(locale, "daily_events/buttons/manage_personal.md"),
+                options::MANAGE_PERSONAL_BUTTON
+            ]
+        ],
+        buttons_row![[
+            message!(locale, "daily_events/buttons/done.md"),
+            options::DONE_BUTTON
+        ]],
+        settings::keyboard::back_to_menu(locale),
+    ]))
+}
+
+/// Keyboard to rename or remove options, added by the user
 #[tracing::instrument(skip(txn), err)]
-pub async fn user_daily_options(
+pub async fn user_personal_options(
     txn: &mut PgTransaction<'_>,
     chat_id: i64,
+    locale: Locale,
 ) -> Result<InlineKeyboardMarkup> {
-    let current = PollCustomOptions::get_for_user(txn, chat_id, PollKind::DailyEvents).await?;
-    let rendered_options = options::ALL_OPTIONS
-        .values()
-        .map(|data| (data, current.options.contains(&data.value)))
-        .map(|(data, enabled)| buttons_row![[format_option(data.value(), enabled), data]])
-        .chain([
+    let personal = PollOption::get_personal(txn, chat_id, PollKind::DailyEvents).await?;
+    let rendered_options = personal
+        .into_iter()
+        .map(|option| {
             buttons_row![
-                ["🚫 Ничего из этого", options::NONE_BUTTON],
-                ["✅✅✅ Всё", options::ALL_BUTTON]
-            ],
-            buttons_row![["Сохранить выбор", options::DONE_BUTTON]],
-        ]);
+                [
+                    format!("✏️ {}", option.text),
+                    options::rename_personal_button(option.id)
+                ],
+                ["🗑", options::remove_personal_button(option.id)]
+            ]
+        })
+        .chain([buttons_row![[
+            message!(locale, "daily_events/buttons/new_personal.md"),
+            options::NEW_PERSONAL_BUTTON
+        ]]]);
 
     Ok(InlineKeyboardMarkup::new(rendered_options))
 }
 
-pub fn promo() -> InlineKeyboardMarkup {
+pub fn promo(locale: Locale) -> InlineKeyboardMarkup {
     InlineKeyboardMarkup::new([buttons_row![
-        ["Да", options::PROMO_YES_BUTTON],
-        ["Нет", options::PROMO_NO_BUTTON]
+        [
+            message!(locale, "daily_events/buttons/yes.md"),
+            options::PROMO_YES_BUTTON
+        ],
+        [
+            message!(locale, "daily_events/buttons/no.md"),
+            options::PROMO_NO_BUTTON
+        ]
     ]])
 }
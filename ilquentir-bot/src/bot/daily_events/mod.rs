@@ -1,5 +1,7 @@
 mod handlers;
 pub mod keyboard;
 pub(self) mod options;
+pub(self) mod settings;
 
 pub use handlers::*;
+pub use options::{PersonalAction, PromoAction, SettingsAction};
@@ -1,45 +1,78 @@
-use indexmap::IndexMap;
-use once_cell::sync::Lazy;
-
-use crate::bot::callbacks::{make_callback_data, CallbackButtonData, Scope};
-
-pub(super) static ALL_OPTIONS: Lazy<IndexMap<String, CallbackButtonData>> = Lazy::new(|| {
-    make_callback_data(
-        [
-            "Выход на улицу (>10 минут)",
-            "Сон >6 часов",
-            "Общение вживую",
-            "Спорт/активность",
-            "Регулярное питание",
-            "Хобби/обучение/свой проект",
-            "Позитивные личные события",
-            "Конфликты с людьми (в том числе онлайн)",
-            "Важные новости о внешнем мире",
-            "Контакты с семьёй",
-            "Солнечно на улице",
-            "Стресс на работе",
-            "Секс",
-            "Алкоголь",
-            "Вещества",
-            "Поездка за город/путешествие",
-            "Проблемы со здоровьем",
-        ]
-        .into_iter(),
-        Scope::DailyEvents,
-    )
-});
-
-pub(super) static NONE_BUTTON: Lazy<CallbackButtonData> =
-    Lazy::new(|| CallbackButtonData::new("none", Scope::DailyEvents));
-
-pub(super) static ALL_BUTTON: Lazy<CallbackButtonData> =
-    Lazy::new(|| CallbackButtonData::new("all", Scope::DailyEvents));
-
-pub(super) static DONE_BUTTON: Lazy<CallbackButtonData> =
-    Lazy::new(|| CallbackButtonData::new("done", Scope::DailyEvents));
-
-pub(super) static PROMO_YES_BUTTON: Lazy<CallbackButtonData> =
-    Lazy::new(|| CallbackButtonData::new("promo_yes", Scope::PromoDailyEvents));
-
-pub(super) static PROMO_NO_BUTTON: Lazy<CallbackButtonData> =
-    Lazy::new(|| CallbackButtonData::new("promo_no", Scope::PromoDailyEvents));
+use serde::{Deserialize, Serialize};
+
+use crate::bot::callbacks::CallbackData;
+
+/// Actions of the settings keyboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SettingsAction {
+    /// Toggles option (either from the catalog or a personal one)
+    Toggle(i64),
+    /// Opens the page
+    Page(usize),
+    /// Title of the current page, does nothing
+    PageTitle,
+    /// Disables all the options, keeping the page open
+    None(usize),
+    /// Enables all the options, keeping the page open
+    All(usize),
+    Done,
+    AddPersonal,
+    ManagePersonal,
+}
+
+/// Actions of the personal options list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PersonalAction {
+    Rename(i64),
+    Remove(i64),
+    New,
+}
+
+/// Answers to the promo message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PromoAction {
+    Yes,
+    No,
+}
+
+pub(super) fn option_button(option_id: i64) -> CallbackData {
+    CallbackData::DailyEvents(SettingsAction::Toggle(option_id))
+}
+
+pub(super) fn page_button(page: usize) -> CallbackData {
+    CallbackData::DailyEvents(SettingsAction::Page(page))
+}
+
+pub(super) const PAGE_TITLE_BUTTON: CallbackData =
+    CallbackData::DailyEvents(SettingsAction::PageTitle);
+
+pub(super) fn none_button(page: usize) -> CallbackData {
+    CallbackData::DailyEvents(SettingsAction::None(page))
+}
+
+pub(super) fn all_button(page: usize) -> CallbackData {
+    CallbackData::DailyEvents(SettingsAction::All(page))
+}
+
+pub(super) const DONE_BUTTON: CallbackData = CallbackData::DailyEvents(SettingsAction::Done);
+
+pub(super) const ADD_PERSONAL_BUTTON: CallbackData =
+    CallbackData::DailyEvents(SettingsAction::AddPersonal);
+
+pub(super) const MANAGE_PERSONAL_BUTTON: CallbackData =
+    CallbackData::DailyEvents(SettingsAction::ManagePersonal);
+
+pub(super) fn rename_personal_button(option_id: i64) -> CallbackData {
+    CallbackData::PersonalDailyEvents(PersonalAction::Rename(option_id))
+}
+
+pub(super) fn remove_personal_button(option_id: i64) -> CallbackData {
+    CallbackData::PersonalDailyEvents(PersonalAction::Remove(option_id))
+}
+
+pub(super) const NEW_PERSONAL_BUTTON: CallbackData =
+    CallbackData::PersonalDailyEvents(PersonalAction::New);
+
+pub(super) const PROMO_YES_BUTTON: CallbackData = CallbackData::PromoDailyEvents(PromoAction::Yes);
+
+pub(super) const PROMO_NO_BUTTON: CallbackData = CallbackData::PromoDailyEvents(PromoAction::No);
@@ -0,0 +1,215 @@
+use color_eyre::Result;
+
+use ilquentir_messages::{message, validate_markdown_v2, Locale, Markup};
+use ilquentir_models::{
+    PgTransaction, PollCustomOptions, PollKind, PollOption, PollOptionCategory,
+};
+
+/// Page of the settings keyboard with options of a single category
+#[derive(Debug, Clone)]
+pub(super) struct SettingsPage {
+    pub title: String,
+    pub options: Vec<PollOption>,
+}
+
+/// Everything user can choose from in daily events settings, split into pages
+#[derive(Debug, Clone)]
+pub(super) struct Settings {
+    pub pages: Vec<SettingsPage>,
+    pub chosen: PollCustomOptions,
+    pub locale: Locale,
+}
+
+impl Settings {
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn load(
+        txn: &mut PgTransaction<'_>,
+        user_tg_id: i64,
+        locale: Locale,
+    ) -> Result<Self> {
+        const POLL_KIND: PollKind = PollKind::DailyEvents;
+
+        let categories =
+            PollOptionCategory::get_all_translated(&mut *txn, POLL_KIND, locale.code()).await?;
+        let mut catalog = PollOption::get_catalog(&mut *txn, POLL_KIND).await?;
+        PollOption::translate(&mut *txn, &mut catalog, locale.code()).await?;
+        let personal = PollOption::get_personal(&mut *txn, user_tg_id, POLL_KIND).await?;
+        let mut chosen = PollCustomOptions::get_for_user(&mut *txn, user_tg_id, POLL_KIND).await?;
+        PollOption::translate(txn, &mut chosen.options, locale.code()).await?;
+
+        let mut pages = vec![];
+        for category in categories {
+            let (options, rest) = catalog
+                .into_iter()
+                .partition(|option| option.category_id == Some(category.id));
+            catalog = rest;
+
+            pages.push(SettingsPage {
+                title: category.label,
+                options,
+            });
+        }
+        // options without category or with a category, which is not loaded anymore
+        pages.push(SettingsPage {
+            title: message!(locale, "daily_events/page_other.md"),
+            options: catalog,
+        });
+        pages.push(SettingsPage {
+            title: message!(locale, "daily_events/page_personal.md"),
+            options: personal,
+        });
+        pages.retain(|page| !page.options.is_empty());
+
+        Ok(Self {
+            pages,
+            chosen,
+            locale,
+        })
+    }
+
+    pub fn options(&self) -> impl Iterator<Item = &PollOption> {
+        self.pages.iter().flat_map(|page| &page.options)
+    }
+
+    /// Index of the page, containing the option
+    pub fn page_of(&self, option_id: i64) -> Option<usize> {
+        self.pages
+            .iter()
+            .position(|page| page.options.iter().any(|option| option.id == option_id))
+    }
+
+    /// Clamps page index, so it always points to an existing page
+    pub fn page(&self, page: usize) -> usize {
+        page.min(self.pages.len().saturating_sub(1))
+    }
+
+    /// Settings message text with the summary of what's enabled,
+    /// texts of the options (including personal ones) are shown literally.
+    ///
+    /// The message is edited along with its keyboard, so it can't be split: options,
+    /// which don't fit into a single message, are only counted at the end of the list.
+    pub fn text(&self) -> String {
+        let mut shown = self.chosen.options.len();
+
+        loop {
+            let text = self.render(&self.chosen.options[..shown]);
+
+            if shown == 0 || validate_markdown_v2(&text).is_ok() {
+                return text;
+            }
+            shown -= 1;
+        }
+    }
+
+    /// Text with only the given chosen options listed
+    fn render(&self, shown: &[PollOption]) -> String {
+        let locale = self.locale;
+        let chosen = &self.chosen.options;
+
+        let mut summary = if chosen.is_empty() {
+            Markup::from(message!(locale, "daily_events/settings_summary_empty.md"))
+        } else {
+            Markup::from(message!(
+                locale,
+                "daily_events/settings_summary.md",
+                count = chosen.len(),
+                total = self.options().count(),
+            ))
+            .list(shown.iter().map(|option| option.text.as_str()))
+        };
+        let hidden = chosen.len() - shown.len();
+        if hidden > 0 {
+            summary = summary.line().text(message!(
+                locale,
+                "daily_events/settings_summary_more.md",
+                count = hidden
+            ));
+        }
+
+        Markup::new()
+            .text(message!(locale, "daily_events/settings.md"))
+            .line()
+            .line()
+            .append(summary)
+            .markdown_v2()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn option(id: i64, text: &str) -> PollOption {
+        PollOption {
+            id,
+            poll_kind: PollKind::DailyEvents,
+            owner_tg_id: Some(1),
+            category_id: None,
+            position: 0,
+            is_default: false,
+            active: true,
+            text: text.to_owned(),
+        }
+    }
+
+    fn settings(chosen: Vec<PollOption>) -> Settings {
+        Settings {
+            pages: vec![SettingsPage {
+                title: "Личное".to_owned(),
+                options: chosen.clone(),
+            }],
+            chosen: PollCustomOptions {
+                poll_kind: PollKind::DailyEvents,
+                user_tg_id: 1,
+                options: chosen,
+            },
+            locale: Locale::Ru,
+        }
+    }
+
+    #[test]
+    fn options_are_escaped() {
+        let text = settings(vec![
+            option(1, "*bold* _italic_ [link](https://example.com)"),
+            option(2, "`code` ~strike~ ||spoiler|| > #tag + - = | {} . !"),
+        ])
+        .text();
+
+        assert_eq!(validate_markdown_v2(&text), Ok(()));
+        assert!(text.contains(r"• \*bold\* \_italic\_ \[link\]\(https://example\.com\)"));
+        assert!(
+            text.contains(r"• \`code\` \~strike\~ \|\|spoiler\|\| \> \#tag \+ \- \= \| \{\} \. \!")
+        );
+    }
+    #[test]
+    fn long_options_fit_into_message() {
+        let options = (0..100)
+            .map(|id| {
+                option(
+                    id,
+                    &format!("{id:03}.").repeat(PollOption::MAX_TEXT_LEN / 4),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert!(options
+            .iter()
+            .all(|option| option.text.chars().count() == PollOption::MAX_TEXT_LEN));
+
+        for locale in [Locale::Ru, Locale::En] {
+            let text = Settings {
+                locale,
+                ..settings(options.clone())
+            }
+            .text();
+
+            assert_eq!(validate_markdown_v2(&text), Ok(()));
+            assert!(text.contains(&options[0].text.replace('.', r"\.")));
+            assert!(!text.contains(&options[99].text.replace('.', r"\.")));
+            assert!(text.contains(&message!(
+                locale,
+                "daily_events/settings_summary_more.md",
+                count = 100 - text.matches("• ").count()
+            )));
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use sqlx::PgPool;
+use teloxide::types::Message;
+use tracing::error;
+
+use ilquentir_models::{
+    AnswerNoteQuestion, Dialogue, DialogueState, JournalPrompt, MoodScaleLabelEdit,
+    OnboardingAnswers, PersonalOptionEdit,
+};
+
+/// Finds dialogue, which the message should be treated as an answer to
+#[tracing::instrument(skip(pool, msg), fields(chat_id = msg.chat.id.0))]
+pub(super) async fn get_active(pool: PgPool, msg: Message) -> Option<Dialogue> {
+    let reply_to_message_id = msg.reply_to_message().map(|reply| reply.id.0);
+
+    let result = async {
+        let mut txn = pool.begin().await?;
+        let dialogue = Dialogue::get_active(&mut txn, msg.chat.id.0, reply_to_message_id).await?;
+        txn.commit().await?;
+
+        Ok::<_, color_eyre::Report>(dialogue)
+    }
+    .await;
+
+    match result {
+        Ok(dialogue) => dialogue,
+        Err(error) => {
+            error!(%error, "failed to check for dialogues awaiting answer");
+
+            None
+        }
+    }
+}
+
+pub(super) fn personal_option_edit(dialogue: Dialogue) -> Option<PersonalOptionEdit> {
+    match dialogue.state {
+        DialogueState::PersonalOptionEdit(edit) => Some(edit),
+        _ => None,
+    }
+}
+
+pub(super) fn mood_scale_label_edit(dialogue: Dialogue) -> Option<MoodScaleLabelEdit> {
+    match dialogue.state {
+        DialogueState::MoodScaleLabelEdit(edit) => Some(edit),
+        _ => None,
+    }
+}
+
+pub(super) fn answer_note(dialogue: Dialogue) -> Option<AnswerNoteQuestion> {
+    match dialogue.state {
+        DialogueState::AnswerNote(question) => Some(question),
+        _ => None,
+    }
+}
+
+/// Onboarding, which waits for the timezone, so it can be detected by the shared location
+#[tracing::instrument(skip(pool, msg), fields(chat_id = msg.chat.id.0))]
+pub(super) async fn onboarding_location(pool: PgPool, msg: Message) -> Option<OnboardingAnswers> {
+    let result = async {
+        let mut txn = pool.begin().await?;
+        let dialogue = Dialogue::get_onboarding(&mut txn, msg.chat.id.0).await?;
+        txn.commit().await?;
+
+        Ok::<_, color_eyre::Report>(dialogue)
+    }
+    .await;
+
+    match result {
+        Ok(dialogue) => match dialogue?.state {
+            DialogueState::OnboardingTimezone(answers)
+            | DialogueState::OnboardingLocation(answers) => Some(answers),
+            _ => None,
+        },
+        Err(error) => {
+            error!(%error, "failed to check for onboarding awaiting location");
+
+            None
+        }
+    }
+}
+
+pub(super) fn journal_prompt(dialogue: Dialogue) -> Option<JournalPrompt> {
+    match dialogue.state {
+        DialogueState::JournalPrompt(prompt) => Some(prompt),
+        _ => None,
+    }
+}
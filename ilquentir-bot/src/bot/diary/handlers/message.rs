@@ -1,5 +1,5 @@
 use color_eyre::{eyre::eyre, Result};
-use ilquentir_messages::md_message;
+use ilquentir_messages::{md_message, Locale};
 use sqlx::PgPool;
 use teloxide::{
     requests::Requester,
@@ -16,6 +16,7 @@ pub async fn append_diary_entry(
     pool: PgPool,
     msg: Message,
     msg_text: MediaText,
+    locale: Locale,
 ) -> Result<()> {
     let mut txn = pool.begin().await?;
 
@@ -25,7 +26,7 @@ pub async fn append_diary_entry(
         .ok_or_else(|| eyre!("trying to handle message without known author, aborting"))?;
 
     DiaryEntry::insert(&mut txn, user_tg_id as i64, &msg_text.text).await?;
-    bot.send_message(msg.chat.id, md_message!("diary/new_entry.md"))
+    bot.send_message(msg.chat.id, md_message!(locale, "diary/new_entry.md"))
         .await?;
 
     txn.commit().await?;
@@ -1,5 +1,6 @@
 use teloxide::types::{
-    MediaKind, MediaText, Message, MessageKind, MessageWebAppData, Poll, Update, UpdateKind,
+    Location, MediaKind, MediaText, Message, MessageKind, MessageWebAppData, Poll, PollAnswer,
+    Update, UpdateKind,
 };
 
 pub(super) fn get_message_text(msg: Message) -> Option<MediaText> {
@@ -12,6 +13,10 @@ pub(super) fn get_message_text(msg: Message) -> Option<MediaText> {
     None
 }
 
+pub(super) fn get_location(msg: Message) -> Option<Location> {
+    msg.location().cloned()
+}
+
 pub(super) fn get_web_app_data(msg: Message) -> Option<MessageWebAppData> {
     match msg.kind {
         MessageKind::WebAppData(data) => Some(data),
@@ -25,3 +30,10 @@ pub(super) fn get_poll(update: Update) -> Option<Poll> {
         _ => None,
     }
 }
+
+pub(super) fn get_poll_answer(update: Update) -> Option<PollAnswer> {
+    match update.kind {
+        UpdateKind::PollAnswer(answer) => Some(answer),
+        _ => None,
+    }
+}
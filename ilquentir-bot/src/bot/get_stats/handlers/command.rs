@@ -1,7 +1,7 @@
 use color_eyre::Result;
 use teloxide::{requests::Requester, types::ChatId};
 
-use ilquentir_messages::md_message;
+use ilquentir_messages::{md_message, Locale};
 use ilquentir_models::PgTransaction;
 use ilquentir_python_graph::Plotter;
 
@@ -13,12 +13,13 @@ pub async fn handle_get_stats_command(
     plotter: &Plotter,
     txn: &mut PgTransaction<'_>,
     chat_id: ChatId,
+    locale: Locale,
 ) -> Result<()> {
     set_typing(bot, chat_id, None).await?;
 
     let graph_url = plotter.create_plot(txn, chat_id.0).await?;
 
-    let message = md_message!("stats/get_stat.md", graph_url = graph_url);
+    let message = md_message!(locale, "stats/get_stat.md", graph_url = graph_url.as_str());
     bot.send_message(chat_id, message).await?;
 
     Ok(())
@@ -2,24 +2,85 @@ use color_eyre::{eyre::eyre, Result};
 use sqlx::PgPool;
 use teloxide::types::CallbackQuery;
 
-use crate::bot::{callbacks::Scope, daily_events, Bot};
+use ilquentir_config::Config;
+use ilquentir_messages::Locale;
 
-#[tracing::instrument(skip(bot, pool), err)]
-pub async fn handle_callback(bot: Bot, pool: PgPool, callback: CallbackQuery) -> Result<()> {
-    let data = callback
+use crate::bot::{
+    callbacks::{answer_stale, CallbackData},
+    daily_events, how_was_your_day, inline_poll, language, mood_check_in, mood_scale, onboarding,
+    settings, setup_schedule, Bot,
+};
+
+#[tracing::instrument(skip(bot, pool, config), err)]
+pub async fn handle_callback(
+    bot: Bot,
+    pool: PgPool,
+    config: Config,
+    callback: CallbackQuery,
+    locale: Locale,
+) -> Result<()> {
+    let payload = callback
         .data
         .as_ref()
         .ok_or_else(|| eyre!("callback without data"))?;
-    let scope = Scope::from_payload(data).ok_or_else(|| eyre!("scope extraction failed"))?;
+    let data = if let Some(data) = CallbackData::decode(payload) {
+        data
+    } else {
+        return answer_stale(&bot, &callback, locale).await;
+    };
 
     let mut txn = pool.begin().await?;
 
-    match scope {
-        Scope::DailyEvents => {
-            daily_events::handle_callback(&bot, &mut txn, &callback, data).await?
+    match data {
+        CallbackData::DailyEvents(action) => {
+            daily_events::handle_callback(&bot, &mut txn, &callback, action, locale).await?
+        }
+        CallbackData::PromoDailyEvents(action) => {
+            daily_events::handle_promo_callback(&bot, &mut txn, &callback, action, locale).await?;
+        }
+        CallbackData::PersonalDailyEvents(action) => {
+            daily_events::handle_personal_callback(&bot, &mut txn, &callback, action, locale)
+                .await?
+        }
+        CallbackData::AnswerMode(kind) => {
+            inline_poll::handle_answer_mode_callback(&bot, &mut txn, &callback, kind, locale)
+                .await?
+        }
+        CallbackData::AnswerNote(action) => {
+            how_was_your_day::handle_skip_note_callback(&bot, &mut txn, &callback, action, locale)
+                .await?
+        }
+        CallbackData::MoodCheckInSettings(action) => {
+            mood_check_in::handle_settings_callback(&bot, &mut txn, &callback, action, locale)
+                .await?
+        }
+        CallbackData::MoodCheckIn(action) => {
+            // commits the answer by itself before replying to the user
+            mood_check_in::handle_check_in_callback(&bot, &pool, &callback, action, locale).await?
+        }
+        CallbackData::MoodScale(action) => {
+            mood_scale::handle_mood_scale_callback(&bot, &mut txn, &callback, action, locale)
+                .await?
+        }
+        CallbackData::PollAnswer(action) => {
+            // commits the answer by itself before replying to the user
+            inline_poll::handle_poll_answer_callback(&bot, &pool, &callback, action, locale).await?
+        }
+        CallbackData::Settings(action) => {
+            settings::handle_menu_callback(&bot, &mut txn, &callback, action, locale).await?
+        }
+        CallbackData::Schedule(action) => {
+            setup_schedule::handle_time_picker_callback(
+                &bot, &config, &mut txn, &callback, action, locale,
+            )
+            .await?
+        }
+        CallbackData::Language(action) => {
+            language::handle_language_callback(&bot, &mut txn, &callback, action, locale).await?
         }
-        Scope::PromoDailyEvents => {
-            daily_events::handle_promo_callback(&bot, &mut txn, &callback, data).await?;
+        CallbackData::Onboarding(action) => {
+            onboarding::handle_onboarding_callback(&bot, &mut txn, &callback, action, locale)
+                .await?
         }
     };
 
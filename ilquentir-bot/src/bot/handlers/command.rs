@@ -2,9 +2,13 @@ use color_eyre::Result;
 use sqlx::PgPool;
 use teloxide::types::Message;
 
+use ilquentir_messages::Locale;
 use ilquentir_python_graph::Plotter;
 
-use crate::bot::{daily_events, get_stats, setup_schedule, Bot, Command};
+use crate::bot::{
+    daily_events, get_stats, inline_poll, invite, journal, language, mood_check_in, mood_scale,
+    settings, setup_schedule, Bot, Command,
+};
 
 mod help;
 use help::handle_help;
@@ -22,26 +26,47 @@ pub async fn handle_command(
     plotter: Plotter,
     msg: Message,
     command: Command,
+    locale: Locale,
 ) -> Result<()> {
     let mut txn = pool.begin().await?;
     let chat_id = msg.chat.id;
 
     match command {
-        Command::Start => handle_start(&bot, &mut txn, chat_id).await?,
+        Command::Start(start_param) => {
+            handle_start(&bot, &mut txn, chat_id, msg.from(), &start_param, locale).await?
+        }
+        Command::Settings => {
+            settings::handle_settings_command(&bot, &mut txn, chat_id, locale).await?
+        }
         Command::DailyEventsSettings => {
-            daily_events::handle_settings_command(&bot, &mut txn, chat_id).await?
+            daily_events::handle_settings_command(&bot, &mut txn, chat_id, locale).await?
+        }
+        Command::GratitudeJournal => {
+            journal::handle_gratitude_journal_command(&bot, &mut txn, chat_id, locale).await?
+        }
+        Command::MoodScale => {
+            mood_scale::handle_mood_scale_command(&bot, &mut txn, chat_id, locale).await?
+        }
+        Command::MoodCheckIn => {
+            mood_check_in::handle_settings_command(&bot, &mut txn, chat_id, locale).await?
+        }
+        Command::AnswerMode => {
+            inline_poll::handle_answer_mode_command(&bot, &mut txn, chat_id, locale).await?
         }
         Command::SetupSchedule => {
-            setup_schedule::handle_setup_schedule_command(&bot, chat_id).await?
+            setup_schedule::handle_setup_schedule_command(&bot, &mut txn, chat_id, locale).await?
         }
 
         Command::GetStat => {
-            get_stats::handle_get_stats_command(&bot, &plotter, &mut txn, chat_id).await?
+            get_stats::handle_get_stats_command(&bot, &plotter, &mut txn, chat_id, locale).await?
         }
+        Command::Invite => invite::handle_invite_command(&bot, &mut txn, chat_id, locale).await?,
+
+        Command::Language => language::handle_language_command(&bot, chat_id, locale).await?,
 
-        Command::Help => handle_help(&bot, chat_id).await?,
+        Command::Help => handle_help(&bot, chat_id, locale).await?,
 
-        Command::Stop => handle_stop(&bot, &mut txn, chat_id).await?,
+        Command::Stop => handle_stop(&bot, &mut txn, chat_id, locale).await?,
     }
     txn.commit().await?;
 
@@ -1,20 +1,27 @@
 use std::time::Duration;
 
 use color_eyre::Result;
-use teloxide::{requests::Requester, types::ChatId};
-use tracing::info;
+use teloxide::{
+    requests::Requester,
+    types::{ChatId, User as TgUser},
+};
+use tracing::{debug, info};
 
-use ilquentir_messages::{md, md_message};
-use ilquentir_models::{PgTransaction, Poll, User};
+use ilquentir_messages::{md_message, Locale};
+use ilquentir_models::{Dialogue, PgTransaction, User};
 
-use crate::bot::{
-    helpers::{send_poll, set_typing},
-    Bot,
-};
+use crate::bot::{handlers::save_profile, helpers::set_typing, invite, onboarding, Bot};
 
 #[tracing::instrument(skip_all, fields(chat_id=chat_id.0), err)]
-pub async fn handle_start(bot: &Bot, txn: &mut PgTransaction<'_>, chat_id: ChatId) -> Result<()> {
-    info!(chat_id = chat_id.0, "processing Start command");
+pub async fn handle_start(
+    bot: &Bot,
+    txn: &mut PgTransaction<'_>,
+    chat_id: ChatId,
+    from: Option<&TgUser>,
+    start_param: &str,
+    locale: Locale,
+) -> Result<()> {
+    info!(chat_id = chat_id.0, start_param, "processing Start command");
 
     if let Some(user) = User::get_user_by_id(&mut *txn, chat_id.0).await? {
         info!(
@@ -23,12 +30,18 @@ pub async fn handle_start(bot: &Bot, txn: &mut PgTransaction<'_>, chat_id: ChatI
             "user already exists and active :)"
         );
 
-        bot.send_message(chat_id, md!("И снова здравствуй :)"))
+        bot.send_message(chat_id, md_message!(locale, "onboarding/welcome_back.md"))
             .await?;
 
+        if let Some(dialogue) = Dialogue::get_onboarding(&mut *txn, chat_id.0).await? {
+            onboarding::send_current_step(bot, txn, chat_id, dialogue.state, locale).await?;
+        }
+
         return Ok(());
     }
 
+    // only brand new users are attributed to the deep link they came with
+    let is_new = !User::exists(&mut *txn, chat_id.0).await?;
     let user = User::activate(&mut *txn, chat_id.0).await?;
     info!(
         chat_id = chat_id.0,
@@ -36,42 +49,33 @@ pub async fn handle_start(bot: &Bot, txn: &mut PgTransaction<'_>, chat_id: ChatI
         "(re?) activated user"
     );
 
-    let polls = Poll::create_for_user(&mut *txn, &user).await?;
+    // profile middleware runs before the user is created, so the first one is saved here
+    if let Some(from) = from {
+        save_profile(&mut *txn, from).await?;
+    }
+
+    if let Some(source) = invite::parse_start_param(chat_id.0, start_param) {
+        if is_new {
+            let saved = source.save(&mut *txn).await?;
+            debug!(chat_id = chat_id.0, ?source, saved, "saved user source");
+        }
+    }
 
     info!(
         chat_id = chat_id.0,
         user_tg_id = user.tg_id,
         "sending welcome sequence to user"
     );
-    bot.send_message(chat_id, md_message!("onboarding/step_1.md"))
+    bot.send_message(chat_id, md_message!(locale, "onboarding/step_1.md"))
         .await?;
     set_typing(bot, chat_id, Some(Duration::from_millis(500))).await?;
 
-    bot.send_message(chat_id, md_message!("onboarding/step_2.md"))
+    bot.send_message(chat_id, md_message!(locale, "onboarding/step_2.md"))
         .await?;
     set_typing(bot, chat_id, Some(Duration::from_millis(1000))).await?;
 
-    bot.send_message(chat_id, md_message!("onboarding/step_3.md"))
-        .await?;
-    set_typing(bot, chat_id, Some(Duration::from_millis(1000))).await?;
-
-    info!(
-        chat_id = chat_id.0,
-        user_tg_id = user.tg_id,
-        "sent welcome sequence, sending {} initial polls",
-        polls.len(),
-    );
-    set_typing(bot, chat_id, Some(Duration::from_millis(500))).await?;
-
-    for poll in polls {
-        send_poll(bot, &mut *txn, poll).await?;
-    }
-
-    info!(
-        chat_id = chat_id.0,
-        user_tg_id = user.tg_id,
-        "initial polls sent"
-    );
+    // polls are scheduled only when the onboarding is finished
+    onboarding::start_onboarding(bot, txn, chat_id, locale).await?;
 
     Ok(())
 }
@@ -1,5 +1,11 @@
 mod ban_update;
 pub use ban_update::handle_ban;
 
+mod poll_answer;
+pub use poll_answer::handle_poll_answer;
+
 mod poll_update;
-pub use poll_update::handle_poll_update;
+pub use poll_update::{handle_poll_update, poll_answered};
+
+mod profile;
+pub use profile::{refresh_profile, save_profile};
@@ -0,0 +1,45 @@
+use color_eyre::Result;
+use sqlx::PgPool;
+use teloxide::types::PollAnswer as TgPollAnswer;
+use tracing::info;
+
+use ilquentir_messages::Locale;
+use ilquentir_models::PollAnswer;
+
+use crate::bot::Bot;
+
+use super::poll_update::selection_saved;
+
+#[tracing::instrument(skip(bot, pool), err)]
+pub async fn handle_poll_answer(
+    bot: Bot,
+    pool: PgPool,
+    tg_answer: TgPollAnswer,
+    locale: Locale,
+) -> Result<()> {
+    let user_tg_id = tg_answer.user.id.0;
+
+    info!(
+        user_tg_id,
+        poll_tg_id = tg_answer.poll_id,
+        retracted = tg_answer.option_ids.is_empty(),
+        "got PollAnswer update, saving data"
+    );
+
+    let mut txn = pool.begin().await?;
+
+    let saved = PollAnswer::save_poll_answer(&mut txn, &tg_answer).await?;
+    info!(
+        user_tg_id,
+        poll_tg_id = tg_answer.poll_id,
+        poll_id = saved.poll.id,
+        "data saved, sending the reply"
+    );
+
+    // let's finish the txn before lots of communication with external APIs
+    txn.commit().await?;
+
+    selection_saved(&bot, &pool, &saved, locale).await?;
+
+    Ok(())
+}
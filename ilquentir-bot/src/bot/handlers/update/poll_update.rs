@@ -4,12 +4,12 @@ use teloxide::{
     requests::Requester,
     types::{ChatId, Poll as TgPoll, Update},
 };
-use tracing::info;
+use tracing::{info, warn};
 
-use ilquentir_messages::md;
-use ilquentir_models::{PollAnswer, PollKind};
+use ilquentir_messages::{md_message, Locale, Markup};
+use ilquentir_models::{Poll, PollAnswer, PollKind, SavedSelection};
 
-use crate::bot::{daily_events, how_was_your_day, Bot};
+use crate::bot::{context::UserContext, daily_events, how_was_your_day, language, Bot};
 
 #[tracing::instrument(skip(bot, pool), err)]
 pub async fn handle_poll_update(
@@ -28,6 +28,21 @@ pub async fn handle_poll_update(
         "start processing update"
     );
 
+    if tg_poll.is_closed {
+        info!(poll_tg_id = tg_poll.id, "poll is closed, nothing to save");
+
+        return Ok(());
+    }
+
+    if !tg_poll.is_anonymous {
+        info!(
+            poll_tg_id = tg_poll.id,
+            "votes in non-anonymous polls are saved from PollAnswer updates"
+        );
+
+        return Ok(());
+    }
+
     info!(
         user_tg_id,
         chat_id = chat_id_trace,
@@ -37,38 +52,81 @@ pub async fn handle_poll_update(
 
     let mut txn = pool.begin().await?;
 
-    let poll = PollAnswer::save_answer(&mut txn, &tg_poll).await?;
+    let saved = PollAnswer::save_answer(&mut txn, &tg_poll).await?;
+    // anonymous poll has no user, the reply goes to the poll's chat
+    let locale = language::locale_of(&mut txn, saved.poll.chat_tg_id).await?;
     info!(
         user_tg_id,
         chat_id = chat_id_trace,
-        poll_tg_id = poll.tg_id,
-        poll_id = poll.id,
+        poll_tg_id = tg_poll.id,
+        poll_id = saved.poll.id,
         "data saved, sending the reply"
     );
 
     // let's finish the txn before lots of communication with external APIs
     txn.commit().await?;
 
+    selection_saved(&bot, &pool, &saved, locale).await?;
+
+    info!(
+        user_tg_id,
+        chat_id = chat_id_trace,
+        poll_tg_id = tg_poll.id,
+        poll_id = saved.poll.id,
+        "reply sent"
+    );
+
+    Ok(())
+}
+
+/// Reacts to the answer, if user answered the poll for the first time
+#[tracing::instrument(skip(bot, pool), err)]
+pub(super) async fn selection_saved(
+    bot: &Bot,
+    pool: &PgPool,
+    saved: &SavedSelection,
+    locale: Locale,
+) -> Result<()> {
+    if !saved.first_answer() {
+        info!(
+            poll_id = saved.poll.id,
+            is_answered = saved.is_answered,
+            "poll was answered before or vote was retracted, no reaction needed"
+        );
+
+        return Ok(());
+    }
+
+    poll_answered(bot, pool, &saved.poll, locale).await
+}
+
+/// Sends reply to the answered poll
+#[tracing::instrument(skip(bot, pool), err)]
+pub async fn poll_answered(bot: &Bot, pool: &PgPool, poll: &Poll, locale: Locale) -> Result<()> {
     let chat_id = ChatId(poll.chat_tg_id);
 
     // send generic response
-    info!(user_tg_id, chat_id = chat_id.0, "sending message");
+    info!(chat_id = chat_id.0, "sending message");
     match poll.kind {
-        PollKind::HowWasYourDay => how_was_your_day::poll_answered(&bot, &pool, &poll).await?,
+        PollKind::HowWasYourDay => how_was_your_day::poll_answered(bot, pool, poll, locale).await?,
         PollKind::FoodAllergy => {
-            bot.send_message(chat_id.to_string(), md!("Meow :)"))
+            bot.send_message(chat_id.to_string(), Markup::from("Meow :)").markdown_v2())
                 .await?;
         }
-        PollKind::DailyEvents => daily_events::poll_answered(&bot, &pool, &poll).await?,
-    };
+        PollKind::DailyEvents => daily_events::poll_answered(bot, pool, poll, locale).await?,
+        PollKind::MoodCheckIn => {
+            let context = UserContext::load_or_default(pool, chat_id.0, locale).await;
 
-    info!(
-        user_tg_id,
-        chat_id = chat_id_trace,
-        poll_tg_id = poll.tg_id,
-        poll_id = poll.id,
-        "reply sent, commiting txn"
-    );
+            bot.send_message(
+                chat_id.to_string(),
+                md_message!(context, "voted_poll_reaction/generic_response.md"),
+            )
+            .await?;
+        }
+        PollKind::GratitudeJournal => {
+            warn!(poll_id = poll.id, "got Poll update for a prompt-style poll");
+        }
+    };
 
     Ok(())
 }
@@ -0,0 +1,53 @@
+use color_eyre::Result;
+use sqlx::PgPool;
+use teloxide::types::{Update, User as TgUser};
+use tracing::warn;
+
+use ilquentir_models::{PgTransaction, UserProfile};
+
+/// Middleware, which refreshes Telegram profile of the user, who sent the update.
+///
+/// Never stops the update from being handled, errors are only logged.
+pub async fn refresh_profile(pool: PgPool, update: Update) {
+    let user = if let Some(user) = update.user() {
+        user
+    } else {
+        return;
+    };
+
+    let result = async {
+        let mut txn = pool.begin().await?;
+        save_profile(&mut txn, user).await?;
+        txn.commit().await?;
+
+        Ok::<_, color_eyre::Report>(())
+    }
+    .await;
+
+    if let Err(error) = result {
+        warn!(
+            user_tg_id = user.id.0,
+            ?error,
+            "failed to refresh user profile"
+        );
+    }
+}
+
+/// Saves the profile, unless the user has never started the bot or is a bot itself.
+///
+/// Returns whether the profile was saved.
+#[tracing::instrument(skip_all, fields(user_tg_id = user.id.0), err)]
+pub async fn save_profile(txn: &mut PgTransaction<'_>, user: &TgUser) -> Result<bool> {
+    if user.is_bot {
+        return Ok(false);
+    }
+
+    UserProfile::refresh(
+        txn,
+        user.id.0 as i64,
+        user.username.as_deref(),
+        &user.first_name,
+        user.language_code.as_deref(),
+    )
+    .await
+}
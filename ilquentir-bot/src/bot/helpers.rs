@@ -1,7 +1,13 @@
 use std::time::Duration;
 
-use color_eyre::{eyre::ensure, Result};
-use ilquentir_models::{PgTransaction, Poll};
+use color_eyre::{
+    eyre::{ensure, eyre},
+    Result,
+};
+use ilquentir_messages::{md_message, split_markdown_v2, MAX_MESSAGE_LEN};
+use ilquentir_models::{
+    AnswerMode, Dialogue, DialogueState, JournalPrompt, PgTransaction, Poll, PollPart, PollSettings,
+};
 use teloxide::{
     payloads::SendPollSetters,
     requests::Requester,
@@ -9,7 +15,7 @@ use teloxide::{
 };
 use tracing::{info, warn};
 
-use super::Bot;
+use super::{inline_poll, language, mood_check_in, Bot};
 
 /// Telegram only allows <= 10 options per poll
 const TELEGRAM_POLL_OPTIONS_LIMIT: usize = 10;
@@ -30,11 +36,105 @@ pub async fn set_typing(
     Ok(())
 }
 
+/// Sends `MarkdownV2` text, which may exceed the message length limit, e.g. a report built from user data.
+///
+/// Long text is split into several messages, keeping the formatting of every part.
+#[tracing::instrument(skip(bot, text), fields(text_len = text.len()), err)]
+pub async fn send_long_message(
+    bot: &Bot,
+    chat_id: impl Into<Recipient> + Clone + std::fmt::Debug,
+    text: &str,
+) -> Result<Vec<Message>> {
+    let mut sent_messages = vec![];
+
+    for part in split_markdown_v2(text, MAX_MESSAGE_LEN)? {
+        sent_messages.push(bot.send_message(chat_id.clone(), part).await?);
+    }
+
+    Ok(sent_messages)
+}
+
+/// Replaces the text of the message with `MarkdownV2` text, which may exceed the message length limit,
+/// e.g. an answer built from user data.
+///
+/// The message gets the first part of the text, the rest is sent as new messages after it.
+#[tracing::instrument(skip(bot, text), fields(text_len = text.len()), err)]
+pub async fn edit_long_message(
+    bot: &Bot,
+    chat_id: impl Into<Recipient> + Clone + std::fmt::Debug,
+    message_id: MessageId,
+    text: &str,
+) -> Result<Vec<Message>> {
+    let mut parts = split_markdown_v2(text, MAX_MESSAGE_LEN)?.into_iter();
+    let mut messages = vec![];
+
+    if let Some(first) = parts.next() {
+        messages.push(
+            bot.edit_message_text(chat_id.clone(), message_id, first)
+                .await?,
+        );
+    }
+    for part in parts {
+        messages.push(bot.send_message(chat_id.clone(), part).await?);
+    }
+
+    Ok(messages)
+}
+
 #[tracing::instrument(skip(bot, txn), err)]
 pub async fn send_poll(bot: &Bot, txn: &mut PgTransaction<'_>, poll: Poll) -> Result<Vec<Message>> {
     info!(poll_id = poll.id, "sending poll");
+    let locale = language::locale_of(&mut *txn, poll.chat_tg_id).await?;
 
-    let options = poll.kind.options(&mut *txn, poll.chat_tg_id).await?;
+    if poll.kind.is_prompt() {
+        let message = bot
+            .send_message(
+                poll.chat_tg_id.to_string(),
+                md_message!(
+                    locale,
+                    "journal/prompt.md",
+                    question = poll.kind.question(locale)
+                ),
+            )
+            .await?;
+
+        info!(poll_id = poll.id, "prompt sent");
+
+        let poll = poll
+            .published_to_tg(&mut *txn, &[message.clone()], &[])
+            .await?;
+        if let Some(poll_id) = poll.id {
+            // the next text message is the answer
+            Dialogue::start(
+                &mut *txn,
+                poll.chat_tg_id,
+                DialogueState::JournalPrompt(JournalPrompt { poll_id }),
+                message.id.0,
+            )
+            .await?;
+        }
+
+        return Ok(vec![message]);
+    }
+
+    let options = poll
+        .kind
+        .options(&mut *txn, poll.chat_tg_id, locale)
+        .await?;
+
+    if poll.kind.is_check_in() {
+        let message = mood_check_in::send_check_in(bot, &mut *txn, poll, options, locale).await?;
+
+        return Ok(vec![message]);
+    }
+
+    let answer_mode = PollSettings::get_answer_mode(&mut *txn, poll.chat_tg_id, poll.kind).await?;
+    if answer_mode == AnswerMode::InlineKeyboard {
+        let message =
+            inline_poll::send_keyboard_poll(bot, &mut *txn, poll, options, locale).await?;
+
+        return Ok(vec![message]);
+    }
 
     let mut chunk_size = TELEGRAM_POLL_OPTIONS_LIMIT;
     while options.len() % chunk_size == 1 && chunk_size > 1 {
@@ -47,35 +147,64 @@ pub async fn send_poll(bot: &Bot, txn: &mut PgTransaction<'_>, poll: Poll) -> Re
         sent_messages.push(
             bot.send_poll(
                 poll.chat_tg_id.to_string(),
-                poll.kind.question(),
-                options_chunk.iter().cloned(),
+                poll.kind.question(locale),
+                options_chunk.iter().map(|option| option.text.clone()),
             )
             .allows_multiple_answers(poll.kind.allows_multiple_answers())
+            // votes in non-anonymous polls are delivered as PollAnswer updates
+            .is_anonymous(false)
             .await?,
         );
     }
 
     info!(poll_id = poll.id, "poll sent");
 
-    poll.published_to_tg(&mut *txn, &sent_messages).await?;
+    poll.published_to_tg(&mut *txn, &sent_messages, &options)
+        .await?;
 
     Ok(sent_messages)
 }
 
+/// Removes all parts of an overdue poll from the chat.
+///
+/// Bots can't delete messages older than 48 hours,
+/// so native polls are closed and keyboards are removed instead when deletion fails.
 #[tracing::instrument(skip(bot, txn), err)]
 pub async fn overdue_poll(bot: &Bot, txn: &mut PgTransaction<'_>, poll: Poll) -> Result<()> {
-    ensure!(poll.tg_id.is_some(), "trying to delete unpublished poll");
+    ensure!(poll.published, "trying to delete unpublished poll");
+    let poll_id = poll
+        .id
+        .ok_or_else(|| eyre!("trying to overdue unsaved poll"))?;
+
+    for part in PollPart::get_for_poll(&mut *txn, poll_id).await? {
+        let message_id = if let Some(message_id) = part.tg_message_id {
+            MessageId(message_id)
+        } else {
+            info!(poll_id, "poll part with unknown message id is overdue");
+
+            continue;
+        };
 
-    if let Some(message_id) = poll.tg_message_id {
         let response = bot
-            .delete_message(poll.chat_tg_id.to_string(), MessageId(message_id))
+            .delete_message(poll.chat_tg_id.to_string(), message_id)
             .await;
 
         if let Err(err) = response {
-            warn!(%err, "failed to delete obsolete message");
+            warn!(%err, poll_id, "failed to delete obsolete message");
+
+            if part.tg_id.is_some() {
+                if let Err(err) = bot.stop_poll(poll.chat_tg_id.to_string(), message_id).await {
+                    warn!(%err, poll_id, "failed to close obsolete poll");
+                }
+            } else if !part.options.is_empty() {
+                if let Err(err) = bot
+                    .edit_message_reply_markup(poll.chat_tg_id.to_string(), message_id)
+                    .await
+                {
+                    warn!(%err, poll_id, "failed to remove keyboard of obsolete poll");
+                }
+            }
         }
-    } else {
-        info!(poll = poll.id, "post with unknown message id is overdue")
     }
     poll.set_overdue(txn).await?;
 
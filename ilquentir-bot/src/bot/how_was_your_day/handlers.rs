@@ -3,14 +3,25 @@ use std::time::Duration;
 use color_eyre::Result;
 use sqlx::PgPool;
 use teloxide::{payloads::SendMessageSetters, requests::Requester};
+use tracing::info;
 
-use ilquentir_messages::md_message;
-use ilquentir_models::{Poll, PollKind, User};
+use ilquentir_messages::{md_message, Locale};
+use ilquentir_models::{
+    AnswerNoteQuestion, Dialogue, DialogueState, Poll, PollAnswerNote, PollKind, User,
+};
 
-use crate::bot::{daily_events, helpers::set_typing, Bot};
+use crate::bot::{context::UserContext, daily_events, helpers::set_typing, Bot};
+
+use super::keyboard;
+
+mod callback;
+pub use callback::handle_skip_note_callback;
+
+mod message;
+pub use message::save_note;
 
 #[tracing::instrument(skip(bot, pool), err)]
-pub async fn poll_answered(bot: &Bot, pool: &PgPool, poll: &Poll) -> Result<()> {
+pub async fn poll_answered(bot: &Bot, pool: &PgPool, poll: &Poll, locale: Locale) -> Result<()> {
     let chat_id = poll.chat_tg_id;
     let kind = poll.kind;
 
@@ -20,38 +31,91 @@ pub async fn poll_answered(bot: &Bot, pool: &PgPool, poll: &Poll) -> Result<()>
         // onboarding
         bot.send_message(
             chat_id.to_string(),
-            md_message!("onboarding/step_4_after_response.md"),
+            md_message!(locale, "onboarding/step_4_after_response.md"),
         )
         .await?;
-        set_typing(bot, chat_id.to_string(), Some(Duration::from_millis(1000))).await?;
 
-        bot.send_message(
-            chat_id.to_string(),
-            md_message!("onboarding/step_5_after_response.md"),
-        )
-        .reply_markup(daily_events::keyboard::promo())
-        .await?;
+        // daily events could be enabled during the onboarding already
+        if Poll::get_scheduled_for_user(&mut pool.begin().await?, chat_id, PollKind::DailyEvents)
+            .await?
+            .is_empty()
+        {
+            set_typing(bot, chat_id.to_string(), Some(Duration::from_millis(1000))).await?;
+
+            bot.send_message(
+                chat_id.to_string(),
+                md_message!(locale, "onboarding/step_5_after_response.md"),
+            )
+            .reply_markup(daily_events::keyboard::promo(locale))
+            .await?;
+        }
 
         return Ok(());
     }
 
     // main flow
+    let context = UserContext::load_or_default(pool, chat_id, locale).await;
     bot.send_message(
         chat_id.to_string(),
-        md_message!("voted_poll_reaction/generic_response.md"),
+        md_message!(context, "voted_poll_reaction/generic_response.md"),
     )
     .await?;
 
+    ask_for_note(bot, pool, poll, locale).await?;
+
     if Poll::get_scheduled_for_user(&mut pool.begin().await?, chat_id, PollKind::DailyEvents)
         .await?
         .is_empty()
     {
         set_typing(bot, chat_id.to_string(), Some(Duration::from_millis(2500))).await?;
 
-        bot.send_message(chat_id.to_string(), md_message!("promo/daily_events.md"))
-            .reply_markup(daily_events::keyboard::promo())
-            .await?;
+        bot.send_message(
+            chat_id.to_string(),
+            md_message!(locale, "promo/daily_events.md"),
+        )
+        .reply_markup(daily_events::keyboard::promo(locale))
+        .await?;
     }
 
     Ok(())
 }
+
+/// Asks user to optionally explain, why the day was like that
+#[tracing::instrument(skip(bot, pool), err)]
+async fn ask_for_note(bot: &Bot, pool: &PgPool, poll: &Poll, locale: Locale) -> Result<()> {
+    let poll_id = if let Some(poll_id) = poll.id {
+        poll_id
+    } else {
+        info!("poll is not saved, not asking for a note");
+
+        return Ok(());
+    };
+
+    set_typing(
+        bot,
+        poll.chat_tg_id.to_string(),
+        Some(Duration::from_millis(1000)),
+    )
+    .await?;
+
+    let message = bot
+        .send_message(
+            poll.chat_tg_id.to_string(),
+            md_message!(locale, "how_was_your_day/why.md"),
+        )
+        .reply_markup(keyboard::skip_note(poll_id, locale))
+        .await?;
+
+    let mut txn = pool.begin().await?;
+    PollAnswerNote::create(&mut txn, poll_id, message.id.0).await?;
+    Dialogue::start(
+        &mut txn,
+        poll.chat_tg_id,
+        DialogueState::AnswerNote(AnswerNoteQuestion { poll_id }),
+        message.id.0,
+    )
+    .await?;
+    txn.commit().await?;
+
+    Ok(())
+}
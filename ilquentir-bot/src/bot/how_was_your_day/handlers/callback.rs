@@ -0,0 +1,46 @@
+use color_eyre::{eyre::eyre, Result};
+use teloxide::{requests::Requester, types::CallbackQuery};
+use tracing::{info, warn};
+
+use ilquentir_messages::{md_message, Locale};
+use ilquentir_models::{
+    AnswerNoteQuestion, Dialogue, DialogueState, PgTransaction, PollAnswerNote,
+};
+
+use crate::bot::Bot;
+
+use super::super::keyboard::NoteAction;
+
+#[tracing::instrument(skip(bot, txn), err)]
+pub async fn handle_skip_note_callback(
+    bot: &Bot,
+    txn: &mut PgTransaction<'_>,
+    callback: &CallbackQuery,
+    action: NoteAction,
+    locale: Locale,
+) -> Result<()> {
+    let message = callback
+        .message
+        .as_ref()
+        .ok_or_else(|| eyre!("payload with no message"))?;
+    let NoteAction::Skip { poll_id } = action;
+
+    if PollAnswerNote::skip(&mut *txn, message.chat.id.0, message.id.0).await? {
+        let state = DialogueState::AnswerNote(AnswerNoteQuestion { poll_id });
+        Dialogue::cancel(&mut *txn, message.chat.id.0, &state).await?;
+        info!(chat_id = message.chat.id.0, poll_id, "note skipped");
+
+        bot.edit_message_text(
+            message.chat.id,
+            message.id,
+            md_message!(locale, "how_was_your_day/note_skipped.md"),
+        )
+        .await?;
+    } else {
+        warn!(poll_id, "note is already answered or skipped");
+    }
+
+    bot.answer_callback_query(&callback.id).await?;
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use color_eyre::Result;
+use sqlx::PgPool;
+use teloxide::{
+    requests::Requester,
+    types::{MediaText, Message},
+};
+use tracing::info;
+
+use ilquentir_messages::{md_message, Locale};
+use ilquentir_models::{AnswerNoteQuestion, Dialogue, PollAnswerNote};
+
+use crate::bot::Bot;
+
+#[tracing::instrument(skip(bot, pool, msg_text), err)]
+pub async fn save_note(
+    bot: Bot,
+    pool: PgPool,
+    msg: Message,
+    msg_text: MediaText,
+    dialogue: Dialogue,
+    question: AnswerNoteQuestion,
+    locale: Locale,
+) -> Result<()> {
+    let mut txn = pool.begin().await?;
+
+    dialogue.finish(&mut txn).await?;
+    let note = PollAnswerNote::save_text(&mut txn, question.poll_id, &msg_text.text).await?;
+    info!(
+        poll_id = note.poll_id,
+        chat_id = msg.chat.id.0,
+        "saved note for the poll answer"
+    );
+
+    bot.send_message(
+        msg.chat.id,
+        md_message!(locale, "how_was_your_day/note_saved.md"),
+    )
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use teloxide::types::InlineKeyboardMarkup;
+
+use ilquentir_messages::{message, Locale};
+
+use crate::bot::callbacks::{buttons_row, CallbackData};
+
+/// Actions with the note to the poll answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NoteAction {
+    Skip { poll_id: i64 },
+}
+
+pub(super) fn skip_note_button(poll_id: i64) -> CallbackData {
+    CallbackData::AnswerNote(NoteAction::Skip { poll_id })
+}
+
+pub fn skip_note(poll_id: i64, locale: Locale) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new([buttons_row![[
+        message!(locale, "how_was_your_day/buttons/skip_note.md"),
+        skip_note_button(poll_id)
+    ]]])
+}
@@ -1,2 +1,3 @@
 mod handlers;
 pub(super) use handlers::*;
+pub mod keyboard;
@@ -0,0 +1,200 @@
+use color_eyre::{eyre::eyre, Result};
+use sqlx::PgPool;
+use teloxide::{
+    payloads::{AnswerCallbackQuerySetters, EditMessageReplyMarkupSetters},
+    requests::Requester,
+    types::CallbackQuery,
+};
+use tracing::info;
+
+use ilquentir_messages::{message, Locale, Markup};
+use ilquentir_models::{
+    AnswerMode, PgTransaction, Poll, PollAnswer, PollKind, PollPart, PollSettings,
+};
+
+use crate::bot::{
+    callbacks::answer_stale, handlers::poll_answered, helpers::edit_long_message, Bot,
+};
+
+use super::super::keyboard::{
+    answer_mode_settings, poll_options, PollAnswerAction, CONFIGURABLE_KINDS,
+};
+
+#[tracing::instrument(skip(bot, pool), err)]
+pub async fn handle_poll_answer_callback(
+    bot: &Bot,
+    pool: &PgPool,
+    callback: &CallbackQuery,
+    action: PollAnswerAction,
+    locale: Locale,
+) -> Result<()> {
+    let user_tg_id = callback.from.id.0 as i64;
+    let message = callback
+        .message
+        .as_ref()
+        .ok_or_else(|| eyre!("payload with no message"))?;
+
+    let mut txn = pool.begin().await?;
+
+    let part = PollPart::get_by_message(&mut txn, message.chat.id.0, message.id.0)
+        .await?
+        .ok_or_else(|| eyre!("got answer for unknown poll message"))?;
+    let poll = Poll::get_by_id(&mut txn, part.poll_id).await?;
+    let poll_id = part.poll_id;
+    let allows_multiple_answers = poll.kind.allows_multiple_answers();
+
+    let option_idx = match action {
+        PollAnswerAction::Option {
+            poll_id: action_poll_id,
+            option_idx,
+        } if action_poll_id == poll_id && option_idx < part.options.len() => option_idx as i32,
+        PollAnswerAction::Done {
+            poll_id: action_poll_id,
+        } if action_poll_id == poll_id && allows_multiple_answers => {
+            let selected = PollAnswer::get_selected(&mut txn, &part, user_tg_id).await?;
+
+            if selected.is_empty() {
+                bot.answer_callback_query(&callback.id)
+                    .text(message!(locale, "inline_poll/choose_at_least_one.md"))
+                    .await?;
+
+                return Ok(());
+            }
+
+            txn.commit().await?;
+
+            let text = answered_text(poll.kind, &part.options, &selected, locale);
+            edit_long_message(bot, message.chat.id, message.id, &text).await?;
+            bot.answer_callback_query(&callback.id).await?;
+
+            return poll_answered(bot, pool, &poll, locale).await;
+        }
+        _ => return answer_stale(bot, callback, locale).await,
+    };
+
+    if allows_multiple_answers {
+        let mut selected = PollAnswer::get_selected(&mut txn, &part, user_tg_id).await?;
+
+        if let Some(position) = selected.iter().position(|&idx| idx == option_idx) {
+            selected.remove(position);
+        } else {
+            selected.push(option_idx);
+        }
+
+        PollAnswer::save_keyboard_answer(&mut txn, poll, part.clone(), user_tg_id, &selected)
+            .await?;
+        txn.commit().await?;
+
+        bot.edit_message_reply_markup(message.chat.id, message.id)
+            .reply_markup(poll_options(
+                poll_id,
+                &part.options,
+                &selected,
+                true,
+                locale,
+            ))
+            .await?;
+        bot.answer_callback_query(&callback.id).await?;
+
+        return Ok(());
+    }
+
+    let saved =
+        PollAnswer::save_keyboard_answer(&mut txn, poll, part.clone(), user_tg_id, &[option_idx])
+            .await?;
+    txn.commit().await?;
+
+    let text = answered_text(saved.poll.kind, &part.options, &[option_idx], locale);
+    edit_long_message(bot, message.chat.id, message.id, &text).await?;
+    bot.answer_callback_query(&callback.id).await?;
+
+    if saved.first_answer() {
+        poll_answered(bot, pool, &saved.poll, locale).await?;
+    } else {
+        info!(poll_id, "poll was answered before, no reaction needed");
+    }
+
+    Ok(())
+}
+
+/// Poll question with the chosen options, which may be written by the user,
+/// so they are shown literally and the text may exceed a single message
+fn answered_text(kind: PollKind, options: &[String], selected: &[i32], locale: Locale) -> String {
+    let answer = selected
+        .iter()
+        .filter_map(|&idx| options.get(idx as usize))
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    Markup::new()
+        .text(message!(
+            locale,
+            "inline_poll/answered.md",
+            question = kind.question(locale),
+            answer = answer
+        ))
+        .markdown_v2()
+}
+
+#[tracing::instrument(skip(bot, txn), err)]
+pub async fn handle_answer_mode_callback(
+    bot: &Bot,
+    txn: &mut PgTransaction<'_>,
+    callback: &CallbackQuery,
+    kind: PollKind,
+    locale: Locale,
+) -> Result<()> {
+    let user_tg_id = callback.from.id.0 as i64;
+    let message = callback
+        .message
+        .as_ref()
+        .ok_or_else(|| eyre!("payload with no message"))?;
+
+    if !CONFIGURABLE_KINDS.contains(&kind) {
+        return answer_stale(bot, callback, locale).await;
+    }
+
+    let new_mode = match PollSettings::get_answer_mode(&mut *txn, user_tg_id, kind).await? {
+        AnswerMode::NativePoll => AnswerMode::InlineKeyboard,
+        AnswerMode::InlineKeyboard => AnswerMode::NativePoll,
+    };
+
+    PollSettings::set_answer_mode(&mut *txn, user_tg_id, kind, new_mode).await?;
+    info!(user_tg_id, %kind, %new_mode, "answer mode changed");
+
+    let keyboard = answer_mode_settings(txn, user_tg_id, locale).await?;
+    bot.edit_message_reply_markup(message.chat.id, message.id)
+        .reply_markup(keyboard)
+        .await?;
+
+    bot.answer_callback_query(&callback.id).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use ilquentir_messages::{split_markdown_v2, validate_markdown_v2, MAX_MESSAGE_LEN};
+
+    use super::*;
+
+    #[test]
+    fn long_answers_are_split() {
+        let options: Vec<_> = (0..100)
+            .map(|idx| format!("*{idx:02}*_[x](y).").repeat(10))
+            .collect();
+        let selected: Vec<_> = (0..100).collect();
+
+        for locale in [Locale::Ru, Locale::En] {
+            let text = answered_text(PollKind::DailyEvents, &options, &selected, locale);
+            assert!(text.contains(r"\*00\*\_\[x\]\(y\)\."), "{text}");
+
+            let parts = split_markdown_v2(&text, MAX_MESSAGE_LEN).unwrap();
+            assert!(parts.len() > 1);
+            for part in parts {
+                assert_eq!(validate_markdown_v2(&part), Ok(()));
+            }
+        }
+    }
+}
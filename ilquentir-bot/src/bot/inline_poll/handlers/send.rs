@@ -0,0 +1,54 @@
+use color_eyre::{eyre::eyre, Result};
+use teloxide::{payloads::SendMessageSetters, requests::Requester, types::Message};
+use tracing::info;
+
+use ilquentir_messages::{md_message, Locale, Markup};
+use ilquentir_models::{PgTransaction, Poll, PollChoice};
+
+use crate::bot::Bot;
+
+use super::super::keyboard::poll_options;
+
+/// Sends poll as a message with options rendered as inline keyboard
+#[tracing::instrument(skip(bot, txn), err)]
+pub async fn send_keyboard_poll(
+    bot: &Bot,
+    txn: &mut PgTransaction<'_>,
+    poll: Poll,
+    options: Vec<PollChoice>,
+    locale: Locale,
+) -> Result<Message> {
+    let poll_id = poll
+        .id
+        .ok_or_else(|| eyre!("trying to send unsaved poll"))?;
+    let allows_multiple_answers = poll.kind.allows_multiple_answers();
+
+    let text = if allows_multiple_answers {
+        md_message!(
+            locale,
+            "inline_poll/question_multiple.md",
+            question = poll.kind.question(locale)
+        )
+    } else {
+        Markup::from(poll.kind.question(locale)).markdown_v2()
+    };
+
+    let texts: Vec<_> = options.iter().map(|option| option.text.clone()).collect();
+    let message = bot
+        .send_message(poll.chat_tg_id.to_string(), text)
+        .reply_markup(poll_options(
+            poll_id,
+            &texts,
+            &[],
+            allows_multiple_answers,
+            locale,
+        ))
+        .await?;
+
+    info!(poll_id, "poll sent as inline keyboard");
+
+    poll.published_as_keyboard(&mut *txn, &message, options)
+        .await?;
+
+    Ok(message)
+}
@@ -0,0 +1,113 @@
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+use teloxide::types::InlineKeyboardMarkup;
+
+use ilquentir_messages::{message, Locale};
+use ilquentir_models::{AnswerMode, PgTransaction, PollKind, PollSettings};
+
+use crate::bot::{
+    callbacks::{buttons_row, CallbackData},
+    settings,
+};
+
+const SELECTED: char = '✅';
+const NOT_SELECTED: char = '⬜';
+
+/// Poll kinds, which user can choose answer mode for
+pub const CONFIGURABLE_KINDS: [PollKind; 2] = [PollKind::HowWasYourDay, PollKind::DailyEvents];
+
+/// Answers to the poll, sent as a keyboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PollAnswerAction {
+    /// Chooses the option, or toggles it for multiple-answers polls
+    Option { poll_id: i64, option_idx: usize },
+    /// Finishes multiple-answers poll
+    Done { poll_id: i64 },
+}
+
+pub(super) fn option_button(poll_id: i64, option_idx: usize) -> CallbackData {
+    CallbackData::PollAnswer(PollAnswerAction::Option {
+        poll_id,
+        option_idx,
+    })
+}
+
+pub(super) fn done_button(poll_id: i64) -> CallbackData {
+    CallbackData::PollAnswer(PollAnswerAction::Done { poll_id })
+}
+
+pub(super) fn answer_mode_button(kind: PollKind) -> CallbackData {
+    CallbackData::AnswerMode(kind)
+}
+
+/// Keyboard with poll options, one option per row.
+///
+/// Multiple-answers polls show current selection and have "done" button.
+pub fn poll_options(
+    poll_id: i64,
+    options: &[String],
+    selected: &[i32],
+    allows_multiple_answers: bool,
+    locale: Locale,
+) -> InlineKeyboardMarkup {
+    let rows = options.iter().enumerate().map(|(idx, option)| {
+        let text = if allows_multiple_answers {
+            let mark = if selected.contains(&(idx as i32)) {
+                SELECTED
+            } else {
+                NOT_SELECTED
+            };
+
+            format!("{mark} {option}")
+        } else {
+            option.clone()
+        };
+
+        buttons_row![[text, option_button(poll_id, idx)]]
+    });
+
+    if allows_multiple_answers {
+        InlineKeyboardMarkup::new(rows.chain([buttons_row![[
+            message!(locale, "inline_poll/buttons/done.md"),
+            done_button(poll_id)
+        ]]]))
+    } else {
+        InlineKeyboardMarkup::new(rows)
+    }
+}
+
+fn format_answer_mode(mode: AnswerMode, locale: Locale) -> String {
+    match mode {
+        AnswerMode::NativePoll => message!(locale, "inline_poll/answer_modes/native_poll.md"),
+        AnswerMode::InlineKeyboard => {
+            message!(locale, "inline_poll/answer_modes/inline_keyboard.md")
+        }
+    }
+}
+
+#[tracing::instrument(skip(txn), err)]
+pub async fn answer_mode_settings(
+    txn: &mut PgTransaction<'_>,
+    chat_id: i64,
+    locale: Locale,
+) -> Result<InlineKeyboardMarkup> {
+    let mut rows = Vec::with_capacity(CONFIGURABLE_KINDS.len() + 1);
+
+    for kind in CONFIGURABLE_KINDS {
+        let mode = PollSettings::get_answer_mode(&mut *txn, chat_id, kind).await?;
+
+        rows.push(buttons_row![[
+            message!(
+                locale,
+                "inline_poll/buttons/answer_mode.md",
+                question = kind.question(locale),
+                mode = format_answer_mode(mode, locale)
+            ),
+            answer_mode_button(kind)
+        ]]);
+    }
+
+    rows.push(settings::keyboard::back_to_menu(locale));
+
+    Ok(InlineKeyboardMarkup::new(rows))
+}
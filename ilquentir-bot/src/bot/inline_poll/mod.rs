@@ -0,0 +1,4 @@
+mod handlers;
+pub mod keyboard;
+
+pub use handlers::*;
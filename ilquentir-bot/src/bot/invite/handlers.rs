@@ -0,0 +1,2 @@
+mod command;
+pub use command::handle_invite_command;
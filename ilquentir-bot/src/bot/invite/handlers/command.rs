@@ -0,0 +1,33 @@
+use color_eyre::Result;
+use teloxide::{requests::Requester, types::ChatId};
+
+use ilquentir_messages::{md_message, Locale};
+use ilquentir_models::{PgTransaction, UserSource};
+
+use crate::bot::Bot;
+
+use super::super::invite_link;
+
+#[tracing::instrument(skip(bot, txn), fields(chat_id=chat_id.0), err)]
+pub async fn handle_invite_command(
+    bot: &Bot,
+    txn: &mut PgTransaction<'_>,
+    chat_id: ChatId,
+    locale: Locale,
+) -> Result<()> {
+    let me = bot.get_me().await?;
+    let invited = UserSource::count_referred(txn, chat_id.0).await?;
+
+    bot.send_message(
+        chat_id,
+        md_message!(
+            locale,
+            "invite/link.md",
+            link = invite_link(me.username(), chat_id.0),
+            invited = invited
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
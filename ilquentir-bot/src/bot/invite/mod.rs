@@ -0,0 +1,5 @@
+mod handlers;
+pub(super) use handlers::*;
+
+mod start_param;
+pub(super) use start_param::{invite_link, parse as parse_start_param};
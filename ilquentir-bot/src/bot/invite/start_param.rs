@@ -0,0 +1,95 @@
+use tracing::debug;
+
+use ilquentir_models::UserSource;
+
+/// Telegram limits the deep link payload to 64 characters
+const MAX_LEN: usize = 64;
+/// Payload consists of `<key>_<value>` segments, joined by this separator,
+/// e.g. `c_spring-p_psyclub`
+const SEGMENTS_SEPARATOR: char = '-';
+
+const REFERRER_KEY: &str = "ref";
+const CAMPAIGN_KEY: &str = "c";
+const PARTNER_KEY: &str = "p";
+
+/// Parses the payload of the `t.me/<bot>?start=<payload>` deep link.
+///
+/// Returns `None` if there is no payload or it isn't allowed by Telegram; unknown segments
+/// are ignored, but the raw payload is kept anyway.
+pub fn parse(user_tg_id: i64, start_param: &str) -> Option<UserSource> {
+    let start_param = start_param.trim();
+
+    if start_param.is_empty()
+        || start_param.len() > MAX_LEN
+        || !start_param
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return None;
+    }
+
+    let mut source = UserSource {
+        user_tg_id,
+        start_param: start_param.to_owned(),
+        ..UserSource::default()
+    };
+
+    for segment in start_param.split(SEGMENTS_SEPARATOR) {
+        match segment.split_once('_') {
+            Some((REFERRER_KEY, referrer)) => {
+                // one can't invite themselves
+                source.referrer_tg_id = referrer.parse().ok().filter(|&id| id != user_tg_id);
+            }
+            Some((CAMPAIGN_KEY, campaign)) if !campaign.is_empty() => {
+                source.campaign = Some(campaign.to_owned());
+            }
+            Some((PARTNER_KEY, partner_code)) if !partner_code.is_empty() => {
+                source.partner_code = Some(partner_code.to_owned());
+            }
+            _ => debug!(segment, "unknown start param segment"),
+        }
+    }
+
+    Some(source)
+}
+
+/// Personal link, which attributes everyone who joins via it to the user
+pub fn invite_link(bot_username: &str, user_tg_id: i64) -> String {
+    format!("https://t.me/{bot_username}?start={REFERRER_KEY}_{user_tg_id}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_segments() {
+        let source = parse(1, "ref_42-c_spring_2023-p_psyclub").unwrap();
+
+        assert_eq!(source.start_param, "ref_42-c_spring_2023-p_psyclub");
+        assert_eq!(source.referrer_tg_id, Some(42));
+        assert_eq!(source.campaign.as_deref(), Some("spring_2023"));
+        assert_eq!(source.partner_code.as_deref(), Some("psyclub"));
+
+        let link = invite_link("ilquentir_bot", 42);
+        let payload = link.split_once("?start=").unwrap().1;
+        assert_eq!(parse(1, payload).unwrap().referrer_tg_id, Some(42));
+    }
+
+    #[test]
+    fn parse_unknown_and_invalid() {
+        let unknown = parse(1, "telegram_channel").unwrap();
+        assert_eq!(unknown.start_param, "telegram_channel");
+        assert_eq!(unknown.referrer_tg_id, None);
+        assert_eq!(unknown.campaign, None);
+        assert_eq!(unknown.partner_code, None);
+
+        // self-referral and broken ids
+        assert_eq!(parse(42, "ref_42").unwrap().referrer_tg_id, None);
+        assert_eq!(parse(1, "ref_abc").unwrap().referrer_tg_id, None);
+
+        for invalid in ["", "  ", "c_весна", "c_a b", &"a".repeat(65)] {
+            assert_eq!(parse(1, invalid), None);
+        }
+    }
+}
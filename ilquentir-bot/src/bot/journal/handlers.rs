@@ -0,0 +1,5 @@
+mod command;
+pub use command::{handle_gratitude_journal_command, toggle_gratitude_journal};
+
+mod message;
+pub use message::save_prompt_answer;
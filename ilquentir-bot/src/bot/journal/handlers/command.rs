@@ -0,0 +1,59 @@
+use color_eyre::Result;
+use teloxide::{requests::Requester, types::ChatId};
+use time::OffsetDateTime;
+use tracing::info;
+
+use ilquentir_messages::{md_message, Locale};
+use ilquentir_models::{PgTransaction, Poll, PollKind};
+
+use crate::bot::Bot;
+
+#[tracing::instrument(skip(bot, txn), fields(chat_id=chat_id.0), err)]
+pub async fn handle_gratitude_journal_command(
+    bot: &Bot,
+    txn: &mut PgTransaction<'_>,
+    chat_id: ChatId,
+    locale: Locale,
+) -> Result<()> {
+    let text = if toggle_gratitude_journal(txn, chat_id.0).await? {
+        md_message!(locale, "journal/enabled.md")
+    } else {
+        md_message!(locale, "journal/disabled.md")
+    };
+
+    bot.send_message(chat_id, text).await?;
+
+    Ok(())
+}
+
+/// Schedules gratitude journal prompts, or disables them if they are scheduled already.
+///
+/// Returns `true` if prompts were enabled.
+#[tracing::instrument(skip(txn), err)]
+pub async fn toggle_gratitude_journal(txn: &mut PgTransaction<'_>, chat_id: i64) -> Result<bool> {
+    const POLL_KIND: PollKind = PollKind::GratitudeJournal;
+
+    let scheduled = Poll::get_scheduled_for_user(&mut *txn, chat_id, POLL_KIND).await?;
+
+    if !scheduled.is_empty() {
+        Poll::disable_pending_for_user(&mut *txn, chat_id, POLL_KIND).await?;
+        info!(chat_id, "disabled gratitude journal prompts");
+
+        return Ok(false);
+    }
+
+    let now = OffsetDateTime::now_utc();
+    let publication_date = POLL_KIND
+        .schedule_next_custom(&mut *txn, chat_id, now)
+        .await?
+        .unwrap_or_else(|| POLL_KIND.schedule_next(now));
+
+    let poll = Poll::create(&mut *txn, chat_id, POLL_KIND, Some(publication_date)).await?;
+    info!(
+        chat_id,
+        poll_id = poll.id,
+        "enabled gratitude journal prompts"
+    );
+
+    Ok(true)
+}
@@ -0,0 +1,121 @@
+use color_eyre::Result;
+use sqlx::PgPool;
+use teloxide::{
+    requests::Requester,
+    types::{MediaText, Message},
+};
+use tracing::info;
+
+use ilquentir_messages::{md_message, Locale};
+use ilquentir_models::{Dialogue, JournalPrompt, PgTransaction, PollTextAnswer};
+
+use crate::bot::Bot;
+
+#[tracing::instrument(skip(bot, pool, msg_text), err)]
+pub async fn save_prompt_answer(
+    bot: Bot,
+    pool: PgPool,
+    msg: Message,
+    msg_text: MediaText,
+    dialogue: Dialogue,
+    prompt: JournalPrompt,
+    locale: Locale,
+) -> Result<()> {
+    let mut txn = pool.begin().await?;
+
+    let answer = save_answer(&mut txn, dialogue, prompt, &msg_text.text).await?;
+    info!(
+        poll_id = answer.poll_id,
+        chat_id = msg.chat.id.0,
+        "saved prompt answer"
+    );
+
+    bot.send_message(msg.chat.id, md_message!(locale, "journal/answer_saved.md"))
+        .await?;
+
+    txn.commit().await?;
+
+    Ok(())
+}
+
+/// Finishes the prompt with the given answer, other questions in the chat keep waiting
+#[tracing::instrument(skip(txn, text), err)]
+async fn save_answer(
+    txn: &mut PgTransaction<'_>,
+    dialogue: Dialogue,
+    prompt: JournalPrompt,
+    text: &str,
+) -> Result<PollTextAnswer> {
+    dialogue.finish(&mut *txn).await?;
+
+    PollTextAnswer::insert(txn, prompt.poll_id, text).await
+}
+
+#[cfg(test)]
+mod tests {
+    use ilquentir_models::{AnswerNoteQuestion, DialogueState, Poll, PollKind, User};
+
+    use crate::bot::dialogue;
+
+    use super::*;
+
+    const CHAT_ID: i64 = 42;
+
+    fn text_message(text: &str, reply_to_message_id: Option<i32>) -> Message {
+        let chat = serde_json::json!({"id": CHAT_ID, "type": "private", "first_name": "Ilquentir"});
+        let mut message = serde_json::json!({
+            "message_id": 100,
+            "date": 0,
+            "chat": chat,
+            "text": text,
+        });
+        if let Some(message_id) = reply_to_message_id {
+            message["reply_to_message"] = serde_json::json!({
+                "message_id": message_id,
+                "date": 0,
+                "chat": chat,
+                "text": "question",
+            });
+        }
+
+        serde_json::from_value(message).unwrap()
+    }
+
+    #[sqlx::test(migrations = "../ilquentir-models/migrations")]
+    async fn prompt_answer_while_note_is_pending(pool: PgPool) -> Result<()> {
+        let mut txn = pool.begin().await?;
+        User::activate(&mut txn, CHAT_ID).await?;
+        let prompt_poll = Poll::create(&mut txn, CHAT_ID, PollKind::GratitudeJournal, None).await?;
+        let prompt_poll_id = prompt_poll.id.expect("poll is saved");
+
+        // both are sent at 19:00, the note is asked after the mood poll is answered
+        let prompt = DialogueState::JournalPrompt(JournalPrompt {
+            poll_id: prompt_poll_id,
+        });
+        Dialogue::start(&mut txn, CHAT_ID, prompt, 10).await?;
+        let note = DialogueState::AnswerNote(AnswerNoteQuestion { poll_id: 7 });
+        Dialogue::start(&mut txn, CHAT_ID, note.clone(), 11).await?;
+        txn.commit().await?;
+
+        let dialogue = dialogue::get_active(pool.clone(), text_message("grateful", Some(10)))
+            .await
+            .expect("prompt is waiting");
+        let prompt = dialogue::journal_prompt(dialogue.clone()).expect("reply answers the prompt");
+        assert_eq!(prompt.poll_id, prompt_poll_id);
+
+        let mut txn = pool.begin().await?;
+        let answer = save_answer(&mut txn, dialogue, prompt, "grateful").await?;
+        txn.commit().await?;
+        assert_eq!(answer.poll_id, prompt_poll_id);
+        assert_eq!(answer.text, "grateful");
+
+        let dialogue = dialogue::get_active(pool, text_message("long day", None)).await;
+        assert_eq!(
+            dialogue.map(|dialogue| dialogue.state),
+            Some(note),
+            "note is still waiting"
+        );
+
+        Ok(())
+    }
+}
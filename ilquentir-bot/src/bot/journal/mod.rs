@@ -0,0 +1,2 @@
+mod handlers;
+pub(super) use handlers::*;
@@ -0,0 +1,5 @@
+mod callback;
+pub use callback::handle_language_callback;
+
+mod command;
+pub use command::{handle_language_command, language_view};
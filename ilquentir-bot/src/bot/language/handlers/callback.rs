@@ -0,0 +1,75 @@
+use color_eyre::{eyre::eyre, Result};
+use teloxide::{
+    payloads::{
+        EditMessageTextSetters, GetChatMenuButtonSetters, SetChatMenuButtonSetters,
+        SetMyCommandsSetters,
+    },
+    requests::Requester,
+    types::{BotCommandScope, CallbackQuery, MenuButton, Recipient},
+};
+use tracing::info;
+
+use ilquentir_messages::{md_message, message, Locale};
+use ilquentir_models::{PgTransaction, User};
+
+use crate::bot::{callbacks::answer_stale, commands, Bot};
+
+use super::super::{keyboard, options::LanguageAction};
+
+#[tracing::instrument(skip(bot, txn), err)]
+pub async fn handle_language_callback(
+    bot: &Bot,
+    txn: &mut PgTransaction<'_>,
+    callback: &CallbackQuery,
+    action: LanguageAction,
+    locale: Locale,
+) -> Result<()> {
+    let user_tg_id = callback.from.id.0 as i64;
+    let message = callback
+        .message
+        .as_ref()
+        .ok_or_else(|| eyre!("payload with no message"))?;
+
+    let LanguageAction::Pick(idx) = action;
+    let locale = if let Some(&picked) = Locale::ALL.get(idx) {
+        picked
+    } else {
+        return answer_stale(bot, callback, locale).await;
+    };
+
+    User::set_language(txn, user_tg_id, locale.code()).await?;
+    info!(
+        user_tg_id,
+        language = locale.code(),
+        "user picked the language"
+    );
+
+    // menu of the commands follows the language of the Telegram client otherwise
+    bot.set_my_commands(commands::localized(locale))
+        .scope(BotCommandScope::Chat {
+            chat_id: Recipient::Id(message.chat.id),
+        })
+        .await?;
+    // as is the Mini App button, which is set for all the chats in the default language
+    let menu_button = bot.get_chat_menu_button().chat_id(message.chat.id).await?;
+    if let MenuButton::WebApp { web_app, .. } = menu_button {
+        bot.set_chat_menu_button()
+            .chat_id(message.chat.id)
+            .menu_button(MenuButton::WebApp {
+                text: message!(locale, "settings/buttons/menu.md"),
+                web_app,
+            })
+            .await?;
+    }
+
+    bot.edit_message_text(
+        message.chat.id,
+        message.id,
+        md_message!(locale, "language/saved.md", language = locale.name()),
+    )
+    .reply_markup(keyboard::languages(locale))
+    .await?;
+    bot.answer_callback_query(&callback.id).await?;
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use color_eyre::Result;
+use teloxide::{
+    payloads::SendMessageSetters,
+    requests::Requester,
+    types::{ChatId, InlineKeyboardMarkup},
+};
+
+use ilquentir_messages::{md_message, Locale};
+
+use crate::bot::Bot;
+
+use super::super::keyboard;
+
+#[tracing::instrument(skip(bot), fields(chat_id=chat_id.0), err)]
+pub async fn handle_language_command(bot: &Bot, chat_id: ChatId, locale: Locale) -> Result<()> {
+    let (text, keyboard) = language_view(locale);
+
+    bot.send_message(chat_id, text)
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
+
+/// Language picker, also shown in place of the settings menu
+pub fn language_view(locale: Locale) -> (String, InlineKeyboardMarkup) {
+    (
+        md_message!(locale, "language/choose.md"),
+        keyboard::languages(locale),
+    )
+}
@@ -0,0 +1,20 @@
+use teloxide::types::InlineKeyboardMarkup;
+
+use ilquentir_messages::Locale;
+
+use crate::bot::{callbacks::buttons_row, settings};
+
+use super::options;
+
+pub(super) fn languages(current: Locale) -> InlineKeyboardMarkup {
+    let languages = Locale::ALL.into_iter().enumerate().map(|(idx, locale)| {
+        let mark = if locale == current { "✅" } else { "⬜" };
+
+        buttons_row![[
+            format!("{mark} {}", locale.name()),
+            options::pick_button(idx)
+        ]]
+    });
+
+    InlineKeyboardMarkup::new(languages.chain([settings::keyboard::back_to_menu(current)]))
+}
@@ -0,0 +1,50 @@
+use color_eyre::Result;
+use sqlx::PgPool;
+use teloxide::types::Update;
+use tracing::warn;
+
+use ilquentir_messages::Locale;
+use ilquentir_models::{PgTransaction, User};
+
+/// Locale of the user, who sent the update, provided to every handler as a dependency.
+///
+/// Users, who haven't chosen the language, get the language of their Telegram client.
+pub async fn user_locale(pool: PgPool, update: Update) -> Locale {
+    let user = if let Some(user) = update.user() {
+        user
+    } else {
+        return Locale::default();
+    };
+
+    let language = async {
+        let mut txn = pool.begin().await?;
+
+        User::get_language(&mut txn, user.id.0 as i64).await
+    }
+    .await
+    .unwrap_or_else(|error| {
+        warn!(
+            user_tg_id = user.id.0,
+            ?error,
+            "failed to get user language"
+        );
+
+        None
+    });
+
+    language
+        .as_deref()
+        .or(user.language_code.as_deref())
+        .and_then(Locale::from_code)
+        .unwrap_or_default()
+}
+
+/// Locale of the user for the messages, which aren't replies to any update
+#[tracing::instrument(skip(txn), err)]
+pub async fn locale_of(txn: &mut PgTransaction<'_>, user_tg_id: i64) -> Result<Locale> {
+    Ok(User::get_language(txn, user_tg_id)
+        .await?
+        .as_deref()
+        .and_then(Locale::from_code)
+        .unwrap_or_default())
+}
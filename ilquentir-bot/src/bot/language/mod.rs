@@ -0,0 +1,8 @@
+mod handlers;
+pub(self) mod keyboard;
+mod locale;
+pub(self) mod options;
+
+pub(super) use handlers::*;
+pub(super) use locale::{locale_of, user_locale};
+pub use options::LanguageAction;
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+use crate::bot::callbacks::CallbackData;
+
+/// Actions of the language picker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LanguageAction {
+    /// Index of the locale in [`ilquentir_messages::Locale::ALL`]
+    Pick(usize),
+}
+
+pub(super) fn pick_button(idx: usize) -> CallbackData {
+    CallbackData::Language(LanguageAction::Pick(idx))
+}
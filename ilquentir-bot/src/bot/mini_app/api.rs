@@ -0,0 +1,237 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{Html, IntoResponse, Response},
+    Json,
+};
+use color_eyre::Report;
+use serde::{Deserialize, Serialize};
+use teloxide::types::ChatId;
+use time::macros::format_description;
+use time_tz::TimeZone;
+use tracing::{error, info};
+
+use ilquentir_models::{PgTransaction, Poll, PollKind, User, WideHowWasYourDay};
+
+use crate::bot::{
+    language,
+    settings::{subscription_title, SUBSCRIPTIONS},
+    setup_schedule,
+};
+
+use super::{init_data::WebAppUser, AppState};
+
+/// Error of the API, internal details are logged, but not exposed to the client
+pub(super) enum ApiError {
+    Rejected(StatusCode, &'static str),
+    Internal(Report),
+}
+
+impl<E: Into<Report>> From<E> for ApiError {
+    fn from(error: E) -> Self {
+        Self::Internal(error.into())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        #[derive(Serialize)]
+        struct Error {
+            error: &'static str,
+        }
+
+        let (status, error) = match self {
+            Self::Rejected(status, error) => (status, error),
+            Self::Internal(report) => {
+                error!(error = %report, "Mini App request failed");
+
+                (StatusCode::INTERNAL_SERVER_ERROR, "internal")
+            }
+        };
+
+        (status, Json(Error { error })).into_response()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct Settings {
+    schedule: Schedule,
+    subscriptions: Vec<Subscription>,
+}
+
+/// Time of the polls in user's timezone
+#[derive(Debug, Serialize)]
+struct Schedule {
+    time: String,
+    timezone: String,
+    offset: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Subscription {
+    kind: PollKind,
+    /// Name in the user's language
+    title: String,
+    enabled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct SubscriptionUpdate {
+    kind: PollKind,
+    enabled: bool,
+}
+
+/// Day of the stats dashboard
+#[derive(Debug, Serialize)]
+pub(super) struct StatsDay {
+    date: String,
+    /// Answer on the -1..1 scale, `None` if the poll wasn't answered
+    value: Option<f64>,
+    note: Option<String>,
+    /// Daily events, one per line
+    events: Option<String>,
+}
+
+pub(super) async fn index() -> Html<&'static str> {
+    Html(include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/mini_app/index.html"
+    )))
+}
+
+#[tracing::instrument(skip(state))]
+pub(super) async fn get_settings(
+    State(state): State<AppState>,
+    user: WebAppUser,
+) -> Result<Json<Settings>, ApiError> {
+    let mut txn = state.pool.begin().await?;
+    ensure_active(&mut txn, user).await?;
+
+    let settings = load_settings(&mut txn, user).await?;
+    txn.commit().await?;
+
+    Ok(Json(settings))
+}
+
+/// Accepts the same payload, as the WebApp sends to the chat
+#[tracing::instrument(skip(state))]
+pub(super) async fn set_schedule(
+    State(state): State<AppState>,
+    user: WebAppUser,
+    payload: String,
+) -> Result<Json<Settings>, ApiError> {
+    let picked = setup_schedule::parse_web_app_data(&payload)
+        .map_err(|error| ApiError::Rejected(StatusCode::BAD_REQUEST, error.code()))?;
+
+    let mut txn = state.pool.begin().await?;
+    ensure_active(&mut txn, user).await?;
+
+    setup_schedule::save_picked_time(&mut txn, user.tg_id, &picked).await?;
+    let settings = load_settings(&mut txn, user).await?;
+    let locale = language::locale_of(&mut txn, user.tg_id).await?;
+    txn.commit().await?;
+
+    setup_schedule::send_schedule_done(&state.bot, ChatId(user.tg_id), &picked, locale).await?;
+
+    Ok(Json(settings))
+}
+
+#[tracing::instrument(skip(state))]
+pub(super) async fn set_subscription(
+    State(state): State<AppState>,
+    user: WebAppUser,
+    Json(update): Json<SubscriptionUpdate>,
+) -> Result<Json<Settings>, ApiError> {
+    if !SUBSCRIPTIONS.contains(&update.kind) {
+        return Err(ApiError::Rejected(
+            StatusCode::BAD_REQUEST,
+            "unknown_subscription",
+        ));
+    }
+
+    let mut txn = state.pool.begin().await?;
+    ensure_active(&mut txn, user).await?;
+
+    if update.enabled {
+        Poll::schedule_for_user(&mut txn, user.tg_id, update.kind).await?;
+    } else {
+        Poll::disable_pending_for_user(&mut txn, user.tg_id, update.kind).await?;
+    }
+    info!(
+        user_tg_id = user.tg_id,
+        kind = %update.kind,
+        enabled = update.enabled,
+        "subscription changed in the Mini App"
+    );
+
+    let settings = load_settings(&mut txn, user).await?;
+    txn.commit().await?;
+
+    Ok(Json(settings))
+}
+
+#[tracing::instrument(skip(state))]
+pub(super) async fn get_stats(
+    State(state): State<AppState>,
+    user: WebAppUser,
+) -> Result<Json<Vec<StatsDay>>, ApiError> {
+    let mut txn = state.pool.begin().await?;
+    ensure_active(&mut txn, user).await?;
+
+    let days = WideHowWasYourDay::collect_for_user(&mut txn, user.tg_id)
+        .await?
+        .into_iter()
+        .map(|day| {
+            Ok(StatsDay {
+                date: day
+                    .poll_date_about
+                    .format(format_description!("[year]-[month]-[day]"))?,
+                value: day.answer_normalized_value,
+                note: day.note,
+                events: day.events.map(|events| events.replace(",<br>", "\n")),
+            })
+        })
+        .collect::<Result<_, ApiError>>()?;
+    txn.commit().await?;
+
+    Ok(Json(days))
+}
+
+/// Mini App can be opened only by the users, who have started the bot
+async fn ensure_active(txn: &mut PgTransaction<'_>, user: WebAppUser) -> Result<(), ApiError> {
+    match User::get_user_by_id(txn, user.tg_id).await? {
+        Some(_) => Ok(()),
+        None => Err(ApiError::Rejected(StatusCode::FORBIDDEN, "inactive_user")),
+    }
+}
+
+async fn load_settings(
+    txn: &mut PgTransaction<'_>,
+    user: WebAppUser,
+) -> Result<Settings, ApiError> {
+    let schedule = setup_schedule::local_schedule(&mut *txn, user.tg_id).await?;
+
+    let locale = language::locale_of(&mut *txn, user.tg_id).await?;
+
+    let mut subscriptions = Vec::with_capacity(SUBSCRIPTIONS.len());
+    for kind in SUBSCRIPTIONS {
+        let enabled = !Poll::get_scheduled_for_user(&mut *txn, user.tg_id, kind)
+            .await?
+            .is_empty();
+
+        subscriptions.push(Subscription {
+            kind,
+            title: subscription_title(kind, locale).unwrap_or_default(),
+            enabled,
+        });
+    }
+
+    Ok(Settings {
+        schedule: Schedule {
+            time: setup_schedule::format_time(schedule.time),
+            timezone: schedule.timezone.name().to_owned(),
+            offset: setup_schedule::format_offset(schedule.offset)?,
+        },
+        subscriptions,
+    })
+}
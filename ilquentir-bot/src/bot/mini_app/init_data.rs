@@ -0,0 +1,192 @@
+use std::collections::BTreeMap;
+
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header::AUTHORIZATION, request::Parts, StatusCode},
+};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+use time::{Duration, OffsetDateTime};
+use tracing::info;
+
+use super::AppState;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// `initData` is accepted only for this long after Telegram has signed it
+const MAX_AGE: Duration = Duration::DAY;
+
+/// Why the `initData` was rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum InitDataError {
+    Missing,
+    Malformed,
+    BadSignature,
+    Expired,
+}
+
+/// User, who has opened the Mini App, authenticated by the `initData` signed by Telegram.
+///
+/// Extracted from the `Authorization: tma <initData>` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct WebAppUser {
+    pub tg_id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    id: i64,
+}
+
+/// Validates the `initData` as described in
+/// [the docs](https://core.telegram.org/bots/webapps#validating-data-received-via-the-mini-app)
+pub(super) fn validate(
+    init_data: &str,
+    bot_token: &str,
+    now: OffsetDateTime,
+) -> Result<WebAppUser, InitDataError> {
+    let mut fields: BTreeMap<_, _> = form_urlencoded::parse(init_data.as_bytes()).collect();
+
+    let hash = fields.remove("hash").ok_or(InitDataError::Malformed)?;
+    let hash = hex::decode(hash.as_bytes()).map_err(|_| InitDataError::Malformed)?;
+
+    // fields are already sorted by the key
+    let data_check_string = fields
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut mac =
+        HmacSha256::new_from_slice(&secret_key(bot_token)).expect("HMAC accepts keys of any size");
+    mac.update(data_check_string.as_bytes());
+    mac.verify_slice(&hash)
+        .map_err(|_| InitDataError::BadSignature)?;
+
+    let auth_date = fields
+        .get("auth_date")
+        .and_then(|auth_date| auth_date.parse::<i64>().ok())
+        .and_then(|auth_date| OffsetDateTime::from_unix_timestamp(auth_date).ok())
+        .ok_or(InitDataError::Malformed)?;
+    if now - auth_date > MAX_AGE {
+        return Err(InitDataError::Expired);
+    }
+
+    let user: User = fields
+        .get("user")
+        .and_then(|user| serde_json::from_str(user).ok())
+        .ok_or(InitDataError::Malformed)?;
+
+    Ok(WebAppUser { tg_id: user.id })
+}
+
+fn secret_key(bot_token: &str) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(b"WebAppData").expect("HMAC accepts keys of any size");
+    mac.update(bot_token.as_bytes());
+
+    mac.finalize().into_bytes().to_vec()
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for WebAppUser {
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let init_data = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|header| header.to_str().ok())
+            .and_then(|header| header.strip_prefix("tma "))
+            .ok_or(InitDataError::Missing);
+
+        init_data
+            .and_then(|init_data| validate(init_data, state.bot_token(), OffsetDateTime::now_utc()))
+            .map_err(|error| {
+                info!(?error, "rejected Mini App request");
+
+                (StatusCode::UNAUTHORIZED, "invalid initData")
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::ext::NumericalDuration;
+
+    use super::*;
+
+    const TOKEN: &str = "123456:test-token";
+
+    fn sign(fields: &[(&str, &str)], token: &str) -> String {
+        let mut sorted = fields.to_vec();
+        sorted.sort();
+        let data_check_string = sorted
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let mut mac = HmacSha256::new_from_slice(&secret_key(token)).unwrap();
+        mac.update(data_check_string.as_bytes());
+        let hash = hex::encode(mac.finalize().into_bytes());
+
+        form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(fields)
+            .append_pair("hash", &hash)
+            .finish()
+    }
+
+    fn fields(auth_date: &str) -> [(&str, &str); 3] {
+        [
+            ("query_id", "AAHdF6IQAAAAAN0XohDhrOrc"),
+            (
+                "user",
+                r#"{"id":42,"first_name":"Ilquentir","language_code":"ru"}"#,
+            ),
+            ("auth_date", auth_date),
+        ]
+    }
+
+    #[test]
+    fn valid() {
+        let now = OffsetDateTime::now_utc();
+        let init_data = sign(&fields(&now.unix_timestamp().to_string()), TOKEN);
+
+        assert_eq!(
+            validate(&init_data, TOKEN, now),
+            Ok(WebAppUser { tg_id: 42 })
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        let now = OffsetDateTime::now_utc();
+        let auth_date = now.unix_timestamp().to_string();
+
+        assert_eq!(
+            validate(&sign(&fields(&auth_date), "654321:other-token"), TOKEN, now),
+            Err(InitDataError::BadSignature)
+        );
+        assert_eq!(
+            validate(
+                &sign(&fields(&auth_date), TOKEN).replace("42", "43"),
+                TOKEN,
+                now
+            ),
+            Err(InitDataError::BadSignature)
+        );
+        assert_eq!(
+            validate(&sign(&fields(&auth_date), TOKEN), TOKEN, now + 2.days()),
+            Err(InitDataError::Expired)
+        );
+        assert_eq!(
+            validate("user=%7B%22id%22%3A42%7D", TOKEN, now),
+            Err(InitDataError::Malformed)
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+use color_eyre::Result;
+use sqlx::PgPool;
+use tracing::info;
+
+use ilquentir_config::Config;
+
+use crate::bot::Bot;
+
+mod api;
+mod init_data;
+
+/// State, shared by all the Mini App handlers
+#[derive(Clone)]
+struct AppState {
+    bot: Bot,
+    pool: PgPool,
+}
+
+impl AppState {
+    /// `initData` is signed with the token of the bot, which has opened the Mini App
+    fn bot_token(&self) -> &str {
+        self.bot.inner().inner().token()
+    }
+}
+
+/// Serves the Mini App page and its API until the process is stopped
+pub async fn serve(bot: Bot, pool: PgPool, config: Config) -> Result<()> {
+    let app = Router::new()
+        .route("/", get(api::index))
+        .route("/api/settings", get(api::get_settings))
+        .route("/api/schedule", post(api::set_schedule))
+        .route("/api/subscriptions", post(api::set_subscription))
+        .route("/api/stats", get(api::get_stats))
+        .with_state(AppState { bot, pool });
+
+    info!(addr = %config.mini_app.listen_addr, "serving Mini App");
+    axum::Server::bind(&config.mini_app.listen_addr)
+        .serve(app.into_make_service())
+        .await?;
+
+    Ok(())
+}
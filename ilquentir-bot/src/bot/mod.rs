@@ -3,31 +3,47 @@ use sqlx::PgPool;
 use teloxide::{
     adaptors::{trace::Settings, DefaultParseMode, Trace},
     dispatching::{DefaultKey, HandlerExt, UpdateFilterExt},
+    payloads::{SetChatMenuButtonSetters, SetMyCommandsSetters},
     prelude::Dispatcher as TgDispatcher,
     requests::{Requester, RequesterExt},
-    types::{ParseMode, Update},
-    utils::command::BotCommands,
+    types::{MenuButton, ParseMode, Update, WebAppInfo},
     Bot as TgBot,
 };
 
 use ilquentir_config::Config;
+use ilquentir_messages::{message, Locale};
 use ilquentir_python_graph::Plotter;
 
 pub(self) mod callbacks;
 pub mod commands;
 pub mod handlers;
 pub mod helpers;
+pub mod mini_app;
+pub mod onboarding;
 
+mod admin;
+mod context;
 mod daily_events;
+mod dialogue;
 mod diary;
 mod extractors;
 mod get_stats;
 mod how_was_your_day;
+mod inline_poll;
+mod invite;
+mod journal;
+mod language;
+mod mood_check_in;
+mod mood_scale;
+mod settings;
 mod setup_schedule;
 
 use self::{
-    commands::Command,
-    handlers::{handle_ban, handle_callback, handle_command, handle_poll_update},
+    commands::{AdminCommand, Command},
+    handlers::{
+        handle_ban, handle_callback, handle_command, handle_poll_answer, handle_poll_update,
+        refresh_profile,
+    },
 };
 
 pub type Bot = Trace<DefaultParseMode<TgBot>>;
@@ -37,17 +53,36 @@ pub async fn create_bot() -> Result<Bot> {
     let bot = TgBot::from_env()
         .parse_mode(ParseMode::MarkdownV2)
         .trace(Settings::TRACE_EVERYTHING);
-    bot.set_my_commands(commands::Command::bot_commands())
+    bot.set_my_commands(commands::localized(Locale::default()))
         .await?;
+    for locale in Locale::ALL {
+        if locale != Locale::default() {
+            bot.set_my_commands(commands::localized(locale))
+                .language_code(locale.code())
+                .await?;
+        }
+    }
 
     Ok(bot)
 }
 
 pub async fn create_bot_and_dispatcher(pool: PgPool, config: &Config) -> Result<(Dispatcher, Bot)> {
     let bot = create_bot().await?;
+    if let Some(url) = &config.mini_app.url {
+        bot.set_chat_menu_button()
+            .menu_button(MenuButton::WebApp {
+                text: message!(Locale::default(), "settings/buttons/menu.md"),
+                web_app: WebAppInfo { url: url.clone() },
+            })
+            .await?;
+    }
     let plotter = Plotter::new(&mut pool.begin().await?, config.clone()).await?;
 
     let handler = dptree::entry()
+        // keep Telegram profile of the user up to date, before any handler runs
+        .inspect_async(refresh_profile)
+        // every handler can take the user's locale
+        .map_async(language::user_locale)
         // generic Command handler
         .branch(
             Update::filter_message().chain(
@@ -56,22 +91,68 @@ pub async fn create_bot_and_dispatcher(pool: PgPool, config: &Config) -> Result<
                     .endpoint(handle_command),
             ),
         )
+        // catalog management, admin check is done by the handler
+        .branch(
+            Update::filter_message().chain(
+                dptree::entry()
+                    .filter_command::<AdminCommand>()
+                    .endpoint(admin::handle_admin_command),
+            ),
+        )
         // save Poll response
         .branch(
             dptree::entry()
                 .filter_map(extractors::get_poll)
                 .endpoint(handle_poll_update),
         )
+        // save user's vote in non-anonymous Poll
+        .branch(
+            dptree::entry()
+                .filter_map(extractors::get_poll_answer)
+                .endpoint(handle_poll_answer),
+        )
         // handle the case in which user is banned the bot
         .branch(Update::filter_my_chat_member().endpoint(handle_ban))
         // generic callbacks handler
         .branch(Update::filter_callback_query().endpoint(handle_callback))
+        // location, shared to detect the timezone during onboarding
+        .branch(
+            Update::filter_message()
+                .filter_map(extractors::get_location)
+                .filter_map_async(dialogue::onboarding_location)
+                .endpoint(onboarding::handle_location),
+        )
         // setup schedule WebApp
         .branch(
             Update::filter_message()
                 .filter_map(extractors::get_web_app_data)
                 .endpoint(setup_schedule::handle_webapp),
         )
+        // answer to the question, asked in a dialogue
+        .branch(
+            Update::filter_message()
+                .filter_map(extractors::get_message_text)
+                .filter_map_async(dialogue::get_active)
+                // text of a personal daily events option
+                .branch(
+                    dptree::filter_map(dialogue::personal_option_edit)
+                        .endpoint(daily_events::save_personal_option),
+                )
+                // new name for a label of the mood scale
+                .branch(
+                    dptree::filter_map(dialogue::mood_scale_label_edit)
+                        .endpoint(mood_scale::save_label),
+                )
+                // note on why the day was like that
+                .branch(
+                    dptree::filter_map(dialogue::answer_note).endpoint(how_was_your_day::save_note),
+                )
+                // answer to a journaling prompt
+                .branch(
+                    dptree::filter_map(dialogue::journal_prompt)
+                        .endpoint(journal::save_prompt_answer),
+                ),
+        )
         // any other text message – append to diary
         .branch(
             Update::filter_message()
@@ -0,0 +1,8 @@
+mod callback;
+pub use callback::*;
+
+mod command;
+pub use command::*;
+
+mod send;
+pub use send::*;
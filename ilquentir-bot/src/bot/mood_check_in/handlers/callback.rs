@@ -0,0 +1,226 @@
+use color_eyre::{eyre::eyre, Result};
+use sqlx::PgPool;
+use teloxide::{
+    payloads::{AnswerCallbackQuerySetters, EditMessageReplyMarkupSetters},
+    requests::Requester,
+    types::CallbackQuery,
+};
+use time::OffsetDateTime;
+use tracing::info;
+
+use ilquentir_messages::{md_message, message, Locale};
+use ilquentir_models::{
+    PgTransaction, Poll, PollCustomOptions, PollDimensionAnswer, PollKind, PollOption, PollPart,
+};
+
+use crate::bot::{callbacks::answer_stale, handlers::poll_answered, Bot};
+
+use super::super::{
+    keyboard::{check_in, user_dimensions},
+    options::{CheckInAction, SettingsAction},
+};
+
+#[tracing::instrument(skip(bot, txn), err)]
+pub async fn handle_settings_callback(
+    bot: &Bot,
+    txn: &mut PgTransaction<'_>,
+    callback: &CallbackQuery,
+    action: SettingsAction,
+    locale: Locale,
+) -> Result<()> {
+    const POLL_KIND: PollKind = PollKind::MoodCheckIn;
+
+    let user_tg_id = callback.from.id.0 as i64;
+    let message = callback
+        .message
+        .as_ref()
+        .ok_or_else(|| eyre!("payload with no message"))?;
+
+    let dimension_id = match action {
+        SettingsAction::Toggle(dimension_id) => dimension_id,
+        SettingsAction::Enable => {
+            if Poll::get_scheduled_for_user(&mut *txn, user_tg_id, POLL_KIND)
+                .await?
+                .is_empty()
+            {
+                let now = OffsetDateTime::now_utc();
+                let publication_date = POLL_KIND
+                    .schedule_next_custom(&mut *txn, user_tg_id, now)
+                    .await?
+                    .unwrap_or_else(|| POLL_KIND.schedule_next(now));
+
+                let poll =
+                    Poll::create(&mut *txn, user_tg_id, POLL_KIND, Some(publication_date)).await?;
+                info!(user_tg_id, poll_id = poll.id, "enabled mood check-in");
+            }
+
+            bot.edit_message_text(
+                user_tg_id.to_string(),
+                message.id,
+                md_message!(locale, "mood_check_in/enabled.md"),
+            )
+            .await?;
+            bot.answer_callback_query(&callback.id).await?;
+
+            return Ok(());
+        }
+        SettingsAction::Disable => {
+            Poll::disable_pending_for_user(&mut *txn, user_tg_id, POLL_KIND).await?;
+            info!(user_tg_id, "disabled mood check-in");
+
+            bot.edit_message_text(
+                user_tg_id.to_string(),
+                message.id,
+                md_message!(locale, "mood_check_in/disabled.md"),
+            )
+            .await?;
+            bot.answer_callback_query(&callback.id).await?;
+
+            return Ok(());
+        }
+    };
+
+    let catalog = PollOption::get_catalog(&mut *txn, POLL_KIND).await?;
+    let dimension = if let Some(dimension) = catalog.iter().find(|o| o.id == dimension_id) {
+        dimension
+    } else {
+        return answer_stale(bot, callback, locale).await;
+    };
+
+    let custom = PollCustomOptions::get_for_user(&mut *txn, user_tg_id, POLL_KIND).await?;
+    let current = POLL_KIND.options(&mut *txn, user_tg_id, locale).await?;
+
+    if current.len() == 1 && current[0].option_id == Some(dimension.id) {
+        bot.answer_callback_query(&callback.id)
+            .text(message!(locale, "mood_check_in/keep_at_least_one.md"))
+            .await?;
+
+        return Ok(());
+    }
+
+    if custom.options.is_empty() {
+        // user hasn't chosen anything yet and sees the defaults, so let's start from them
+        for option_id in current.iter().filter_map(|choice| choice.option_id) {
+            PollCustomOptions::toggle_option(&mut *txn, user_tg_id, POLL_KIND, option_id).await?;
+        }
+    }
+    PollCustomOptions::toggle_option(&mut *txn, user_tg_id, POLL_KIND, dimension.id).await?;
+
+    let keyboard = user_dimensions(txn, user_tg_id, locale).await?;
+
+    bot.edit_message_reply_markup(user_tg_id.to_string(), message.id)
+        .reply_markup(keyboard)
+        .await?;
+    bot.answer_callback_query(&callback.id).await?;
+
+    Ok(())
+}
+
+#[tracing::instrument(skip(bot, pool), err)]
+pub async fn handle_check_in_callback(
+    bot: &Bot,
+    pool: &PgPool,
+    callback: &CallbackQuery,
+    action: CheckInAction,
+    locale: Locale,
+) -> Result<()> {
+    let user_tg_id = callback.from.id.0 as i64;
+    let message = callback
+        .message
+        .as_ref()
+        .ok_or_else(|| eyre!("payload with no message"))?;
+
+    let mut txn = pool.begin().await?;
+
+    let part = PollPart::get_by_message(&mut txn, message.chat.id.0, message.id.0)
+        .await?
+        .ok_or_else(|| eyre!("got answer for unknown check-in message"))?;
+    let poll_id = part.poll_id;
+    let answers = PollDimensionAnswer::get_for_poll(&mut txn, poll_id).await?;
+    let values = |answers: &[PollDimensionAnswer]| -> Vec<Option<i32>> {
+        part.options
+            .iter()
+            .map(|dimension| {
+                answers
+                    .iter()
+                    .find(|answer| &answer.dimension == dimension)
+                    .map(|answer| answer.value)
+            })
+            .collect()
+    };
+
+    let (dimension, value) = match action {
+        CheckInAction::Done {
+            poll_id: action_poll_id,
+        } if action_poll_id == poll_id => {
+            let values = values(&answers);
+
+            if values.iter().any(Option::is_none) {
+                bot.answer_callback_query(&callback.id)
+                    .text(message!(locale, "mood_check_in/rate_all.md"))
+                    .await?;
+
+                return Ok(());
+            }
+
+            let poll = Poll::get_by_id(&mut txn, poll_id).await?;
+            txn.commit().await?;
+
+            let summary = part
+                .options
+                .iter()
+                .zip(values.into_iter().flatten())
+                .map(|(dimension, value)| format!("• {dimension}: {value}"))
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            bot.edit_message_text(
+                message.chat.id,
+                message.id,
+                md_message!(
+                    locale,
+                    "mood_check_in/answered.md",
+                    question = poll.kind.question(locale),
+                    answers = summary
+                ),
+            )
+            .await?;
+            bot.answer_callback_query(&callback.id).await?;
+
+            return poll_answered(bot, pool, &poll, locale).await;
+        }
+        CheckInAction::Label {
+            poll_id: action_poll_id,
+            dimension,
+        } if action_poll_id == poll_id && dimension < part.options.len() => {
+            bot.answer_callback_query(&callback.id)
+                .text(message!(locale, "mood_check_in/pick_score.md"))
+                .await?;
+
+            return Ok(());
+        }
+        CheckInAction::Rate {
+            poll_id: action_poll_id,
+            dimension,
+            value,
+        } if action_poll_id == poll_id
+            && dimension < part.options.len()
+            && (PollDimensionAnswer::MIN_VALUE..=PollDimensionAnswer::MAX_VALUE)
+                .contains(&value) =>
+        {
+            (dimension, value)
+        }
+        _ => return answer_stale(bot, callback, locale).await,
+    };
+
+    PollDimensionAnswer::save(&mut txn, &part, user_tg_id, dimension, value).await?;
+    let answers = PollDimensionAnswer::get_for_poll(&mut txn, poll_id).await?;
+    txn.commit().await?;
+
+    bot.edit_message_reply_markup(message.chat.id, message.id)
+        .reply_markup(check_in(poll_id, &part.options, &values(&answers), locale))
+        .await?;
+    bot.answer_callback_query(&callback.id).await?;
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use color_eyre::{eyre::eyre, Result};
+use teloxide::{payloads::SendMessageSetters, requests::Requester, types::Message};
+use tracing::info;
+
+use ilquentir_messages::{md_message, Locale};
+use ilquentir_models::{PgTransaction, Poll, PollChoice};
+
+use crate::bot::Bot;
+
+use super::super::keyboard::check_in;
+
+/// Sends check-in as a single message with rating keyboard for every dimension
+#[tracing::instrument(skip(bot, txn), err)]
+pub async fn send_check_in(
+    bot: &Bot,
+    txn: &mut PgTransaction<'_>,
+    poll: Poll,
+    dimensions: Vec<PollChoice>,
+    locale: Locale,
+) -> Result<Message> {
+    let poll_id = poll
+        .id
+        .ok_or_else(|| eyre!("trying to send unsaved check-in"))?;
+
+    let texts: Vec<_> = dimensions
+        .iter()
+        .map(|dimension| dimension.text.clone())
+        .collect();
+    let message = bot
+        .send_message(
+            poll.chat_tg_id.to_string(),
+            md_message!(
+                locale,
+                "mood_check_in/question.md",
+                question = poll.kind.question(locale)
+            ),
+        )
+        .reply_markup(check_in(poll_id, &texts, &[], locale))
+        .await?;
+
+    info!(poll_id, "check-in sent");
+
+    poll.published_as_keyboard(&mut *txn, &message, dimensions)
+        .await?;
+
+    Ok(message)
+}
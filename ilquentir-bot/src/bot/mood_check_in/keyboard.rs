@@ -0,0 +1,94 @@
+use color_eyre::Result;
+use teloxide::types::InlineKeyboardMarkup;
+
+use ilquentir_messages::{message, Locale};
+use ilquentir_models::{PgTransaction, PollDimensionAnswer, PollKind, PollOption};
+
+use crate::bot::{callbacks::buttons_row, settings};
+
+use super::options;
+
+const ENABLED: char = '✅';
+const DISABLED: char = '⬜';
+const NOT_RATED: char = '–';
+
+/// Settings keyboard, allowing to choose dimensions of the check-in
+#[tracing::instrument(skip(txn), err)]
+pub async fn user_dimensions(
+    txn: &mut PgTransaction<'_>,
+    chat_id: i64,
+    locale: Locale,
+) -> Result<InlineKeyboardMarkup> {
+    let current = PollKind::MoodCheckIn
+        .options(&mut *txn, chat_id, locale)
+        .await?;
+    let mut catalog = PollOption::get_catalog(&mut *txn, PollKind::MoodCheckIn).await?;
+    PollOption::translate(txn, &mut catalog, locale.code()).await?;
+    let rendered_options = catalog
+        .into_iter()
+        .map(|option| {
+            let enabled = current
+                .iter()
+                .any(|choice| choice.option_id == Some(option.id));
+            let mark = if enabled { ENABLED } else { DISABLED };
+
+            buttons_row![[
+                format!("{mark} {}", option.text),
+                options::dimension_button(option.id)
+            ]]
+        })
+        .chain([buttons_row![
+            [
+                message!(locale, "mood_check_in/buttons/disable.md"),
+                options::DISABLE_BUTTON
+            ],
+            [
+                message!(locale, "mood_check_in/buttons/enable.md"),
+                options::ENABLE_BUTTON
+            ]
+        ]])
+        .chain([settings::keyboard::back_to_menu(locale)]);
+
+    Ok(InlineKeyboardMarkup::new(rendered_options))
+}
+
+/// Check-in keyboard: for every dimension there is a row with its name
+/// and current rating, followed by a row of rating buttons.
+///
+/// `values` are current ratings in the order of `dimensions`.
+pub fn check_in(
+    poll_id: i64,
+    dimensions: &[String],
+    values: &[Option<i32>],
+    locale: Locale,
+) -> InlineKeyboardMarkup {
+    let rows = dimensions.iter().enumerate().flat_map(|(idx, dimension)| {
+        let current = values.get(idx).copied().flatten();
+        let label = match current {
+            Some(value) => format!("{dimension}: {value}"),
+            None => format!("{dimension}: {NOT_RATED}"),
+        };
+
+        let rating = (PollDimensionAnswer::MIN_VALUE..=PollDimensionAnswer::MAX_VALUE)
+            .map(|value| {
+                let text = if current == Some(value) {
+                    format!("•{value}•")
+                } else {
+                    value.to_string()
+                };
+
+                options::value_button(poll_id, idx, value).create_button(text)
+            })
+            .collect();
+
+        [
+            buttons_row![[label, options::label_button(poll_id, idx)]],
+            rating,
+        ]
+    });
+
+    InlineKeyboardMarkup::new(rows.chain([buttons_row![[
+        message!(locale, "mood_check_in/buttons/done.md"),
+        options::done_button(poll_id)
+    ]]]))
+}
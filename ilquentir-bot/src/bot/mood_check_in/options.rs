@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+use crate::bot::callbacks::CallbackData;
+
+/// Actions of the check-in settings keyboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SettingsAction {
+    /// Toggles dimension of the catalog
+    Toggle(i64),
+    Enable,
+    Disable,
+}
+
+/// Answers to the check-in message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CheckInAction {
+    Rate {
+        poll_id: i64,
+        dimension: usize,
+        value: i32,
+    },
+    /// Dimension name, does nothing but a hint
+    Label {
+        poll_id: i64,
+        dimension: usize,
+    },
+    Done {
+        poll_id: i64,
+    },
+}
+
+pub(super) fn dimension_button(option_id: i64) -> CallbackData {
+    CallbackData::MoodCheckInSettings(SettingsAction::Toggle(option_id))
+}
+
+pub(super) const ENABLE_BUTTON: CallbackData =
+    CallbackData::MoodCheckInSettings(SettingsAction::Enable);
+
+pub(super) const DISABLE_BUTTON: CallbackData =
+    CallbackData::MoodCheckInSettings(SettingsAction::Disable);
+
+pub(super) fn value_button(poll_id: i64, dimension: usize, value: i32) -> CallbackData {
+    CallbackData::MoodCheckIn(CheckInAction::Rate {
+        poll_id,
+        dimension,
+        value,
+    })
+}
+
+pub(super) fn label_button(poll_id: i64, dimension: usize) -> CallbackData {
+    CallbackData::MoodCheckIn(CheckInAction::Label { poll_id, dimension })
+}
+
+pub(super) fn done_button(poll_id: i64) -> CallbackData {
+    CallbackData::MoodCheckIn(CheckInAction::Done { poll_id })
+}
@@ -0,0 +1,95 @@
+use color_eyre::{eyre::eyre, Result};
+use teloxide::{
+    payloads::{EditMessageReplyMarkupSetters, SendMessageSetters},
+    requests::Requester,
+    types::{CallbackQuery, ForceReply},
+};
+use tracing::info;
+
+use ilquentir_messages::{message, Locale, Markup};
+use ilquentir_models::{Dialogue, DialogueState, MoodScale, MoodScaleLabelEdit, PgTransaction};
+
+use crate::bot::{callbacks::answer_stale, Bot};
+
+use super::super::{keyboard::scale_settings, options::ScaleAction};
+
+/// Question about the new name of the label, which is quoted literally
+fn rename_question(label: &str, locale: Locale) -> String {
+    Markup::new()
+        .text(message!(
+            locale,
+            "mood_scale/rename_label.md",
+            label = label,
+            max_len = MoodScale::MAX_LABEL_LEN
+        ))
+        .markdown_v2()
+}
+
+#[tracing::instrument(skip(bot, txn), err)]
+pub async fn handle_mood_scale_callback(
+    bot: &Bot,
+    txn: &mut PgTransaction<'_>,
+    callback: &CallbackQuery,
+    action: ScaleAction,
+    locale: Locale,
+) -> Result<()> {
+    let user_tg_id = callback.from.id.0 as i64;
+    let message = callback
+        .message
+        .as_ref()
+        .ok_or_else(|| eyre!("payload with no message"))?;
+    let scale = MoodScale::get(&mut *txn, user_tg_id, locale).await?;
+
+    let new_scale = match action {
+        ScaleAction::Label(label_idx) if label_idx < scale.labels.len() => {
+            let question = bot
+                .send_message(
+                    user_tg_id.to_string(),
+                    rename_question(&scale.labels[label_idx], locale),
+                )
+                .reply_markup(ForceReply::new())
+                .await?;
+
+            let state = DialogueState::MoodScaleLabelEdit(MoodScaleLabelEdit { label_idx });
+            Dialogue::start(&mut *txn, user_tg_id, state, question.id.0).await?;
+            bot.answer_callback_query(&callback.id).await?;
+
+            return Ok(());
+        }
+        ScaleAction::Reset => scale.reset_labels(&mut *txn, user_tg_id, locale).await?,
+        ScaleAction::Points(points) if points == scale.points => {
+            bot.answer_callback_query(&callback.id).await?;
+
+            return Ok(());
+        }
+        ScaleAction::Points(points) if MoodScale::SUPPORTED_POINTS.contains(&points) => {
+            MoodScale::set_points(&mut *txn, user_tg_id, points, locale).await?
+        }
+        _ => return answer_stale(bot, callback, locale).await,
+    };
+    info!(user_tg_id, points = new_scale.points, "mood scale changed");
+
+    bot.edit_message_reply_markup(user_tg_id.to_string(), message.id)
+        .reply_markup(scale_settings(&new_scale, locale))
+        .await?;
+    bot.answer_callback_query(&callback.id).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use ilquentir_messages::validate_markdown_v2;
+
+    use super::*;
+
+    #[test]
+    fn rename_question_quotes_label_literally() {
+        for locale in [Locale::Ru, Locale::En] {
+            let text = rename_question("_так себе_ :-) *", locale);
+
+            assert_eq!(validate_markdown_v2(&text), Ok(()));
+            assert!(text.contains(r"«\_так себе\_ :\-\) \*»"), "{text}");
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use color_eyre::Result;
+use teloxide::{
+    payloads::SendMessageSetters,
+    requests::Requester,
+    types::{ChatId, InlineKeyboardMarkup},
+};
+
+use ilquentir_messages::{md_message, Locale};
+use ilquentir_models::{MoodScale, PgTransaction};
+
+use crate::bot::Bot;
+
+use super::super::keyboard::scale_settings;
+
+#[tracing::instrument(skip(bot, txn), fields(chat_id=chat_id.0), err)]
+pub async fn handle_mood_scale_command(
+    bot: &Bot,
+    txn: &mut PgTransaction<'_>,
+    chat_id: ChatId,
+    locale: Locale,
+) -> Result<()> {
+    let (text, keyboard) = settings_view(txn, chat_id.0, locale).await?;
+
+    bot.send_message(chat_id, text)
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
+
+#[tracing::instrument(skip(txn), err)]
+pub async fn settings_view(
+    txn: &mut PgTransaction<'_>,
+    chat_id: i64,
+    locale: Locale,
+) -> Result<(String, InlineKeyboardMarkup)> {
+    let scale = MoodScale::get(txn, chat_id, locale).await?;
+
+    Ok((
+        md_message!(locale, "mood_scale/settings.md"),
+        scale_settings(&scale, locale),
+    ))
+}
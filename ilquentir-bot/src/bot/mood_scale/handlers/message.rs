@@ -0,0 +1,74 @@
+use color_eyre::Result;
+use sqlx::PgPool;
+use teloxide::{
+    payloads::SendMessageSetters,
+    requests::Requester,
+    types::{MediaText, Message},
+};
+use tracing::info;
+
+use ilquentir_messages::{md_message, Locale};
+use ilquentir_models::{Dialogue, MoodScale, MoodScaleLabelEdit};
+
+use crate::bot::Bot;
+
+use super::super::keyboard::scale_settings;
+
+#[tracing::instrument(skip(bot, pool, msg_text), err)]
+pub async fn save_label(
+    bot: Bot,
+    pool: PgPool,
+    msg: Message,
+    msg_text: MediaText,
+    dialogue: Dialogue,
+    edit: MoodScaleLabelEdit,
+    locale: Locale,
+) -> Result<()> {
+    let label = msg_text.text.trim();
+
+    if label.is_empty() || label.chars().count() > MoodScale::MAX_LABEL_LEN {
+        bot.send_message(
+            msg.chat.id,
+            md_message!(
+                locale,
+                "mood_scale/label_invalid.md",
+                max_len = MoodScale::MAX_LABEL_LEN
+            ),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    let mut txn = pool.begin().await?;
+
+    let user_tg_id = dialogue.chat_tg_id;
+    let scale = MoodScale::get(&mut txn, user_tg_id, locale).await?;
+    let label_idx = edit.label_idx;
+    dialogue.finish(&mut txn).await?;
+
+    let scale = if label_idx < scale.labels.len() {
+        let scale = scale
+            .rename_label(&mut txn, user_tg_id, label_idx, label)
+            .await?;
+        info!(user_tg_id, label_idx, "mood scale label renamed");
+
+        scale
+    } else {
+        // scale was changed while user was typing the label
+        info!(user_tg_id, label_idx, "renamed label doesn't exist anymore");
+
+        scale
+    };
+
+    bot.send_message(
+        msg.chat.id,
+        md_message!(locale, "mood_scale/label_saved.md"),
+    )
+    .reply_markup(scale_settings(&scale, locale))
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use teloxide::types::InlineKeyboardMarkup;
+
+use ilquentir_messages::{message, Locale};
+use ilquentir_models::MoodScale;
+
+use crate::bot::{callbacks::buttons_row, settings};
+
+use super::options;
+
+/// Settings keyboard: scale resolution in the first row, then one row per label
+pub fn scale_settings(scale: &MoodScale, locale: Locale) -> InlineKeyboardMarkup {
+    let points_row = MoodScale::SUPPORTED_POINTS
+        .into_iter()
+        .map(|points| {
+            let text = if points == scale.points {
+                format!("•{points}•")
+            } else {
+                points.to_string()
+            };
+
+            options::points_button(points).create_button(text)
+        })
+        .collect();
+
+    let label_rows = scale
+        .labels
+        .iter()
+        .enumerate()
+        .map(|(idx, label)| buttons_row![[format!("✏️ {label}"), options::label_button(idx)]]);
+
+    InlineKeyboardMarkup::new([points_row].into_iter().chain(label_rows).chain([
+        buttons_row![[
+            message!(locale, "mood_scale/buttons/reset.md"),
+            options::RESET_BUTTON
+        ]],
+        settings::keyboard::back_to_menu(locale),
+    ]))
+}
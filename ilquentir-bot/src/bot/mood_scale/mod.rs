@@ -0,0 +1,6 @@
+mod handlers;
+pub(super) use handlers::*;
+pub mod keyboard;
+pub(self) mod options;
+
+pub use options::ScaleAction;
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+use crate::bot::callbacks::CallbackData;
+
+/// Actions of the mood scale settings keyboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScaleAction {
+    Points(i32),
+    /// Asks for a new name of the label with the given index
+    Label(usize),
+    Reset,
+}
+
+pub(super) fn points_button(points: i32) -> CallbackData {
+    CallbackData::MoodScale(ScaleAction::Points(points))
+}
+
+pub(super) fn label_button(label_idx: usize) -> CallbackData {
+    CallbackData::MoodScale(ScaleAction::Label(label_idx))
+}
+
+pub(super) const RESET_BUTTON: CallbackData = CallbackData::MoodScale(ScaleAction::Reset);
@@ -0,0 +1,11 @@
+mod callback;
+pub use callback::*;
+
+mod command;
+pub use command::*;
+
+mod location;
+pub use location::handle_location;
+
+mod scheduled;
+pub use scheduled::*;
@@ -0,0 +1,125 @@
+use color_eyre::{eyre::eyre, Result};
+use teloxide::{
+    payloads::{EditMessageTextSetters, SendMessageSetters},
+    requests::Requester,
+    types::CallbackQuery,
+};
+
+use ilquentir_messages::{md_message, Locale};
+use ilquentir_models::{Dialogue, DialogueState, PgTransaction};
+
+use crate::bot::{callbacks::answer_stale, daily_events, settings::SUBSCRIPTIONS, Bot};
+
+use super::super::{keyboard, options::OnboardingAction, wizard};
+
+#[tracing::instrument(skip(bot, txn), err)]
+pub async fn handle_onboarding_callback(
+    bot: &Bot,
+    txn: &mut PgTransaction<'_>,
+    callback: &CallbackQuery,
+    action: OnboardingAction,
+    locale: Locale,
+) -> Result<()> {
+    let user_tg_id = callback.from.id.0 as i64;
+    let message = callback
+        .message
+        .as_ref()
+        .ok_or_else(|| eyre!("payload with no message"))?;
+    let dialogue = match Dialogue::get_onboarding(&mut *txn, user_tg_id).await? {
+        Some(dialogue) if dialogue.tg_message_id == message.id.0 => dialogue,
+        // already finished, by the user or with defaults, or continued in a newer message
+        _ => return answer_stale(bot, callback, locale).await,
+    };
+    let mut answers = dialogue
+        .state
+        .onboarding_answers()
+        .cloned()
+        .unwrap_or_default();
+
+    let state = match action {
+        OnboardingAction::Timezone(idx) => {
+            let name = if let Some(timezone) = wizard::TIMEZONES.get(idx) {
+                timezone
+            } else {
+                return answer_stale(bot, callback, locale).await;
+            };
+
+            answers.timezone = Some((*name).to_owned());
+
+            DialogueState::OnboardingTime(answers)
+        }
+        OnboardingAction::Location => {
+            // location is accepted until the timezone is picked in the wizard
+            Dialogue::start(
+                &mut *txn,
+                user_tg_id,
+                DialogueState::OnboardingLocation(answers),
+                message.id.0,
+            )
+            .await?;
+
+            bot.send_message(
+                message.chat.id,
+                md_message!(locale, "onboarding/location.md"),
+            )
+            .reply_markup(keyboard::location_request(locale))
+            .await?;
+            bot.answer_callback_query(&callback.id).await?;
+
+            return Ok(());
+        }
+        OnboardingAction::Time(time) => {
+            answers.send_at_local = Some(time);
+
+            DialogueState::OnboardingTrackers(answers)
+        }
+        OnboardingAction::Toggle(kind) if SUBSCRIPTIONS.contains(&kind) => {
+            if answers.trackers.contains(&kind) {
+                answers.trackers.retain(|&tracker| tracker != kind);
+            } else {
+                answers.trackers.push(kind);
+            }
+
+            DialogueState::OnboardingTrackers(answers)
+        }
+        OnboardingAction::Toggle(_) => return answer_stale(bot, callback, locale).await,
+        OnboardingAction::Finish => {
+            let completed = wizard::complete(txn, dialogue, locale).await?;
+
+            bot.edit_message_text(
+                message.chat.id,
+                message.id,
+                md_message!(
+                    locale,
+                    "onboarding/done.md",
+                    time = completed.time,
+                    timezone = completed.timezone,
+                    trackers = completed.trackers,
+                ),
+            )
+            .await?;
+            bot.answer_callback_query(&callback.id).await?;
+
+            if completed.daily_events {
+                let (text, keyboard) = daily_events::settings_view(txn, user_tg_id, locale).await?;
+
+                bot.send_message(message.chat.id, text)
+                    .reply_markup(keyboard)
+                    .await?;
+            }
+
+            return Ok(());
+        }
+    };
+
+    let (text, keyboard) =
+        wizard::render(&state, locale).ok_or_else(|| eyre!("dialogue isn't an onboarding"))?;
+    Dialogue::start(txn, user_tg_id, state, message.id.0).await?;
+
+    bot.edit_message_text(message.chat.id, message.id, text)
+        .reply_markup(keyboard)
+        .await?;
+    bot.answer_callback_query(&callback.id).await?;
+
+    Ok(())
+}
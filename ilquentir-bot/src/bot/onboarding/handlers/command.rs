@@ -0,0 +1,46 @@
+use color_eyre::{eyre::eyre, Result};
+use teloxide::{payloads::SendMessageSetters, requests::Requester, types::ChatId};
+use tracing::info;
+
+use ilquentir_messages::Locale;
+use ilquentir_models::{Dialogue, DialogueState, OnboardingAnswers, PgTransaction};
+
+use crate::bot::Bot;
+
+use super::super::wizard;
+
+/// Starts the wizard from the first question
+#[tracing::instrument(skip(bot, txn), fields(chat_id=chat_id.0), err)]
+pub async fn start_onboarding(
+    bot: &Bot,
+    txn: &mut PgTransaction<'_>,
+    chat_id: ChatId,
+    locale: Locale,
+) -> Result<()> {
+    let state = DialogueState::OnboardingTimezone(OnboardingAnswers::default());
+    send_current_step(bot, txn, chat_id, state, locale).await?;
+    info!(chat_id = chat_id.0, "started onboarding");
+
+    Ok(())
+}
+
+/// Sends the question of the given step, the wizard continues from the new message
+#[tracing::instrument(skip(bot, txn), fields(chat_id=chat_id.0), err)]
+pub async fn send_current_step(
+    bot: &Bot,
+    txn: &mut PgTransaction<'_>,
+    chat_id: ChatId,
+    state: DialogueState,
+    locale: Locale,
+) -> Result<()> {
+    let (text, keyboard) =
+        wizard::render(&state, locale).ok_or_else(|| eyre!("dialogue isn't an onboarding"))?;
+
+    let message = bot
+        .send_message(chat_id, text)
+        .reply_markup(keyboard)
+        .await?;
+    Dialogue::start(txn, chat_id.0, state, message.id.0).await?;
+
+    Ok(())
+}
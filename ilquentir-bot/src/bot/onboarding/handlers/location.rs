@@ -0,0 +1,55 @@
+use color_eyre::Result;
+use sqlx::PgPool;
+use teloxide::{
+    payloads::SendMessageSetters,
+    requests::Requester,
+    types::{Location, Message, ReplyMarkup},
+};
+use time_tz::TimeZone;
+use tracing::info;
+
+use ilquentir_messages::{md_message, Locale};
+use ilquentir_models::{DialogueState, OnboardingAnswers};
+
+use crate::bot::{setup_schedule, Bot};
+
+use super::{super::wizard, command::send_current_step};
+
+#[tracing::instrument(skip(bot, pool, msg), fields(chat_id = msg.chat.id.0), err)]
+pub async fn handle_location(
+    bot: Bot,
+    pool: PgPool,
+    msg: Message,
+    location: Location,
+    mut answers: OnboardingAnswers,
+    locale: Locale,
+) -> Result<()> {
+    let timezone = wizard::timezone_by_location(location.longitude);
+    info!(timezone = timezone.name(), "detected timezone by location");
+
+    answers.timezone = Some(timezone.name().to_owned());
+
+    bot.send_message(
+        msg.chat.id,
+        md_message!(
+            locale,
+            "onboarding/location_received.md",
+            offset = setup_schedule::format_offset(setup_schedule::current_offset(timezone))?,
+        ),
+    )
+    .reply_markup(ReplyMarkup::kb_remove())
+    .await?;
+
+    let mut txn = pool.begin().await?;
+    send_current_step(
+        &bot,
+        &mut txn,
+        msg.chat.id,
+        DialogueState::OnboardingTime(answers),
+        locale,
+    )
+    .await?;
+    txn.commit().await?;
+
+    Ok(())
+}
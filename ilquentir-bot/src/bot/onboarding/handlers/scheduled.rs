@@ -0,0 +1,50 @@
+use color_eyre::Result;
+use teloxide::{
+    payloads::SendMessageSetters,
+    requests::Requester,
+    types::{ChatId, ReplyMarkup},
+};
+use tracing::info;
+
+use ilquentir_messages::md_message;
+use ilquentir_models::{Dialogue, PgTransaction, User};
+
+use crate::bot::{language, Bot};
+
+use super::super::wizard;
+
+/// Finishes the wizard, abandoned by the user, with default answers
+#[tracing::instrument(skip(bot, txn), err)]
+pub async fn finish_abandoned(
+    bot: &Bot,
+    txn: &mut PgTransaction<'_>,
+    dialogue: Dialogue,
+) -> Result<()> {
+    let user_tg_id = dialogue.chat_tg_id;
+
+    if User::get_user_by_id(&mut *txn, user_tg_id).await?.is_none() {
+        info!(user_tg_id, "user has stopped the bot during onboarding");
+
+        return dialogue.finish(txn).await;
+    }
+
+    let locale = language::locale_of(&mut *txn, user_tg_id).await?;
+    let completed = wizard::complete(txn, dialogue, locale).await?;
+    info!(user_tg_id, "finished abandoned onboarding with defaults");
+
+    bot.send_message(
+        ChatId(user_tg_id),
+        md_message!(
+            locale,
+            "onboarding/defaults.md",
+            time = completed.time,
+            timezone = completed.timezone,
+            trackers = completed.trackers,
+        ),
+    )
+    // location request may be still shown
+    .reply_markup(ReplyMarkup::kb_remove())
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use teloxide::types::{ButtonRequest, InlineKeyboardMarkup, KeyboardButton, KeyboardMarkup};
+use time_tz::timezones;
+
+use ilquentir_messages::{message, Locale};
+use ilquentir_models::PollKind;
+
+use crate::bot::{
+    callbacks::buttons_row,
+    settings::{subscription_title, SUBSCRIPTIONS},
+    setup_schedule,
+};
+
+use super::{
+    options,
+    wizard::{city, TIMES, TIMEZONES},
+};
+
+pub(super) fn timezones(locale: Locale) -> InlineKeyboardMarkup {
+    let buttons = TIMEZONES
+        .iter()
+        .enumerate()
+        .map(|(idx, name)| {
+            let city = city(name, locale).unwrap_or_default();
+            let offset = timezones::get_by_name(name)
+                .map(setup_schedule::current_offset)
+                .and_then(|offset| setup_schedule::format_offset(offset).ok())
+                .unwrap_or_default();
+
+            options::timezone_button(idx).create_button(format!("{city} (UTC{offset})"))
+        })
+        .collect::<Vec<_>>();
+
+    InlineKeyboardMarkup::new(buttons.chunks(2).map(|row| row.to_vec()).chain([
+        buttons_row![[
+            message!(locale, "onboarding/buttons/location.md"),
+            options::LOCATION_BUTTON
+        ]],
+        buttons_row![[
+            message!(locale, "onboarding/buttons/skip.md"),
+            options::FINISH_BUTTON
+        ]],
+    ]))
+}
+
+pub(super) fn times(locale: Locale) -> InlineKeyboardMarkup {
+    let buttons = TIMES
+        .iter()
+        .map(|&time| options::time_button(time).create_button(setup_schedule::format_time(time)))
+        .collect::<Vec<_>>();
+
+    InlineKeyboardMarkup::new(
+        buttons
+            .chunks(3)
+            .map(|row| row.to_vec())
+            .chain([buttons_row![[
+                message!(locale, "onboarding/buttons/skip.md"),
+                options::FINISH_BUTTON
+            ]]]),
+    )
+}
+
+pub(super) fn trackers(chosen: &[PollKind], locale: Locale) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(
+        SUBSCRIPTIONS
+            .iter()
+            .map(|&kind| {
+                let mark = if chosen.contains(&kind) { '✅' } else { '⬜' };
+                let title = subscription_title(kind, locale).unwrap_or_default();
+
+                buttons_row![[format!("{mark} {title}"), options::toggle_button(kind)]]
+            })
+            .chain([buttons_row![[
+                message!(locale, "onboarding/buttons/done.md"),
+                options::FINISH_BUTTON
+            ]]]),
+    )
+}
+
+/// Reply keyboard, sharing user's location
+pub(super) fn location_request(locale: Locale) -> KeyboardMarkup {
+    KeyboardMarkup::new([[KeyboardButton::new(message!(
+        locale,
+        "onboarding/buttons/send_location.md"
+    ))
+    .request(ButtonRequest::Location)]])
+    .one_time_keyboard(true)
+    .resize_keyboard(true)
+}
@@ -0,0 +1,8 @@
+mod handlers;
+pub(self) mod keyboard;
+pub(self) mod options;
+pub(self) mod wizard;
+
+pub use handlers::finish_abandoned;
+pub(super) use handlers::*;
+pub use options::OnboardingAction;
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+use time::Time;
+
+use ilquentir_models::PollKind;
+
+use crate::bot::callbacks::CallbackData;
+
+/// Actions of the onboarding wizard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OnboardingAction {
+    /// Index of the timezone in [`super::wizard::TIMEZONES`]
+    Timezone(usize),
+    /// Asks to share the location to detect the timezone
+    Location,
+    Time(Time),
+    Toggle(PollKind),
+    /// Finishes the wizard, unanswered questions get default answers
+    Finish,
+}
+
+pub(super) fn timezone_button(idx: usize) -> CallbackData {
+    CallbackData::Onboarding(OnboardingAction::Timezone(idx))
+}
+
+pub(super) const LOCATION_BUTTON: CallbackData =
+    CallbackData::Onboarding(OnboardingAction::Location);
+
+pub(super) fn time_button(time: Time) -> CallbackData {
+    CallbackData::Onboarding(OnboardingAction::Time(time))
+}
+
+pub(super) fn toggle_button(kind: PollKind) -> CallbackData {
+    CallbackData::Onboarding(OnboardingAction::Toggle(kind))
+}
+
+pub(super) const FINISH_BUTTON: CallbackData = CallbackData::Onboarding(OnboardingAction::Finish);
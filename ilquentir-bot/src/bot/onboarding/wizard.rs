@@ -0,0 +1,192 @@
+use color_eyre::Result;
+use teloxide::types::InlineKeyboardMarkup;
+use time::{macros::time, Time};
+use time_tz::{timezones, TimeZone, Tz};
+use tracing::info;
+
+use ilquentir_messages::{md_message, message, Locale};
+use ilquentir_models::{
+    Dialogue, DialogueState, OnboardingAnswers, PgTransaction, Poll, PollKind, User,
+};
+
+use crate::bot::{
+    settings::{subscription_title, SUBSCRIPTIONS},
+    setup_schedule,
+};
+
+use super::keyboard;
+
+/// Timezones, offered in the wizard, see [`city`] for the names shown to the user
+pub(super) const TIMEZONES: [&str; 12] = [
+    "Europe/Kaliningrad",
+    "Europe/Moscow",
+    "Europe/Samara",
+    "Asia/Yekaterinburg",
+    "Asia/Novosibirsk",
+    "Asia/Vladivostok",
+    "Europe/Lisbon",
+    "Europe/Berlin",
+    "Europe/Belgrade",
+    "Asia/Tbilisi",
+    "Asia/Yerevan",
+    "Asia/Almaty",
+];
+
+/// City of the timezone, `None` if it isn't one of the [`TIMEZONES`]
+pub(super) fn city(timezone: &str, locale: Locale) -> Option<String> {
+    Some(match timezone {
+        "Europe/Kaliningrad" => message!(locale, "onboarding/cities/kaliningrad.md"),
+        "Europe/Moscow" => message!(locale, "onboarding/cities/moscow.md"),
+        "Europe/Samara" => message!(locale, "onboarding/cities/samara.md"),
+        "Asia/Yekaterinburg" => message!(locale, "onboarding/cities/yekaterinburg.md"),
+        "Asia/Novosibirsk" => message!(locale, "onboarding/cities/novosibirsk.md"),
+        "Asia/Vladivostok" => message!(locale, "onboarding/cities/vladivostok.md"),
+        "Europe/Lisbon" => message!(locale, "onboarding/cities/lisbon.md"),
+        "Europe/Berlin" => message!(locale, "onboarding/cities/berlin.md"),
+        "Europe/Belgrade" => message!(locale, "onboarding/cities/belgrade.md"),
+        "Asia/Tbilisi" => message!(locale, "onboarding/cities/tbilisi.md"),
+        "Asia/Yerevan" => message!(locale, "onboarding/cities/yerevan.md"),
+        "Asia/Almaty" => message!(locale, "onboarding/cities/almaty.md"),
+        _ => return None,
+    })
+}
+
+/// Time of the polls, offered in the wizard, other time can be picked via /setup_schedule
+pub(super) const TIMES: [Time; 6] = [
+    time!(09:00),
+    time!(12:00),
+    time!(19:00),
+    time!(21:00),
+    time!(22:00),
+    time!(23:00),
+];
+
+const DEFAULT_TIME: Time = time!(22:00);
+
+/// Timezone, which covers the given longitude.
+///
+/// Without a timezones map it's a rough guess, ignoring borders and daylight saving time,
+/// but it's good enough to pick the time of the polls.
+pub(super) fn timezone_by_location(longitude: f64) -> &'static Tz {
+    let offset_hours = (longitude / 15.).round().clamp(-12., 12.) as i32;
+    // signs of the `Etc/GMT` zones are inverted
+    let name = match offset_hours {
+        0 => "Etc/GMT".to_owned(),
+        _ => format!("Etc/GMT{:+}", -offset_hours),
+    };
+
+    timezones::get_by_name(&name).unwrap_or_else(setup_schedule::default_timezone)
+}
+
+fn timezone(answers: &OnboardingAnswers) -> &'static Tz {
+    answers
+        .timezone
+        .as_deref()
+        .and_then(timezones::get_by_name)
+        .unwrap_or_else(setup_schedule::default_timezone)
+}
+
+/// Question of the current step, `None` if the dialogue isn't an onboarding
+pub(super) fn render(
+    state: &DialogueState,
+    locale: Locale,
+) -> Option<(String, InlineKeyboardMarkup)> {
+    Some(match state {
+        // location request is sent as a separate message, the wizard still offers the timezones
+        DialogueState::OnboardingTimezone(_) | DialogueState::OnboardingLocation(_) => (
+            md_message!(locale, "onboarding/timezone.md"),
+            keyboard::timezones(locale),
+        ),
+        DialogueState::OnboardingTime(answers) => (
+            md_message!(
+                locale,
+                "onboarding/time.md",
+                timezone = timezone(answers).name()
+            ),
+            keyboard::times(locale),
+        ),
+        DialogueState::OnboardingTrackers(answers) => (
+            md_message!(locale, "onboarding/trackers.md"),
+            keyboard::trackers(&answers.trackers, locale),
+        ),
+        _ => return None,
+    })
+}
+
+/// Settings, saved at the end of the wizard
+#[derive(Debug, Clone)]
+pub(super) struct Completed {
+    pub time: String,
+    pub timezone: &'static str,
+    /// Names of the chosen polls, one per line
+    pub trackers: String,
+    pub daily_events: bool,
+}
+
+/// Saves the answers, filling the missing ones with defaults, and schedules the first polls
+#[tracing::instrument(skip(txn), err)]
+pub(super) async fn complete(
+    txn: &mut PgTransaction<'_>,
+    dialogue: Dialogue,
+    locale: Locale,
+) -> Result<Completed> {
+    let user_tg_id = dialogue.chat_tg_id;
+    let answers = dialogue
+        .state
+        .onboarding_answers()
+        .cloned()
+        .unwrap_or_default();
+    let timezone = timezone(&answers);
+    let send_at_local = answers.send_at_local.unwrap_or(DEFAULT_TIME);
+    let trackers = if answers.trackers.is_empty() {
+        vec![PollKind::HowWasYourDay]
+    } else {
+        answers.trackers
+    };
+
+    User::set_timezone(&mut *txn, user_tg_id, timezone.name()).await?;
+    let send_at_utc =
+        setup_schedule::to_utc(send_at_local, setup_schedule::current_offset(timezone));
+    setup_schedule::set_send_at(&mut *txn, user_tg_id, send_at_utc).await?;
+
+    for &kind in &trackers {
+        let poll = Poll::schedule_for_user(&mut *txn, user_tg_id, kind).await?;
+        info!(user_tg_id, poll_id = poll.id, %kind, "scheduled first poll");
+    }
+    dialogue.finish(txn).await?;
+
+    Ok(Completed {
+        time: setup_schedule::format_time(send_at_local),
+        timezone: timezone.name(),
+        trackers: SUBSCRIPTIONS
+            .into_iter()
+            .filter(|kind| trackers.contains(kind))
+            .filter_map(|kind| subscription_title(kind, locale))
+            .map(|title| format!("• {title}"))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        daily_events: trackers.contains(&PollKind::DailyEvents),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn location_timezones() {
+        // Moscow
+        assert_eq!(timezone_by_location(37.6).name(), "Etc/GMT-3");
+        // London
+        assert_eq!(timezone_by_location(-0.1).name(), "Etc/GMT");
+        // New York
+        assert_eq!(timezone_by_location(-74.).name(), "Etc/GMT+5");
+        // Chukotka, offsets beyond 12 hours are clamped
+        assert_eq!(timezone_by_location(179.9).name(), "Etc/GMT-12");
+
+        for name in TIMEZONES {
+            assert!(timezones::get_by_name(name).is_some(), "{name} is unknown");
+            assert!(city(name, Locale::En).is_some(), "{name} has no city");
+        }
+    }
+}
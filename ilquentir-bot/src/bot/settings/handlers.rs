@@ -0,0 +1,5 @@
+mod callback;
+pub use callback::handle_menu_callback;
+
+mod command;
+pub use command::handle_settings_command;
@@ -0,0 +1,68 @@
+use color_eyre::{eyre::eyre, Result};
+use teloxide::{payloads::EditMessageTextSetters, requests::Requester, types::CallbackQuery};
+use tracing::info;
+
+use ilquentir_messages::{md_message, Locale};
+use ilquentir_models::{PgTransaction, User};
+
+use crate::bot::{
+    daily_events, inline_poll, journal, language, mood_check_in, mood_scale, setup_schedule, Bot,
+};
+
+use super::super::{keyboard, menu, options::MenuAction};
+
+/// Navigates the settings menu, replacing the menu message with the chosen sub-editor
+#[tracing::instrument(skip(bot, txn), err)]
+pub async fn handle_menu_callback(
+    bot: &Bot,
+    txn: &mut PgTransaction<'_>,
+    callback: &CallbackQuery,
+    action: MenuAction,
+    locale: Locale,
+) -> Result<()> {
+    let user_tg_id = callback.from.id.0 as i64;
+    let message = callback
+        .message
+        .as_ref()
+        .ok_or_else(|| eyre!("payload with no message"))?;
+
+    let (text, keyboard) = match action {
+        MenuAction::Main => menu::render(txn, user_tg_id, locale).await?,
+        MenuAction::DailyEvents => daily_events::settings_view(txn, user_tg_id, locale).await?,
+        MenuAction::MoodCheckIn => mood_check_in::settings_view(txn, user_tg_id, locale).await?,
+        MenuAction::MoodScale => mood_scale::settings_view(txn, user_tg_id, locale).await?,
+        MenuAction::AnswerMode => inline_poll::answer_mode_view(txn, user_tg_id, locale).await?,
+        MenuAction::GratitudeJournal => {
+            journal::toggle_gratitude_journal(&mut *txn, user_tg_id).await?;
+
+            menu::render(txn, user_tg_id, locale).await?
+        }
+        MenuAction::Schedule => setup_schedule::time_picker_view(txn, user_tg_id, locale).await?,
+        MenuAction::Language => language::language_view(locale),
+        MenuAction::Stop => (
+            md_message!(locale, "settings/stop_confirmation.md"),
+            keyboard::stop_confirmation(locale),
+        ),
+        MenuAction::StopConfirmed => {
+            let user = User::deactivate(txn, user_tg_id).await?;
+            info!(user = user.tg_id, "disabled user from the settings menu");
+
+            bot.edit_message_text(
+                message.chat.id,
+                message.id,
+                md_message!(locale, "settings/stopped.md"),
+            )
+            .await?;
+            bot.answer_callback_query(&callback.id).await?;
+
+            return Ok(());
+        }
+    };
+
+    bot.edit_message_text(message.chat.id, message.id, text)
+        .reply_markup(keyboard)
+        .await?;
+    bot.answer_callback_query(&callback.id).await?;
+
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use color_eyre::Result;
+use teloxide::{payloads::SendMessageSetters, requests::Requester, types::ChatId};
+
+use ilquentir_messages::Locale;
+use ilquentir_models::PgTransaction;
+
+use crate::bot::Bot;
+
+use super::super::menu;
+
+#[tracing::instrument(skip(bot, txn), fields(chat_id=chat_id.0), err)]
+pub async fn handle_settings_command(
+    bot: &Bot,
+    txn: &mut PgTransaction<'_>,
+    chat_id: ChatId,
+    locale: Locale,
+) -> Result<()> {
+    let (text, keyboard) = menu::render(txn, chat_id.0, locale).await?;
+
+    bot.send_message(chat_id, text)
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
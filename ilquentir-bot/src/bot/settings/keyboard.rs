@@ -0,0 +1,71 @@
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
+
+use ilquentir_messages::{message, Locale};
+
+use crate::bot::callbacks::buttons_row;
+
+use super::options;
+
+/// Row, returning from any of the sub-editors to the settings menu
+pub fn back_to_menu(locale: Locale) -> Vec<InlineKeyboardButton> {
+    buttons_row![[
+        message!(locale, "settings/buttons/back_to_menu.md"),
+        options::MAIN_BUTTON
+    ]]
+}
+
+pub(super) fn menu(gratitude_journal_enabled: bool, locale: Locale) -> InlineKeyboardMarkup {
+    let gratitude_journal = if gratitude_journal_enabled {
+        message!(locale, "settings/buttons/gratitude_journal_off.md")
+    } else {
+        message!(locale, "settings/buttons/gratitude_journal_on.md")
+    };
+
+    InlineKeyboardMarkup::new([
+        buttons_row![
+            [
+                message!(locale, "settings/buttons/daily_events.md"),
+                options::DAILY_EVENTS_BUTTON
+            ],
+            [
+                message!(locale, "settings/buttons/mood_check_in.md"),
+                options::MOOD_CHECK_IN_BUTTON
+            ]
+        ],
+        buttons_row![
+            [
+                message!(locale, "settings/buttons/mood_scale.md"),
+                options::MOOD_SCALE_BUTTON
+            ],
+            [
+                message!(locale, "settings/buttons/answer_mode.md"),
+                options::ANSWER_MODE_BUTTON
+            ]
+        ],
+        buttons_row![[gratitude_journal, options::GRATITUDE_JOURNAL_BUTTON]],
+        buttons_row![
+            [
+                message!(locale, "settings/buttons/schedule.md"),
+                options::SCHEDULE_BUTTON
+            ],
+            [
+                message!(locale, "settings/buttons/language.md"),
+                options::LANGUAGE_BUTTON
+            ]
+        ],
+        buttons_row![[
+            message!(locale, "settings/buttons/stop.md"),
+            options::STOP_BUTTON
+        ]],
+    ])
+}
+
+pub(super) fn stop_confirmation(locale: Locale) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new([
+        buttons_row![[
+            message!(locale, "settings/buttons/stop_confirmed.md"),
+            options::STOP_CONFIRMED_BUTTON
+        ]],
+        back_to_menu(locale),
+    ])
+}
@@ -0,0 +1,75 @@
+use color_eyre::Result;
+use teloxide::types::InlineKeyboardMarkup;
+use time::macros::format_description;
+
+use ilquentir_messages::{md_message, message, Locale};
+use ilquentir_models::{MoodScale, PgTransaction, Poll, PollKind};
+
+use super::keyboard;
+
+/// Polls user can subscribe to, see [`subscription_title`] for their names
+pub const SUBSCRIPTIONS: [PollKind; 4] = [
+    PollKind::HowWasYourDay,
+    PollKind::DailyEvents,
+    PollKind::GratitudeJournal,
+    PollKind::MoodCheckIn,
+];
+
+/// Name of the subscription in menus, `None` for polls, which aren't [`SUBSCRIPTIONS`]
+pub fn subscription_title(kind: PollKind, locale: Locale) -> Option<String> {
+    Some(match kind {
+        PollKind::HowWasYourDay => message!(locale, "settings/subscriptions/how_was_your_day.md"),
+        PollKind::DailyEvents => message!(locale, "settings/subscriptions/daily_events.md"),
+        PollKind::GratitudeJournal => {
+            message!(locale, "settings/subscriptions/gratitude_journal.md")
+        }
+        PollKind::MoodCheckIn => message!(locale, "settings/subscriptions/mood_check_in.md"),
+        PollKind::FoodAllergy => return None,
+    })
+}
+
+/// Menu text with the summary of the current settings, and its keyboard
+#[tracing::instrument(skip(txn), err)]
+pub(super) async fn render(
+    txn: &mut PgTransaction<'_>,
+    user_tg_id: i64,
+    locale: Locale,
+) -> Result<(String, InlineKeyboardMarkup)> {
+    let mut subscriptions = Vec::with_capacity(SUBSCRIPTIONS.len());
+    let mut gratitude_journal_enabled = false;
+
+    for kind in SUBSCRIPTIONS {
+        let title = subscription_title(kind, locale).unwrap_or_default();
+        let next_publication = Poll::get_scheduled_for_user(&mut *txn, user_tg_id, kind)
+            .await?
+            .into_iter()
+            .map(|poll| poll.publication_date)
+            .min();
+
+        subscriptions.push(match next_publication {
+            Some(date) => message!(
+                locale,
+                "settings/subscription_enabled.md",
+                title = title,
+                time = date.time().format(format_description!("[hour]:[minute]"))?
+            ),
+            None => message!(locale, "settings/subscription_disabled.md", title = title),
+        });
+
+        if kind == PollKind::GratitudeJournal {
+            gratitude_journal_enabled = next_publication.is_some();
+        }
+    }
+
+    let scale = MoodScale::get(txn, user_tg_id, locale).await?;
+
+    Ok((
+        md_message!(
+            locale,
+            "settings/menu.md",
+            subscriptions = subscriptions.join("\n"),
+            scale_points = scale.points
+        ),
+        keyboard::menu(gratitude_journal_enabled, locale),
+    ))
+}
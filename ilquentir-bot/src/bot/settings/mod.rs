@@ -0,0 +1,8 @@
+mod handlers;
+pub(super) use handlers::*;
+pub mod keyboard;
+pub(self) mod menu;
+pub(self) mod options;
+
+pub use menu::{subscription_title, SUBSCRIPTIONS};
+pub use options::MenuAction;
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+use crate::bot::callbacks::CallbackData;
+
+/// Actions of the settings menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MenuAction {
+    /// Shows the menu with the summary of all the settings
+    Main,
+    DailyEvents,
+    MoodCheckIn,
+    MoodScale,
+    AnswerMode,
+    Schedule,
+    GratitudeJournal,
+    /// Asks, if user really wants to stop the bot
+    Stop,
+    StopConfirmed,
+    Language,
+}
+
+pub(super) const MAIN_BUTTON: CallbackData = CallbackData::Settings(MenuAction::Main);
+
+pub(super) const DAILY_EVENTS_BUTTON: CallbackData =
+    CallbackData::Settings(MenuAction::DailyEvents);
+
+pub(super) const MOOD_CHECK_IN_BUTTON: CallbackData =
+    CallbackData::Settings(MenuAction::MoodCheckIn);
+
+pub(super) const MOOD_SCALE_BUTTON: CallbackData = CallbackData::Settings(MenuAction::MoodScale);
+
+pub(super) const ANSWER_MODE_BUTTON: CallbackData = CallbackData::Settings(MenuAction::AnswerMode);
+
+pub(super) const SCHEDULE_BUTTON: CallbackData = CallbackData::Settings(MenuAction::Schedule);
+
+pub(super) const GRATITUDE_JOURNAL_BUTTON: CallbackData =
+    CallbackData::Settings(MenuAction::GratitudeJournal);
+
+pub(super) const STOP_BUTTON: CallbackData = CallbackData::Settings(MenuAction::Stop);
+
+pub(super) const STOP_CONFIRMED_BUTTON: CallbackData =
+    CallbackData::Settings(MenuAction::StopConfirmed);
+
+pub(super) const LANGUAGE_BUTTON: CallbackData = CallbackData::Settings(MenuAction::Language);
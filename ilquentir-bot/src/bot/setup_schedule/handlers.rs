@@ -1,5 +1,8 @@
+mod callback;
+pub use callback::*;
+
 mod command;
-pub use command::handle_setup_schedule_command;
+pub use command::*;
 
 mod web_app;
-pub use web_app::handle_webapp;
+pub use web_app::*;
@@ -0,0 +1,68 @@
+use color_eyre::{eyre::eyre, Result};
+use teloxide::{
+    payloads::EditMessageTextSetters,
+    requests::Requester,
+    types::{CallbackQuery, InlineKeyboardMarkup},
+};
+use tracing::info;
+
+use ilquentir_config::Config;
+use ilquentir_messages::Locale;
+use ilquentir_models::PgTransaction;
+
+use crate::bot::{settings, Bot};
+
+use super::{
+    super::{
+        options::TimePickerAction,
+        schedule::{current_offset, done_message, set_send_at, to_utc, user_timezone},
+    },
+    command::{render_time_picker, send_web_app_picker},
+};
+
+#[tracing::instrument(skip(bot, config, txn), err)]
+pub async fn handle_time_picker_callback(
+    bot: &Bot,
+    config: &Config,
+    txn: &mut PgTransaction<'_>,
+    callback: &CallbackQuery,
+    action: TimePickerAction,
+    locale: Locale,
+) -> Result<()> {
+    let user_tg_id = callback.from.id.0 as i64;
+    let message = callback
+        .message
+        .as_ref()
+        .ok_or_else(|| eyre!("payload with no message"))?;
+    let timezone = user_timezone(&mut *txn, user_tg_id).await?;
+
+    let (text, keyboard) = match action {
+        TimePickerAction::Pick(time) => render_time_picker(timezone, time, locale)?,
+        TimePickerAction::Save(time) => {
+            let offset = current_offset(timezone);
+            let send_at_utc = to_utc(time, offset);
+
+            set_send_at(txn, user_tg_id, send_at_utc).await?;
+            info!(user_tg_id, %send_at_utc, "schedule changed with the inline picker");
+
+            (
+                done_message(time, offset, locale)?,
+                InlineKeyboardMarkup::new([settings::keyboard::back_to_menu(locale)]),
+            )
+        }
+        TimePickerAction::WebApp => {
+            // WebApp can send data back only when opened from the reply keyboard
+            send_web_app_picker(bot, config, message.chat.id, locale).await?;
+            bot.answer_callback_query(&callback.id).await?;
+
+            return Ok(());
+        }
+    };
+
+    bot.edit_message_text(message.chat.id, message.id, text)
+        .reply_markup(keyboard)
+        .await?;
+    bot.answer_callback_query(&callback.id).await?;
+
+    Ok(())
+}
@@ -1,21 +1,80 @@
 use color_eyre::Result;
-use teloxide::{payloads::SendMessageSetters, requests::Requester, types::ChatId};
-// use tracing::info;
+use teloxide::{
+    payloads::SendMessageSetters,
+    requests::Requester,
+    types::{ChatId, InlineKeyboardMarkup},
+};
+use time::Time;
+use time_tz::{TimeZone, Tz};
 
-use ilquentir_messages::md;
+use ilquentir_config::Config;
+use ilquentir_messages::{md_message, Locale};
+use ilquentir_models::PgTransaction;
 
 use crate::bot::Bot;
 
-use super::super::keyboard::create_timepicker_keyboard;
+use super::super::{
+    keyboard::{create_timepicker_keyboard, time_picker},
+    schedule::{current_offset, format_offset, format_time, local_schedule},
+};
 
-#[tracing::instrument(skip(bot), fields(chat_id=chat_id.0), err)]
-pub async fn handle_setup_schedule_command(bot: &Bot, chat_id: ChatId) -> Result<()> {
-    bot.send_message(
-        chat_id,
-        md!("Выбери, в какое время (по твоему часовому поясу) присылать опросы за день:"),
-    )
-    .reply_markup(create_timepicker_keyboard())
-    .await?;
+#[tracing::instrument(skip(bot, txn), fields(chat_id=chat_id.0), err)]
+pub async fn handle_setup_schedule_command(
+    bot: &Bot,
+    txn: &mut PgTransaction<'_>,
+    chat_id: ChatId,
+    locale: Locale,
+) -> Result<()> {
+    let (text, keyboard) = time_picker_view(txn, chat_id.0, locale).await?;
+
+    bot.send_message(chat_id, text)
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
+
+/// Sends the button, opening the WebApp time picker
+#[tracing::instrument(skip(bot, config), fields(chat_id=chat_id.0), err)]
+pub async fn send_web_app_picker(
+    bot: &Bot,
+    config: &Config,
+    chat_id: ChatId,
+    locale: Locale,
+) -> Result<()> {
+    bot.send_message(chat_id, md_message!(locale, "settings/web_app_prompt.md"))
+        .reply_markup(create_timepicker_keyboard(config, locale))
+        .await?;
 
     Ok(())
 }
+
+/// Inline time picker, showing the current time of the polls
+#[tracing::instrument(skip(txn), err)]
+pub async fn time_picker_view(
+    txn: &mut PgTransaction<'_>,
+    chat_id: i64,
+    locale: Locale,
+) -> Result<(String, InlineKeyboardMarkup)> {
+    let schedule = local_schedule(txn, chat_id).await?;
+
+    render_time_picker(schedule.timezone, schedule.time, locale)
+}
+
+/// Inline time picker with the given time, which is not saved yet
+pub(in super::super) fn render_time_picker(
+    timezone: &Tz,
+    time: Time,
+    locale: Locale,
+) -> Result<(String, InlineKeyboardMarkup)> {
+    Ok((
+        md_message!(
+            locale,
+            "settings/time_picker.md",
+            time = format_time(time),
+            timezone = timezone.name(),
+            offset = format_offset(current_offset(timezone))?,
+        ),
+        time_picker(time, locale),
+    ))
+}
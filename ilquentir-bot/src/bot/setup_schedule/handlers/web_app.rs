@@ -1,74 +1,84 @@
-use color_eyre::{eyre::eyre, Result};
+use color_eyre::Result;
 use sqlx::PgPool;
 use teloxide::{
     payloads::SendMessageSetters,
     requests::Requester,
-    types::{Message, MessageWebAppData, ReplyMarkup},
+    types::{ChatId, Message, MessageWebAppData, ReplyMarkup},
 };
-use time::{ext::NumericalDuration, macros::format_description, OffsetDateTime, UtcOffset};
+use time_tz::TimeZone;
+use tracing::{info, warn};
 
-use ilquentir_messages::md_message;
-use ilquentir_models::{Poll, PollKind, PollSettings};
+use ilquentir_config::Config;
+use ilquentir_messages::Locale;
+use ilquentir_models::{PgTransaction, User};
 
 use crate::bot::Bot;
 
+use super::super::{
+    keyboard::create_timepicker_keyboard,
+    schedule::{done_message, set_send_at},
+    web_app_data::{self, PickedTime},
+};
+
+#[tracing::instrument(skip(bot, pool, config, msg), fields(chat_id=msg.chat.id.0), err)]
 pub async fn handle_webapp(
     bot: Bot,
     pool: PgPool,
+    config: Config,
     msg: Message,
     data: MessageWebAppData,
+    locale: Locale,
 ) -> Result<()> {
-    let (timestamp, offset) = data
-        .web_app_data
-        .data
-        .split_once('_')
-        .ok_or_else(|| eyre!("unknown WebApp data format"))?;
+    let picked = match web_app_data::parse(&data.web_app_data.data) {
+        Ok(picked) => picked,
+        Err(error) => {
+            warn!(?error, "got invalid WebApp data");
 
-    let offset: i32 = -(offset.parse()?);
-    let offset = UtcOffset::from_whole_seconds(offset * 60)?;
+            // the keyboard is hidden after use, send it again to let user retry
+            bot.send_message(msg.chat.id, error.reply(locale))
+                .reply_markup(create_timepicker_keyboard(&config, locale))
+                .await?;
 
-    let timestamp_nanos = timestamp.parse::<i128>()? * 1000000;
-    let send_at_datetime = OffsetDateTime::from_unix_timestamp_nanos(timestamp_nanos)?;
-    let send_at_utc = send_at_datetime.time();
+            return Ok(());
+        }
+    };
 
     let mut txn = pool.begin().await?;
+    save_picked_time(&mut txn, msg.chat.id.0, &picked).await?;
+    txn.commit().await?;
 
-    for kind in [PollKind::DailyEvents, PollKind::HowWasYourDay] {
-        PollSettings::set_send_at(&mut txn, msg.chat.id.0, kind, send_at_utc).await?;
-
-        for mut poll in Poll::get_scheduled_for_user(&mut txn, msg.chat.id.0, kind).await? {
-            let new_publication_date = {
-                let new_publication_date = poll.publication_date.replace_time(send_at_utc);
+    send_schedule_done(&bot, msg.chat.id, &picked, locale).await?;
 
-                if new_publication_date < OffsetDateTime::now_utc() {
-                    new_publication_date + 1.days()
-                } else {
-                    new_publication_date
-                }
-            };
+    Ok(())
+}
 
-            poll.publication_date = new_publication_date;
-            poll.update(&mut txn).await?;
-        }
+/// Saves the time, picked in the WebApp, together with user's timezone
+#[tracing::instrument(skip(txn), err)]
+pub async fn save_picked_time(
+    txn: &mut PgTransaction<'_>,
+    chat_id: i64,
+    picked: &PickedTime,
+) -> Result<()> {
+    if let Some(timezone) = picked.timezone {
+        User::set_timezone(&mut *txn, chat_id, timezone.name()).await?;
     }
+    set_send_at(txn, chat_id, picked.send_at_utc).await?;
+    info!(send_at_utc = %picked.send_at_utc, "schedule changed with the WebApp");
 
-    txn.commit().await?;
+    Ok(())
+}
 
-    bot.send_message(
-        msg.chat.id,
-        md_message!(
-            "settings/setup_schedule_done.md",
-            time = send_at_datetime
-                .to_offset(offset)
-                .time()
-                .format(format_description!("[hour]:[minute]"))?,
-            offset = offset.format(format_description!(
-                "[offset_hour sign:mandatory]:[offset_minute]"
-            ))?,
-        ),
-    )
-    .reply_markup(ReplyMarkup::kb_remove())
-    .await?;
+/// Confirms the new schedule in the chat and hides the WebApp keyboard
+#[tracing::instrument(skip(bot), fields(chat_id=chat_id.0), err)]
+pub async fn send_schedule_done(
+    bot: &Bot,
+    chat_id: ChatId,
+    picked: &PickedTime,
+    locale: Locale,
+) -> Result<()> {
+    bot.send_message(chat_id, done_message(picked.local, picked.offset, locale)?)
+        .reply_markup(ReplyMarkup::kb_remove())
+        .await?;
 
     Ok(())
 }
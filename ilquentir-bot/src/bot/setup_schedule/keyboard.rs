@@ -1,15 +1,77 @@
-use teloxide::types::{ButtonRequest, KeyboardButton, KeyboardMarkup, WebAppInfo};
+use teloxide::types::{
+    ButtonRequest, InlineKeyboardMarkup, KeyboardButton, KeyboardMarkup, WebAppInfo,
+};
+use time::{ext::NumericalDuration, Time};
 
-pub fn create_timepicker_keyboard() -> KeyboardMarkup {
-    let button = KeyboardButton::new("Выбрать время ежедневного опроса").request(
-        ButtonRequest::WebApp(WebAppInfo {
-            url: "https://expented.github.io/tgdtp/?hide=date&text=SELECT%20TIME"
-                .parse()
-                .unwrap(),
-        }),
-    );
+use ilquentir_config::Config;
+use ilquentir_messages::{message, Locale};
+
+use crate::bot::{callbacks::buttons_row, settings};
+
+use super::{options, schedule::format_time};
+
+/// Step of the minutes buttons of the time picker
+const MINUTES_STEP: i64 = 15;
+
+/// Opens the bot's own Mini App, if it's configured, or the external time picker otherwise
+pub fn create_timepicker_keyboard(config: &Config, locale: Locale) -> KeyboardMarkup {
+    let url = config.mini_app.url.clone().unwrap_or_else(|| {
+        "https://expented.github.io/tgdtp/?hide=date&text=SELECT%20TIME"
+            .parse()
+            .unwrap()
+    });
+    let button = KeyboardButton::new(message!(locale, "settings/buttons/web_app_time_picker.md"))
+        .request(ButtonRequest::WebApp(WebAppInfo { url }));
 
     KeyboardMarkup::new([[button]])
         .one_time_keyboard(true)
         .resize_keyboard(true)
 }
+
+/// In-chat time picker: hour and minutes steppers around the given time
+pub(super) fn time_picker(time: Time, locale: Locale) -> InlineKeyboardMarkup {
+    // `Time` wraps around midnight, so 23:00 + 1 hour is 00:00
+    InlineKeyboardMarkup::new([
+        buttons_row![
+            [
+                message!(locale, "settings/buttons/hour_back.md"),
+                options::pick_button(time - 1.hours())
+            ],
+            [
+                message!(locale, "settings/buttons/hour_forward.md"),
+                options::pick_button(time + 1.hours())
+            ]
+        ],
+        buttons_row![
+            [
+                message!(
+                    locale,
+                    "settings/buttons/minutes_back.md",
+                    minutes = MINUTES_STEP
+                ),
+                options::pick_button(time - MINUTES_STEP.minutes())
+            ],
+            [
+                message!(
+                    locale,
+                    "settings/buttons/minutes_forward.md",
+                    minutes = MINUTES_STEP
+                ),
+                options::pick_button(time + MINUTES_STEP.minutes())
+            ]
+        ],
+        buttons_row![[
+            message!(
+                locale,
+                "settings/buttons/save_time.md",
+                time = format_time(time)
+            ),
+            options::save_button(time)
+        ]],
+        buttons_row![[
+            message!(locale, "settings/buttons/web_app.md"),
+            options::WEB_APP_BUTTON
+        ]],
+        settings::keyboard::back_to_menu(locale),
+    ])
+}
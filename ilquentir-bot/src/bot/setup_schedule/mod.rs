@@ -1,4 +1,14 @@
 mod handlers;
-pub(super) use handlers::{handle_setup_schedule_command, handle_webapp};
+pub(super) use handlers::*;
 
 mod keyboard;
+pub(self) mod options;
+pub(self) mod schedule;
+pub(self) mod web_app_data;
+
+pub use options::TimePickerAction;
+pub use schedule::{
+    current_offset, default_timezone, format_offset, format_time, local_schedule, set_send_at,
+    to_utc, user_timezone, LocalSchedule,
+};
+pub use web_app_data::{parse as parse_web_app_data, PayloadError, PickedTime};
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+use time::Time;
+
+use crate::bot::callbacks::CallbackData;
+
+/// Actions of the inline time picker, the picked time (in user's timezone) travels
+/// in the buttons themselves
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimePickerAction {
+    /// Shows the picker with the given time
+    Pick(Time),
+    Save(Time),
+    /// Sends the WebApp button, which also detects the timezone
+    WebApp,
+}
+
+pub(super) fn pick_button(time: Time) -> CallbackData {
+    CallbackData::Schedule(TimePickerAction::Pick(time))
+}
+
+pub(super) fn save_button(time: Time) -> CallbackData {
+    CallbackData::Schedule(TimePickerAction::Save(time))
+}
+
+pub(super) const WEB_APP_BUTTON: CallbackData = CallbackData::Schedule(TimePickerAction::WebApp);
@@ -0,0 +1,126 @@
+use color_eyre::Result;
+use time::{
+    ext::NumericalDuration, macros::format_description, macros::time, OffsetDateTime, Time,
+    UtcOffset,
+};
+use time_tz::{timezones, Offset, TimeZone, Tz};
+
+use ilquentir_messages::{md_message, Locale};
+use ilquentir_models::{PgTransaction, Poll, PollKind, PollSettings, User};
+
+/// Timezone of the users, who haven't shared theirs yet
+const DEFAULT_TIMEZONE: &str = "Europe/Moscow";
+/// Polls are sent at 22:00 MSK, until user picks another time
+const DEFAULT_SEND_AT_UTC: Time = time!(19:00);
+
+/// Polls, which are sent at the time picked by the user
+const SCHEDULED_KINDS: [PollKind; 4] = [
+    PollKind::DailyEvents,
+    PollKind::HowWasYourDay,
+    PollKind::GratitudeJournal,
+    PollKind::MoodCheckIn,
+];
+
+/// Timezone, in which user picks the time of the polls
+#[tracing::instrument(skip(txn), err)]
+pub async fn user_timezone(txn: &mut PgTransaction<'_>, user_tg_id: i64) -> Result<&'static Tz> {
+    let timezone = User::get_timezone(txn, user_tg_id)
+        .await?
+        .and_then(|name| timezones::get_by_name(&name));
+
+    Ok(timezone.unwrap_or_else(default_timezone))
+}
+
+pub fn default_timezone() -> &'static Tz {
+    timezones::get_by_name(DEFAULT_TIMEZONE).expect("default timezone is always known")
+}
+
+/// UTC offset of the timezone, which is in effect right now
+pub fn current_offset(timezone: &Tz) -> UtcOffset {
+    timezone.get_offset_utc(&OffsetDateTime::now_utc()).to_utc()
+}
+
+pub fn to_utc(local: Time, offset: UtcOffset) -> Time {
+    OffsetDateTime::now_utc()
+        .to_offset(offset)
+        .replace_time(local)
+        .to_offset(UtcOffset::UTC)
+        .time()
+}
+
+/// Time of the polls in user's timezone
+#[derive(Debug, Clone, Copy)]
+pub struct LocalSchedule {
+    pub time: Time,
+    pub timezone: &'static Tz,
+    pub offset: UtcOffset,
+}
+
+#[tracing::instrument(skip(txn), err)]
+pub async fn local_schedule(txn: &mut PgTransaction<'_>, user_tg_id: i64) -> Result<LocalSchedule> {
+    let timezone = user_timezone(&mut *txn, user_tg_id).await?;
+    let offset = current_offset(timezone);
+
+    let send_at_utc = PollSettings::get(txn, user_tg_id, PollKind::HowWasYourDay)
+        .await?
+        .and_then(|settings| settings.send_at_utc)
+        .unwrap_or(DEFAULT_SEND_AT_UTC);
+
+    Ok(LocalSchedule {
+        time: OffsetDateTime::now_utc()
+            .replace_time(send_at_utc)
+            .to_offset(offset)
+            .time(),
+        timezone,
+        offset,
+    })
+}
+
+/// Saves the new time of the polls and moves already scheduled ones to it
+#[tracing::instrument(skip(txn), err)]
+pub async fn set_send_at(
+    txn: &mut PgTransaction<'_>,
+    user_tg_id: i64,
+    send_at_utc: Time,
+) -> Result<()> {
+    for kind in SCHEDULED_KINDS {
+        PollSettings::set_send_at(&mut *txn, user_tg_id, kind, send_at_utc).await?;
+
+        for mut poll in Poll::get_scheduled_for_user(&mut *txn, user_tg_id, kind).await? {
+            let new_publication_date = {
+                let new_publication_date = poll.publication_date.replace_time(send_at_utc);
+
+                if new_publication_date < OffsetDateTime::now_utc() {
+                    new_publication_date + 1.days()
+                } else {
+                    new_publication_date
+                }
+            };
+
+            poll.publication_date = new_publication_date;
+            poll.update(&mut *txn).await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn format_time(time: Time) -> String {
+    format!("{:02}:{:02}", time.hour(), time.minute())
+}
+
+pub fn format_offset(offset: UtcOffset) -> Result<String> {
+    Ok(offset.format(format_description!(
+        "[offset_hour sign:mandatory]:[offset_minute]"
+    ))?)
+}
+
+/// Confirmation of the new schedule, `local` is the picked time in the given offset
+pub(super) fn done_message(local: Time, offset: UtcOffset, locale: Locale) -> Result<String> {
+    Ok(md_message!(
+        locale,
+        "settings/setup_schedule_done.md",
+        time = format_time(local),
+        offset = format_offset(offset)?,
+    ))
+}
@@ -0,0 +1,191 @@
+use serde::Deserialize;
+use time::{macros::format_description, OffsetDateTime, Time, UtcOffset};
+use time_tz::{timezones, Tz};
+
+use ilquentir_messages::{md_message, Locale};
+
+use super::schedule::{current_offset, to_utc};
+
+/// Version of the JSON payload, sent by the WebApp
+pub const PAYLOAD_VERSION: u64 = 1;
+
+/// Time of the polls, picked in the WebApp
+#[derive(Debug, Clone, Copy)]
+pub struct PickedTime {
+    pub send_at_utc: Time,
+    /// Picked time in user's timezone
+    pub local: Time,
+    pub offset: UtcOffset,
+    /// `None` for the legacy payload, which has only the offset
+    pub timezone: Option<&'static Tz>,
+}
+
+/// Why the WebApp data was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PayloadError {
+    Malformed,
+    UnsupportedVersion(u64),
+    InvalidTime(String),
+    UnknownTimezone(String),
+}
+
+impl PayloadError {
+    /// Machine-readable reason, reported by the Mini App API
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Malformed => "malformed",
+            Self::UnsupportedVersion(_) => "unsupported_version",
+            Self::InvalidTime(_) => "invalid_time",
+            Self::UnknownTimezone(_) => "unknown_timezone",
+        }
+    }
+
+    /// Explanation for the user
+    pub fn reply(&self, locale: Locale) -> String {
+        match self {
+            Self::Malformed => md_message!(locale, "settings/web_app_malformed.md"),
+            Self::UnsupportedVersion(version) => {
+                md_message!(
+                    locale,
+                    "settings/web_app_unsupported_version.md",
+                    version = version
+                )
+            }
+            Self::InvalidTime(time) => {
+                md_message!(locale, "settings/web_app_invalid_time.md", time = time)
+            }
+            Self::UnknownTimezone(timezone) => {
+                md_message!(
+                    locale,
+                    "settings/web_app_unknown_timezone.md",
+                    timezone = timezone
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Versioned {
+    version: u64,
+}
+
+/// `{"version": 1, "time": "22:30", "timezone": "Europe/Moscow"}`, where time is
+/// in the given IANA timezone
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PayloadV1 {
+    #[allow(dead_code)]
+    version: u64,
+    time: String,
+    timezone: String,
+}
+
+/// Parses data, sent by the WebApp.
+///
+/// Besides the JSON payload, accepts the legacy `<unix timestamp in ms>_<JS timezone offset>`
+/// format of the external time picker.
+pub fn parse(data: &str) -> Result<PickedTime, PayloadError> {
+    if !data.starts_with('{') {
+        return parse_legacy(data);
+    }
+
+    let Versioned { version } = serde_json::from_str(data).map_err(|_| PayloadError::Malformed)?;
+    if version != PAYLOAD_VERSION {
+        return Err(PayloadError::UnsupportedVersion(version));
+    }
+
+    let payload: PayloadV1 = serde_json::from_str(data).map_err(|_| PayloadError::Malformed)?;
+
+    let local = Time::parse(&payload.time, format_description!("[hour]:[minute]"))
+        .map_err(|_| PayloadError::InvalidTime(payload.time.clone()))?;
+    let timezone = timezones::get_by_name(&payload.timezone)
+        .ok_or_else(|| PayloadError::UnknownTimezone(payload.timezone.clone()))?;
+    let offset = current_offset(timezone);
+
+    Ok(PickedTime {
+        send_at_utc: to_utc(local, offset),
+        local,
+        offset,
+        timezone: Some(timezone),
+    })
+}
+
+fn parse_legacy(data: &str) -> Result<PickedTime, PayloadError> {
+    let (timestamp, offset) = data.split_once('_').ok_or(PayloadError::Malformed)?;
+
+    let offset = offset
+        .parse::<i32>()
+        .ok()
+        // JS offset is in minutes and has the opposite sign
+        .and_then(|offset| UtcOffset::from_whole_seconds(-offset * 60).ok())
+        .ok_or(PayloadError::Malformed)?;
+    let send_at = timestamp
+        .parse::<i128>()
+        .ok()
+        .and_then(|timestamp| OffsetDateTime::from_unix_timestamp_nanos(timestamp * 1000000).ok())
+        .ok_or(PayloadError::Malformed)?;
+
+    Ok(PickedTime {
+        send_at_utc: send_at.time(),
+        local: send_at.to_offset(offset).time(),
+        offset,
+        timezone: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{offset, time};
+    use time_tz::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn json_payload() {
+        let picked = parse(r#"{"version": 1, "time": "22:30", "timezone": "Asia/Tokyo"}"#).unwrap();
+
+        assert_eq!(picked.local, time!(22:30));
+        assert_eq!(picked.offset, offset!(+9));
+        assert_eq!(picked.send_at_utc, time!(13:30));
+        assert_eq!(picked.timezone.map(|tz| tz.name()), Some("Asia/Tokyo"));
+    }
+
+    #[test]
+    fn legacy_payload() {
+        // 2023-05-26 19:00 UTC, picked in UTC+3
+        let picked = parse("1685127600000_-180").unwrap();
+
+        assert_eq!(picked.local, time!(22:00));
+        assert_eq!(picked.offset, offset!(+3));
+        assert_eq!(picked.send_at_utc, time!(19:00));
+        assert!(picked.timezone.is_none());
+    }
+
+    #[test]
+    fn invalid_payloads() {
+        assert_eq!(
+            parse(r#"{"version": 2, "time": "22:30"}"#).unwrap_err(),
+            PayloadError::UnsupportedVersion(2)
+        );
+        assert_eq!(
+            parse(r#"{"version": 1, "time": "25:00", "timezone": "Europe/Moscow"}"#).unwrap_err(),
+            PayloadError::InvalidTime("25:00".to_owned())
+        );
+        assert_eq!(
+            parse(r#"{"version": 1, "time": "22:30", "timezone": "Mars/Olympus"}"#).unwrap_err(),
+            PayloadError::UnknownTimezone("Mars/Olympus".to_owned())
+        );
+
+        for malformed in [
+            "",
+            "{",
+            r#"{"time": "22:30", "timezone": "Europe/Moscow"}"#,
+            r#"{"version": 1, "time": "22:30"}"#,
+            r#"{"version": 1, "time": "22:30", "timezone": "UTC", "date": "2023-05-26"}"#,
+            "not a timestamp_-180",
+        ] {
+            assert_eq!(parse(malformed).unwrap_err(), PayloadError::Malformed);
+        }
+    }
+}
@@ -1,11 +1,12 @@
 use color_eyre::Result;
 use sqlx::migrate::Migrator;
-use tracing::info;
+use tracing::{error, info};
 
 use ilquentir_config::Config;
 
 mod bot;
 mod scheduler;
+mod templates;
 mod tracing_setup;
 
 use crate::{bot::create_bot_and_dispatcher, scheduler::Scheduler};
@@ -25,6 +26,17 @@ async fn main() -> Result<()> {
     let scheduler = Scheduler::new(&dispatcher);
     let scheduler_shutdown_token = scheduler.shutdown_token();
 
+    if config.mini_app.url.is_some() {
+        let (bot, pool, config) = (bot.clone(), pool.clone(), config.clone());
+        tokio::spawn(async move {
+            if let Err(error) = bot::mini_app::serve(bot, pool, config).await {
+                error!(%error, "Mini App server stopped");
+            }
+        });
+    }
+    if let Some(dir) = config.templates.dir.clone() {
+        tokio::spawn(templates::watch(dir, config.templates.reload_interval));
+    }
     tokio::spawn(async move { scheduler.start(&pool, &bot.clone(), &config).await });
     dispatcher.dispatch().await;
     info!("dispatcher stopped working, shutting down scheduler");
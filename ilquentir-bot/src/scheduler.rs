@@ -8,10 +8,11 @@ use tokio::time::{interval, MissedTickBehavior};
 use tracing::{error, info};
 
 use ilquentir_config::Config;
-use ilquentir_models::{Poll, PollKind};
+use ilquentir_models::{Dialogue, Poll, PollKind};
 
 use crate::bot::{
     helpers::{overdue_poll, send_poll},
+    onboarding::finish_abandoned,
     Bot, Dispatcher,
 };
 
@@ -65,6 +66,11 @@ impl Scheduler {
                 error!(error = %e, "got an error while processing overdue");
             };
 
+            let onboarding_result = handle_abandoned_onboardings(bot, pool).await;
+            if let Err(e) = onboarding_result {
+                error!(error = %e, "got an error while finishing onboardings");
+            };
+
             if !self.running() {
                 info!("scheduler shut down");
 
@@ -149,3 +155,31 @@ pub async fn handle_scheduled_overdue(bot: &Bot, pool: &PgPool) -> Result<()> {
 
     Ok(())
 }
+
+#[tracing::instrument(skip_all, err)]
+pub async fn handle_abandoned_onboardings(bot: &Bot, pool: &PgPool) -> Result<()> {
+    let mut txn = pool.begin().await?;
+
+    let onboardings = Dialogue::get_expired_onboardings(&mut txn).await?;
+    if !onboardings.is_empty() {
+        info!(
+            abandoned_onboarding_count = onboardings.len(),
+            "found some abandoned onboardings"
+        );
+    }
+
+    txn.commit().await?;
+
+    for onboarding in onboardings {
+        let mut txn = pool.begin().await?;
+
+        let finish_result = finish_abandoned(bot, &mut txn, onboarding).await;
+        if let Err(e) = finish_result {
+            error!(error = %e, "got an error while finishing onboarding");
+        };
+
+        txn.commit().await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use ilquentir_messages::{load_overrides, template_index, LoadReport};
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::{error, info, warn};
+
+/// Modification time and size of every file in the templates directory
+type Snapshot = Vec<(PathBuf, SystemTime, u64)>;
+
+/// Loads template overrides from the directory, and reloads them every time its files change
+pub async fn watch(dir: PathBuf, reload_interval: Duration) {
+    let mut ticker = interval(reload_interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut last_snapshot = None;
+
+    loop {
+        ticker.tick().await;
+
+        let dir = dir.clone();
+        let last = last_snapshot.clone();
+        let reloaded = tokio::task::spawn_blocking(move || reload_if_changed(&dir, last)).await;
+
+        match reloaded {
+            Ok(Ok(Some((snapshot, report)))) => {
+                for (path, reason) in &report.rejected {
+                    warn!(path = %path.display(), reason, "template override rejected");
+                }
+                info!(
+                    loaded = report.loaded,
+                    rejected = report.rejected.len(),
+                    "template overrides reloaded"
+                );
+
+                last_snapshot = Some(snapshot);
+            }
+            Ok(Ok(None)) => {}
+            Ok(Err(error)) => error!(%error, "failed to reload template overrides"),
+            Err(error) => error!(%error, "template overrides reloading panicked"),
+        }
+    }
+}
+
+fn reload_if_changed(
+    dir: &Path,
+    last_snapshot: Option<Snapshot>,
+) -> io::Result<Option<(Snapshot, LoadReport)>> {
+    let mut snapshot = vec![];
+    take_snapshot(dir, &mut snapshot)?;
+    snapshot.sort();
+
+    if last_snapshot.as_ref() == Some(&snapshot) {
+        return Ok(None);
+    }
+
+    let report = load_overrides(dir, template_index!())?;
+
+    Ok(Some((snapshot, report)))
+}
+
+fn take_snapshot(dir: &Path, snapshot: &mut Snapshot) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+
+        if metadata.is_dir() {
+            take_snapshot(&entry.path(), snapshot)?;
+        } else {
+            snapshot.push((entry.path(), metadata.modified()?, metadata.len()));
+        }
+    }
+
+    Ok(())
+}
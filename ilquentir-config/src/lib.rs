@@ -1,8 +1,9 @@
-use std::{ops::Deref, path::PathBuf, sync::Arc, time::Duration};
+use std::{net::SocketAddr, ops::Deref, path::PathBuf, sync::Arc, time::Duration};
 
 use color_eyre::Result;
 use serde::Deserialize;
 use time::Date;
+use url::Url;
 
 /// Configuration for the bot.
 ///
@@ -30,6 +31,12 @@ impl Config {
     }
 }
 
+impl ConfigInner {
+    pub fn is_admin(&self, user_tg_id: i64) -> bool {
+        self.admin_tg_ids.contains(&user_tg_id)
+    }
+}
+
 impl Deref for Config {
     type Target = ConfigInner;
 
@@ -51,6 +58,9 @@ pub struct ConfigInner {
     /// Scheduler interval: how long should pauses be between updates
     #[serde(with = "humantime_serde")]
     pub scheduler_interval: Duration,
+    /// Telegram ids of users, who can manage the bot via admin commands
+    #[serde(default)]
+    pub admin_tg_ids: Vec<i64>,
 
     /// S3 configuration
     #[serde(default, flatten)]
@@ -59,6 +69,14 @@ pub struct ConfigInner {
     /// Configuration for the python-based mood graph
     #[serde(default, flatten)]
     pub graph: GraphConfig,
+
+    /// Configuration of the Mini App, served by the bot
+    #[serde(flatten)]
+    pub mini_app: MiniAppConfig,
+
+    /// Configuration of the runtime overrides of message templates
+    #[serde(flatten)]
+    pub templates: TemplatesConfig,
 }
 
 /// Configuration of an S3 storage
@@ -88,6 +106,8 @@ pub struct GraphConfig {
     /// Max tolerable age of wide_how_was_your_day table
     #[serde(with = "humantime_serde")]
     pub wide_how_was_your_day_max_age: Duration,
+    /// Path for the wide_mood_check_in export, refreshed together with wide_how_was_your_day
+    pub wide_mood_check_in_path: PathBuf,
     /// Path to python file, containing plotly graphing function
     pub plotly_python_code_file: PathBuf,
 
@@ -98,3 +118,42 @@ pub struct GraphConfig {
     #[serde(rename = "graph_end_date")]
     pub end_date: Date,
 }
+
+/// Configuration of the Telegram Mini App, served by the bot itself
+#[derive(Debug, Deserialize)]
+pub struct MiniAppConfig {
+    /// Public HTTPS URL of the Mini App, as Telegram clients see it.
+    ///
+    /// If it's not set, the Mini App is not served and the external time picker is used.
+    #[serde(rename = "mini_app_url")]
+    pub url: Option<Url>,
+    /// Address, on which the embedded HTTP server listens
+    #[serde(rename = "mini_app_listen_addr", default = "default_listen_addr")]
+    pub listen_addr: SocketAddr,
+}
+
+fn default_listen_addr() -> SocketAddr {
+    ([0, 0, 0, 0], 8080).into()
+}
+
+/// Configuration of the message templates, loaded at runtime
+#[derive(Debug, Deserialize)]
+pub struct TemplatesConfig {
+    /// Directory with `<locale>/<path>` templates, which override the embedded ones
+    /// and are reloaded, when they change.
+    ///
+    /// If it's not set, only the embedded templates are used, as they should be in production.
+    #[serde(rename = "templates_dir")]
+    pub dir: Option<PathBuf>,
+    /// How often the directory is checked for changes
+    #[serde(
+        rename = "templates_reload_interval",
+        with = "humantime_serde",
+        default = "default_reload_interval"
+    )]
+    pub reload_interval: Duration,
+}
+
+fn default_reload_interval() -> Duration {
+    Duration::from_secs(5)
+}
@@ -0,0 +1,19 @@
+// templates of the crate's own tests are prepared by the very same helper
+#[allow(dead_code)]
+#[path = "src/locale.rs"]
+mod locale;
+#[allow(dead_code)]
+#[path = "src/plural.rs"]
+mod plural;
+#[allow(dead_code)]
+#[path = "src/template.rs"]
+mod template;
+#[path = "src/build.rs"]
+mod templates;
+
+use locale::Locale;
+use plural::PluralCategory;
+
+fn main() {
+    templates::prepare_templates().expect("failed to prepare message templates");
+}
@@ -0,0 +1,216 @@
+//! Build script helpers for the crates with their own message templates
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    template::{Template, CONTEXT_VARIABLES},
+    Locale,
+};
+
+/// Directory of the templates, relative to the crate root
+const TEMPLATES_DIR: &str = "messages";
+/// Directory in `$OUT_DIR/messages` with the placeholders of every template,
+/// which are used to check the arguments of [`message!`](crate::message) at compile time
+const PLACEHOLDERS_DIR: &str = "placeholders";
+/// Directory in `$OUT_DIR/messages` with `true` or `false` for every template, whether any of
+/// its translations uses the [`CONTEXT_VARIABLES`], which [`message!`](crate::message) checks
+/// against the context at compile time
+const CONTEXT_DIR: &str = "context";
+/// File in `$OUT_DIR/messages` with the [`template_index!`](crate::template_index)
+const INDEX_FILE: &str = "index.rs";
+
+/// Prepares templates of every locale in `$OUT_DIR/messages/<locale>`, where
+/// [`message!`](crate::message) looks for them.
+///
+/// Templates are read from `messages/<locale>` of the crate being built; the ones,
+/// which aren't translated yet, are taken from the default locale.
+/// Every template is checked to be valid, and translations to have the same placeholders
+/// as the default template.
+pub fn prepare_templates() -> io::Result<()> {
+    let source = env_path("CARGO_MANIFEST_DIR").join(TEMPLATES_DIR);
+    let target = env_path("OUT_DIR").join(TEMPLATES_DIR);
+
+    println!("cargo:rerun-if-changed={}", source.display());
+
+    if target.exists() {
+        fs::remove_dir_all(&target)?;
+    }
+
+    let default = Locale::default();
+    let default_source = source.join(default.code());
+    let mut placeholders = BTreeMap::new();
+
+    for path in list_templates(&default_source, Path::new(""))? {
+        let text = fs::read_to_string(default_source.join(&path))?;
+        let names = check_template(&default_source.join(&path), &text, default)?;
+
+        write(
+            &target.join(PLACEHOLDERS_DIR).join(&path),
+            &names
+                .iter()
+                .map(|name| format!("{{{name}}}"))
+                .collect::<String>(),
+        )?;
+        placeholders.insert(path, names);
+    }
+
+    let mut uses_context = BTreeMap::<_, bool>::new();
+
+    for locale in Locale::ALL {
+        let locale_source = source.join(locale.code());
+        let locale_target = target.join(locale.code());
+
+        if locale != default && locale_source.exists() {
+            for path in list_templates(&locale_source, Path::new(""))? {
+                if !placeholders.contains_key(&path) {
+                    return Err(invalid(
+                        &locale_source.join(path),
+                        "translation of a missing template",
+                    ));
+                }
+            }
+        }
+
+        for (path, expected) in &placeholders {
+            let translated = locale_source.join(path);
+            let (template_path, template_locale) = if translated.exists() {
+                (translated, locale)
+            } else {
+                (default_source.join(path), default)
+            };
+            let text = fs::read_to_string(&template_path)?;
+            *uses_context.entry(path).or_default() |= uses_context_variables(&text);
+
+            if template_locale != default
+                && &check_template(&template_path, &text, template_locale)? != expected
+            {
+                return Err(invalid(
+                    &template_path,
+                    "placeholders differ from the default template",
+                ));
+            }
+
+            write(&locale_target.join(path), &text)?;
+            write(
+                &locale_target.join(with_suffix(path, ".locale")),
+                template_locale.code(),
+            )?;
+        }
+    }
+
+    for (path, uses_context) in &uses_context {
+        write(
+            &target.join(CONTEXT_DIR).join(path),
+            &uses_context.to_string(),
+        )?;
+    }
+    write(
+        &target.join(INDEX_FILE),
+        &index(&placeholders, &uses_context),
+    )?;
+
+    Ok(())
+}
+
+/// Rust expression of the [`TemplateIndex`](crate::TemplateIndex) type
+fn index(
+    placeholders: &BTreeMap<PathBuf, BTreeSet<String>>,
+    uses_context: &BTreeMap<&PathBuf, bool>,
+) -> String {
+    let mut index = "{\n    const INDEX: &[(&str, &[&str], bool)] = &[\n".to_owned();
+
+    for (path, names) in placeholders {
+        let uses_context = uses_context.get(path).copied().unwrap_or_default();
+        let path = path
+            .iter()
+            .map(|part| part.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let names = names.iter().collect::<Vec<_>>();
+
+        index.push_str(&format!(
+            "        ({path:?}, &{names:?}, {uses_context}),\n"
+        ));
+    }
+    index.push_str("    ];\n\n    INDEX\n}\n");
+
+    index
+}
+
+fn env_path(name: &str) -> PathBuf {
+    env::var_os(name)
+        .unwrap_or_else(|| panic!("{name} is set by cargo for build scripts"))
+        .into()
+}
+
+/// Paths of the templates in the directory, relative to it
+pub(crate) fn list_templates(root: &Path, dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut paths = vec![];
+
+    for entry in fs::read_dir(root.join(dir))? {
+        let entry = entry?;
+        let path = dir.join(entry.file_name());
+
+        if entry.file_type()?.is_dir() {
+            paths.extend(list_templates(root, &path)?);
+        } else {
+            paths.push(path);
+        }
+    }
+
+    Ok(paths)
+}
+
+/// Parses the template and returns its placeholders, but the context variables
+fn check_template(path: &Path, text: &str, locale: Locale) -> io::Result<BTreeSet<String>> {
+    let template = Template::parse(text)
+        .and_then(|template| {
+            template.check_plural_forms(locale)?;
+
+            Ok(template)
+        })
+        .map_err(|error| invalid(path, error))?;
+
+    Ok(template
+        .arguments()
+        .into_iter()
+        .map(str::to_owned)
+        .collect())
+}
+
+/// Whether the valid template uses any of the context variables
+fn uses_context_variables(text: &str) -> bool {
+    Template::parse(text).map_or(false, |template| {
+        template
+            .placeholders()
+            .iter()
+            .any(|name| CONTEXT_VARIABLES.contains(name))
+    })
+}
+
+fn write(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    fs::write(path, contents)
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+
+    path.into()
+}
+
+fn invalid(path: &Path, error: impl std::fmt::Display) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{}: {error}", path.display()),
+    )
+}
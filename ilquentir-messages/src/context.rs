@@ -0,0 +1,59 @@
+use crate::{Locale, Value};
+
+/// Context of the message, which [`message!`](crate::message) gets as its first argument.
+///
+/// Besides the language of the message, it provides the values of the
+/// [`CONTEXT_VARIABLES`](crate::CONTEXT_VARIABLES) to every template. A bare [`Locale`] is the
+/// context without any values, so it can only render templates, which don't use them.
+///
+/// Contexts are `Sync`, so messages can be rendered right in the arguments of awaited requests.
+pub trait RenderContext: Sync {
+    /// Requested language of the message
+    fn locale(&self) -> Locale;
+
+    /// Value of the context variable, `None` if it's unknown
+    fn value(&self, name: &str) -> Option<Value<'_>>;
+}
+
+/// Context, which provides the values of all the [`CONTEXT_VARIABLES`](crate::CONTEXT_VARIABLES).
+///
+/// [`message!`](crate::message) renders templates, which use context variables, only with
+/// such contexts, which is checked at compile time.
+pub trait UserValues: RenderContext {}
+
+impl RenderContext for Locale {
+    fn locale(&self) -> Locale {
+        *self
+    }
+
+    fn value(&self, _name: &str) -> Option<Value<'_>> {
+        None
+    }
+}
+
+impl<T: RenderContext + ?Sized> RenderContext for &T {
+    fn locale(&self) -> Locale {
+        (**self).locale()
+    }
+
+    fn value(&self, name: &str) -> Option<Value<'_>> {
+        (**self).value(name)
+    }
+}
+
+impl<T: UserValues + ?Sized> UserValues for &T {}
+
+/// Contexts, which can render a template, depending on whether it uses context variables
+#[doc(hidden)]
+pub trait ContextFor<const USES_VARIABLES: bool> {}
+
+impl<T: RenderContext + ?Sized> ContextFor<false> for T {}
+
+impl<T: UserValues + ?Sized> ContextFor<true> for T {}
+
+/// Compile time check of the context, passed to [`message!`](crate::message)
+#[doc(hidden)]
+pub fn check_context<const USES_VARIABLES: bool, T: ContextFor<USES_VARIABLES> + ?Sized>(
+    _context: &T,
+) {
+}
@@ -0,0 +1,168 @@
+use time::{Date, Month, Time};
+
+use crate::Locale;
+
+/// Russian months in the genitive case, as they are used in dates
+const RU_MONTHS: [&str; 12] = [
+    "января",
+    "февраля",
+    "марта",
+    "апреля",
+    "мая",
+    "июня",
+    "июля",
+    "августа",
+    "сентября",
+    "октября",
+    "ноября",
+    "декабря",
+];
+
+const EN_MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+impl Locale {
+    /// Separates thousands, e.g. `12 345` in Russian (with a no-break space) and `12,345` in English
+    pub fn format_integer(self, n: i64) -> String {
+        let sign = if n < 0 { "-" } else { "" };
+
+        format!("{sign}{}", self.group_digits(&n.unsigned_abs().to_string()))
+    }
+
+    /// Formats the number with at most `max_fraction_digits` digits after the decimal separator,
+    /// trailing zeros are dropped
+    pub fn format_decimal(self, n: f64, max_fraction_digits: usize) -> String {
+        let formatted = format!("{:.*}", max_fraction_digits, n.abs());
+        let (integer, fraction) = formatted.split_once('.').unwrap_or((&formatted, ""));
+        let fraction = fraction.trim_end_matches('0');
+
+        // rounding may turn a small negative number into zero
+        let sign = if n < 0. && (integer != "0" || !fraction.is_empty()) {
+            "-"
+        } else {
+            ""
+        };
+        let integer = self.group_digits(integer);
+
+        if fraction.is_empty() {
+            return format!("{sign}{integer}");
+        }
+
+        let separator = match self {
+            Self::Ru => ',',
+            Self::En => '.',
+        };
+
+        format!("{sign}{integer}{separator}{fraction}")
+    }
+
+    /// Full date, e.g. `5 июня 2023` in Russian and `June 5, 2023` in English
+    pub fn format_date(self, date: Date) -> String {
+        let day = date.day();
+        let year = date.year();
+
+        match self {
+            Self::Ru => format!("{day} {} {year}", RU_MONTHS[month_idx(date.month())]),
+            Self::En => format!("{} {day}, {year}", EN_MONTHS[month_idx(date.month())]),
+        }
+    }
+
+    /// Time of day, e.g. `14:05` in Russian and `2:05 PM` in English
+    pub fn format_time(self, time: Time) -> String {
+        let minute = time.minute();
+
+        match self {
+            Self::Ru => format!("{}:{minute:02}", time.hour()),
+            Self::En => {
+                let (hour, period) = match time.hour() {
+                    0 => (12, "AM"),
+                    hour @ 1..=11 => (hour, "AM"),
+                    12 => (12, "PM"),
+                    hour => (hour - 12, "PM"),
+                };
+
+                format!("{hour}:{minute:02} {period}")
+            }
+        }
+    }
+
+    fn group_digits(self, digits: &str) -> String {
+        let (separator, min_grouped_len) = match self {
+            // four-digit numbers aren't grouped in Russian
+            Self::Ru => ('\u{a0}', 5),
+            Self::En => (',', 4),
+        };
+
+        let mut result = String::with_capacity(digits.len() * 2);
+        for (idx, digit) in digits.chars().enumerate() {
+            if idx > 0 && (digits.len() - idx) % 3 == 0 && digits.len() >= min_grouped_len {
+                result.push(separator);
+            }
+            result.push(digit);
+        }
+
+        result
+    }
+}
+
+fn month_idx(month: Month) -> usize {
+    u8::from(month) as usize - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{date, time};
+
+    use super::*;
+
+    #[test]
+    fn integers() {
+        assert_eq!(Locale::Ru.format_integer(42), "42");
+        assert_eq!(Locale::Ru.format_integer(1234), "1234");
+        assert_eq!(Locale::Ru.format_integer(12345), "12\u{a0}345");
+        assert_eq!(Locale::Ru.format_integer(-1234567), "-1\u{a0}234\u{a0}567");
+
+        assert_eq!(Locale::En.format_integer(1234), "1,234");
+        assert_eq!(Locale::En.format_integer(123456), "123,456");
+        assert_eq!(Locale::En.format_integer(-100), "-100");
+    }
+
+    #[test]
+    fn decimals() {
+        assert_eq!(Locale::Ru.format_decimal(3.5, 2), "3,5");
+        assert_eq!(Locale::Ru.format_decimal(3.0, 2), "3");
+        assert_eq!(Locale::Ru.format_decimal(12345.678, 1), "12\u{a0}345,7");
+        assert_eq!(Locale::En.format_decimal(-0.25, 2), "-0.25");
+        assert_eq!(Locale::En.format_decimal(-0.001, 2), "0");
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(Locale::Ru.format_date(date!(2023 - 06 - 05)), "5 июня 2023");
+        assert_eq!(
+            Locale::En.format_date(date!(2023 - 06 - 05)),
+            "June 5, 2023"
+        );
+    }
+
+    #[test]
+    fn times() {
+        assert_eq!(Locale::Ru.format_time(time!(14:05)), "14:05");
+        assert_eq!(Locale::Ru.format_time(time!(0:30)), "0:30");
+        assert_eq!(Locale::En.format_time(time!(14:05)), "2:05 PM");
+        assert_eq!(Locale::En.format_time(time!(0:30)), "12:30 AM");
+        assert_eq!(Locale::En.format_time(time!(12:00)), "12:00 PM");
+    }
+}
@@ -11,10 +11,49 @@ macro_rules! regex {
     };
 }
 
+pub mod build;
+
+mod context;
+#[doc(hidden)]
+pub use context::check_context;
+pub use context::{RenderContext, UserValues};
+
+mod format;
+
+mod locale;
+pub use locale::Locale;
+
+mod markdown_v2;
+pub use markdown_v2::{split_markdown_v2, validate_markdown_v2, MarkdownV2Error, MAX_MESSAGE_LEN};
+
+mod markup;
+pub use markup::Markup;
+
+mod overrides;
+pub use overrides::{clear_overrides, load_overrides, LoadReport, TemplateIndex};
+
+mod plural;
+pub use plural::PluralCategory;
+
+mod render;
+pub use render::{render, Embedded, Format, ToValue, Value};
+
+mod template;
+pub use template::{Template, TemplateError, CONTEXT_VARIABLES};
+
+/// Renders template `messages/<locale>/<path>` of the calling crate with the given arguments,
+/// the calling crate prepares templates with [`build::prepare_templates`].
+///
+/// The first argument is a [`RenderContext`], e.g. a bare [`Locale`]; templates, which use
+/// the [`CONTEXT_VARIABLES`], need a [`UserValues`] context. Arguments are checked at compile
+/// time to match the other placeholders of the template, see [`Template`] for its syntax.
+///
+/// `md_message!` renders the template as Markdown for Telegram: texts of the context variables
+/// (e.g. the user's name) stay literal in it, as the ones of [`Markup`] do.
 #[macro_export]
 macro_rules! md_message {
-    ($($args:tt)*) => {
-        $crate::md!(&$crate::message!($($args)*))
+    ($context:expr, $message_path:literal $(, $name:ident = $value:expr)* $(,)?) => {
+        $crate::render_message!(Markdown, $context, $message_path $(, $name = $value)*)
     };
 }
 
@@ -27,12 +66,88 @@ macro_rules! md {
 
 #[macro_export]
 macro_rules! message {
-    ($message_path:literal) => {{
-        format!(include_str!(concat!(::std::env!("CARGO_MANIFEST_DIR"), "/messages/", $message_path)))
+    ($context:expr, $message_path:literal $(, $name:ident = $value:expr)* $(,)?) => {
+        $crate::render_message!(Text, $context, $message_path $(, $name = $value)*)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! render_message {
+    ($format:ident, $context:expr, $message_path:literal $(, $name:ident = $value:expr)*) => {{
+        // never executed, `format!` only checks that every placeholder is supplied and nothing else,
+        // translations are checked by the build script to have the same placeholders
+        if false {
+            let _ = format!(
+                include_str!(concat!(
+                    ::std::env!("OUT_DIR"),
+                    "/messages/placeholders/",
+                    $message_path
+                ))
+                $(, $name = $value)*
+            );
+        }
+
+        let context = &$context;
+        // never executed as well, templates with context variables only accept `UserValues` contexts
+        if false {
+            $crate::check_context::<
+                {
+                    include!(concat!(
+                        ::std::env!("OUT_DIR"),
+                        "/messages/context/",
+                        $message_path
+                    ))
+                },
+                _,
+            >(context);
+        }
+        let template = match $crate::RenderContext::locale(context) {
+            $crate::Locale::Ru => $crate::embedded!("ru", $message_path),
+            $crate::Locale::En => $crate::embedded!("en", $message_path),
+        };
+
+        $crate::render(
+            context,
+            template,
+            &[$((stringify!($name), $crate::ToValue::to_value(&$value))),*],
+            $crate::Format::$format,
+        )
     }};
-    ($message_path:literal, $($args:tt)*) => {{
-        format!(include_str!(concat!(::std::env!("CARGO_MANIFEST_DIR"), "/messages/", $message_path)), $($args)*)
-    }}
+}
+
+/// Paths of the templates of the calling crate with their placeholders and usage of the context
+/// variables, which are needed to validate the templates, passed to [`load_overrides`]
+#[macro_export]
+macro_rules! template_index {
+    () => {
+        include!(concat!(::std::env!("OUT_DIR"), "/messages/index.rs"))
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! embedded {
+    ($code:literal, $message_path:literal) => {
+        $crate::Embedded {
+            path: $message_path,
+            text: include_str!(concat!(
+                ::std::env!("OUT_DIR"),
+                "/messages/",
+                $code,
+                "/",
+                $message_path
+            )),
+            locale: include_str!(concat!(
+                ::std::env!("OUT_DIR"),
+                "/messages/",
+                $code,
+                "/",
+                $message_path,
+                ".locale"
+            )),
+        }
+    };
 }
 
 static TG_MD_ESCAPE_REGEX: Lazy<Regex> = regex!(r"[_*\[\]()~`>#+\-=|{}\.!\\]");
@@ -101,15 +216,143 @@ mod tests {
         let graph = "123";
 
         assert_eq!(
-            message!("test/stats_for_today.md", graph = graph),
+            message!(Locale::Ru, "test/stats_for_today.md", graph = graph),
             "Присылаю статистику по ответам всех за сегодня:\n\n```\n  %\n123\n```"
         );
         assert_eq!(
-            md_message!("test/stats_for_today.md", graph = graph),
+            md_message!(Locale::Ru, "test/stats_for_today.md", graph = graph),
             "Присылаю статистику по ответам всех за сегодня:\n\n```\n  %\n123\n```"
         );
     }
 
+    #[test]
+    fn test_locales() {
+        assert_eq!(
+            message!(Locale::En, "test/greeting.md", name = "Ilquentir"),
+            "Hello, Ilquentir!"
+        );
+        assert_eq!(
+            message!(Locale::Ru, "test/greeting.md", name = "Ilquentir"),
+            "Привет, Ilquentir!"
+        );
+
+        // not translated yet
+        assert_eq!(
+            message!(Locale::En, "test/stats_for_today.md", graph = "123"),
+            message!(Locale::Ru, "test/stats_for_today.md", graph = "123"),
+        );
+    }
+
+    #[test]
+    fn test_overrides() {
+        let dir = std::env::temp_dir().join(format!("ilquentir-overrides-{}", std::process::id()));
+        let write = |path: &str, text: &str| {
+            let path = dir.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, text).unwrap();
+        };
+        write("en/test/override.md", "Overridden for {name}");
+        write("ru/test/days.md", "Ты с нами {count} дней");
+        write("ru/test/missing.md", "Нет такого");
+        write("de/test/override.md", "Für {name}");
+        // rendered without the user's context
+        write(
+            "ru/test/greeting.md",
+            "Привет, {name}, ты с нами {days_since_start} дней!",
+        );
+
+        let report = load_overrides(&dir, template_index!()).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(report.loaded, 1);
+        assert_eq!(report.rejected.len(), 4, "{report:?}");
+        assert_eq!(
+            message!(Locale::En, "test/override.md", name = "Ilquentir"),
+            "Overridden for Ilquentir"
+        );
+        assert_eq!(
+            message!(Locale::Ru, "test/override.md", name = "Ilquentir"),
+            "Встроенный шаблон для Ilquentir"
+        );
+
+        clear_overrides();
+        assert_eq!(
+            message!(Locale::En, "test/override.md", name = "Ilquentir"),
+            "Встроенный шаблон для Ilquentir"
+        );
+    }
+
+    /// User context of the tests, `None` is the name of a user without one
+    struct User(Option<&'static str>);
+
+    impl RenderContext for User {
+        fn locale(&self) -> Locale {
+            Locale::Ru
+        }
+
+        fn value(&self, name: &str) -> Option<Value<'_>> {
+            match name {
+                "user_name" => self.0.map(Value::Text),
+                "streak" => Some(Value::Integer(2)),
+                "answered_days" => Some(Value::Integer(10)),
+                "days_since_start" => Some(Value::Integer(3)),
+                "local_time" => Some(Value::Time(time::macros::time!(9:30))),
+                _ => None,
+            }
+        }
+    }
+
+    impl UserValues for User {}
+
+    #[test]
+    fn test_context() {
+        assert_eq!(
+            message!(User(Some("Ильквентир")), "test/context.md", note = "Ура!"),
+            "Ильквентир, ты с нами 3 дня, сейчас 9:30. Ура!"
+        );
+        // templates without context variables don't need the values
+        assert_eq!(
+            message!(User(None), "test/greeting.md", name = "Ilquentir"),
+            message!(Locale::Ru, "test/greeting.md", name = "Ilquentir"),
+        );
+    }
+
+    #[test]
+    fn test_context_markdown() {
+        let user = User(Some("[Ильквентир](https://t.me) *_`"));
+
+        assert_eq!(
+            md_message!(user, "test/context.md", note = "*Ура!*"),
+            r"\[Ильквентир\]\(https://t\.me\) \*\_\`, ты с нами 3 дня, сейчас 9:30\. *Ура\!*"
+        );
+        assert_eq!(
+            message!(user, "test/context.md", note = "Ура!"),
+            "[Ильквентир](https://t.me) *_`, ты с нами 3 дня, сейчас 9:30. Ура!"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "context variable `user_name` has no value")]
+    fn test_missing_context_variable() {
+        message!(User(None), "test/context.md", note = "Ура!");
+    }
+
+    #[test]
+    fn test_plurals() {
+        assert_eq!(
+            message!(Locale::Ru, "test/days.md", days = 3),
+            "Ты с нами 3 дня"
+        );
+        assert_eq!(
+            message!(Locale::En, "test/days.md", days = 1),
+            "You've been with us for 1 day"
+        );
+        assert_eq!(
+            message!(Locale::En, "test/days.md", days = 1000),
+            "You've been with us for 1,000 days"
+        );
+    }
+
     #[test]
     fn test_md_escape() {
         assert_eq!(
@@ -117,4 +360,15 @@ mod tests {
             r#"Скоро тебе придёт статистика за сегодня, а в целом – доступную стату можно посмотреть по запросу /get\_stat :\)"#
         )
     }
+
+    #[test]
+    fn test_md_escape_is_valid() {
+        for text in [
+            "Не получилось разобрать аргументы, нужно: /catalog_add <категория> <текст>",
+            "**Готово**: #12 (1.5%) _a_ [link](https://t.me/a_b) `code`",
+            "```\n  %\n123\n```",
+        ] {
+            assert_eq!(validate_markdown_v2(&tg_escape(text)), Ok(()), "{text}");
+        }
+    }
 }
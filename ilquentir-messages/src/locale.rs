@@ -0,0 +1,54 @@
+/// Language of the messages, sent to the user.
+///
+/// Adding a locale requires a new arm in [`message!`](crate::message) as well.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Locale {
+    /// Every template has a version in the default locale, others fall back to it
+    #[default]
+    Ru,
+    En,
+}
+
+impl Locale {
+    /// Every supported locale, the default one goes first
+    pub const ALL: [Self; 2] = [Self::Ru, Self::En];
+
+    /// ISO 639-1 code, which is also the name of the templates directory
+    pub const fn code(self) -> &'static str {
+        match self {
+            Self::Ru => "ru",
+            Self::En => "en",
+        }
+    }
+
+    /// Name of the language in this very language, e.g. for the language picker
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Ru => "Русский",
+            Self::En => "English",
+        }
+    }
+
+    /// Parses IETF language tag, e.g. `en-US` sent by Telegram, ignoring the region
+    pub fn from_code(code: &str) -> Option<Self> {
+        let language = code.split(['-', '_']).next()?;
+
+        Self::ALL
+            .into_iter()
+            .find(|locale| locale.code().eq_ignore_ascii_case(language))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_code() {
+        assert_eq!(Locale::from_code("ru"), Some(Locale::Ru));
+        assert_eq!(Locale::from_code("en-US"), Some(Locale::En));
+        assert_eq!(Locale::from_code("EN_gb"), Some(Locale::En));
+        assert_eq!(Locale::from_code("de"), None);
+        assert_eq!(Locale::from_code(""), None);
+    }
+}
@@ -0,0 +1,416 @@
+use std::fmt;
+
+/// Longest message Telegram accepts, counted in UTF-16 code units of the text after entities parsing
+pub const MAX_MESSAGE_LEN: usize = 4096;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkdownV2Error {
+    /// Byte offset in the text
+    pub position: usize,
+    pub reason: &'static str,
+}
+
+impl fmt::Display for MarkdownV2Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at byte {}", self.reason, self.position)
+    }
+}
+
+impl std::error::Error for MarkdownV2Error {}
+
+/// Checks the text against Telegram's `MarkdownV2`
+/// [rules](https://core.telegram.org/bots/api#markdownv2-style): reserved characters are escaped,
+/// entities are closed and properly nested, and the text fits into a single message.
+pub fn validate_markdown_v2(text: &str) -> Result<(), MarkdownV2Error> {
+    let mut len = 0;
+    let mut position = 0;
+
+    for unit in parse(text)? {
+        len += unit.len;
+
+        if len > MAX_MESSAGE_LEN {
+            return Err(MarkdownV2Error {
+                position,
+                reason: "message is too long",
+            });
+        }
+        position = unit.end;
+    }
+
+    Ok(())
+}
+
+/// Splits valid `MarkdownV2` text into messages of at most `max_len` characters.
+///
+/// Text is split between paragraphs, lines or words, whichever is found closer to the limit.
+/// Entities, which span the split, are closed at the end of the message and reopened
+/// in the next one; links and inline code are never split, so a message may only exceed
+/// the limit if one of them is longer than it.
+pub fn split_markdown_v2(text: &str, max_len: usize) -> Result<Vec<String>, MarkdownV2Error> {
+    let units = parse(text)?;
+    let mut messages = vec![];
+    let mut start = 0;
+    let mut prefix = String::new();
+
+    while start < units.len() {
+        let end = split_end(&units[start..], max_len) + start;
+        let open = &units[end].open;
+
+        let from = if start == 0 { 0 } else { units[start - 1].end };
+
+        let mut message = prefix;
+        message.push_str(&text[from..units[end].end]);
+        for entity in open.iter().rev() {
+            message.push_str(entity.closer());
+        }
+        messages.push(message);
+
+        prefix = open.iter().map(|entity| entity.opener()).collect();
+        start = end + 1;
+    }
+
+    Ok(messages)
+}
+
+/// Index of the last unit of the message, which starts with the first of the given units
+fn split_end(units: &[Unit<'_>], max_len: usize) -> usize {
+    let mut len = 0;
+    // split, which leaves at least a half of the message, is preferred to an earlier one of a higher rank
+    let mut best: Option<(Boundary, usize)> = None;
+    let mut fallback: Option<(Boundary, usize)> = None;
+    let mut end = units.len() - 1;
+
+    for (idx, unit) in units.iter().enumerate() {
+        if len + unit.len > max_len {
+            if let Some((_, split)) = best.or(fallback) {
+                end = split;
+
+                break;
+            }
+        }
+        len += unit.len;
+
+        if let Some(boundary) = unit.boundary {
+            let candidate = if len * 2 >= max_len {
+                &mut best
+            } else {
+                &mut fallback
+            };
+            if candidate.map_or(true, |(known, _)| boundary >= known) {
+                *candidate = Some((boundary, idx));
+            }
+        }
+    }
+
+    // closing markers go to the message with the entities' text, otherwise the next one
+    // would start with empty entities
+    while units.get(end + 1).map_or(false, |unit| unit.closing) {
+        end += 1;
+    }
+
+    end
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Boundary {
+    Char,
+    Word,
+    Line,
+    Paragraph,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Entity<'a> {
+    Bold,
+    Italic,
+    Underline,
+    Strikethrough,
+    Spoiler,
+    Code,
+    /// Opening marker with the language, e.g. "```rust\n"
+    Pre(&'a str),
+    LinkText,
+    LinkUrl,
+}
+
+impl<'a> Entity<'a> {
+    fn opener(&self) -> &'a str {
+        match self {
+            Self::Bold => "*",
+            Self::Italic => "_",
+            Self::Underline => "__",
+            Self::Strikethrough => "~",
+            Self::Spoiler => "||",
+            Self::Code => "`",
+            Self::Pre(opener) => opener,
+            Self::LinkText => "[",
+            Self::LinkUrl => "](",
+        }
+    }
+
+    fn closer(&self) -> &'static str {
+        match self {
+            Self::Bold => "*",
+            Self::Italic => "_",
+            Self::Underline => "__",
+            Self::Strikethrough => "~",
+            Self::Spoiler => "||",
+            Self::Code => "`",
+            Self::Pre(_) => "```",
+            Self::LinkText => "]",
+            Self::LinkUrl => ")",
+        }
+    }
+
+    /// Splitting the entity would change the message: links would lead to nowhere,
+    /// and inline code can't span lines
+    fn is_unbreakable(&self) -> bool {
+        matches!(self, Self::Code | Self::LinkText | Self::LinkUrl)
+    }
+}
+
+/// Character or marker of the text
+#[derive(Debug)]
+struct Unit<'a> {
+    /// Byte offset after the unit
+    end: usize,
+    /// Length of the parsed text in UTF-16 code units, markers have none
+    len: usize,
+    /// Whether the message can be split after the unit, and how good is the split
+    boundary: Option<Boundary>,
+    /// Closes an entity
+    closing: bool,
+    /// Entities, which are open after the unit
+    open: Vec<Entity<'a>>,
+}
+
+/// Characters, which must be escaped outside of the entities' markers
+const RESERVED: &[char] = &[
+    '_', '*', '[', ']', '(', ')', '~', '`', '>', '#', '+', '-', '=', '|', '{', '}', '.', '!', '\\',
+];
+
+fn parse(text: &str) -> Result<Vec<Unit<'_>>, MarkdownV2Error> {
+    let mut units = vec![];
+    let mut open: Vec<Entity> = vec![];
+    let mut position = 0;
+    let mut after_newline = false;
+
+    while let Some(c) = text[position..].chars().next() {
+        let rest = &text[position..];
+        let error = |reason| MarkdownV2Error { position, reason };
+        let mut len = 0;
+        let mut boundary = Some(Boundary::Char);
+        let mut closing = false;
+
+        let consumed = match open.last() {
+            Some(Entity::Pre(_) | Entity::Code | Entity::LinkUrl) if c == '\\' => {
+                len = escaped(rest).map_err(error)?;
+
+                2
+            }
+            Some(Entity::Pre(_)) if rest.starts_with("```") => {
+                open.pop();
+                closing = true;
+
+                3
+            }
+            Some(Entity::Code) if c == '`' => {
+                open.pop();
+                closing = true;
+
+                1
+            }
+            Some(Entity::LinkUrl) if c == ')' => {
+                open.pop();
+                closing = true;
+
+                1
+            }
+            Some(Entity::LinkUrl) => c.len_utf8(),
+            Some(Entity::Pre(_) | Entity::Code) => {
+                len = c.len_utf16();
+                if c == '\n' {
+                    boundary = Some(Boundary::Line);
+                }
+
+                c.len_utf8()
+            }
+            _ => match c {
+                '\\' => {
+                    len = escaped(rest).map_err(error)?;
+
+                    2
+                }
+                '`' if rest.starts_with("```") => {
+                    let language_len = rest[3..]
+                        .find('\n')
+                        .filter(|&idx| {
+                            rest[3..3 + idx].chars().all(|c| {
+                                c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '-' | '#')
+                            })
+                        })
+                        .map_or(0, |idx| idx + 1);
+                    let consumed = 3 + language_len;
+
+                    open.push(Entity::Pre(&rest[..consumed]));
+                    boundary = None;
+
+                    consumed
+                }
+                '`' => {
+                    open.push(Entity::Code);
+
+                    1
+                }
+                '[' => {
+                    open.push(Entity::LinkText);
+
+                    1
+                }
+                ']' if open.last() == Some(&Entity::LinkText) => {
+                    if !rest[1..].starts_with('(') {
+                        return Err(error("link without URL"));
+                    }
+                    open.pop();
+                    open.push(Entity::LinkUrl);
+
+                    2
+                }
+                '*' | '_' | '~' | '|' => {
+                    let (entity, consumed) = match c {
+                        '*' => (Entity::Bold, 1),
+                        '_' if rest.starts_with("__") => (Entity::Underline, 2),
+                        '_' => (Entity::Italic, 1),
+                        '~' => (Entity::Strikethrough, 1),
+                        _ if rest.starts_with("||") => (Entity::Spoiler, 2),
+                        _ => return Err(error("reserved character `|` must be escaped")),
+                    };
+
+                    if open.last() == Some(&entity) {
+                        open.pop();
+                        closing = true;
+                    } else if open.contains(&entity) {
+                        return Err(error("entities overlap"));
+                    } else {
+                        open.push(entity);
+                        boundary = None;
+                    }
+
+                    consumed
+                }
+                c if RESERVED.contains(&c) => {
+                    return Err(error("reserved character must be escaped"));
+                }
+                c => {
+                    len = c.len_utf16();
+                    boundary = Some(match c {
+                        '\n' if after_newline => Boundary::Paragraph,
+                        '\n' => Boundary::Line,
+                        c if c.is_whitespace() => Boundary::Word,
+                        _ => Boundary::Char,
+                    });
+
+                    c.len_utf8()
+                }
+            },
+        };
+
+        if open.last() == Some(&Entity::LinkUrl) {
+            len = 0;
+        }
+        if len > 0 {
+            after_newline = c == '\n';
+        }
+        if open.iter().any(Entity::is_unbreakable) {
+            boundary = None;
+        }
+
+        position += consumed;
+        units.push(Unit {
+            end: position,
+            len,
+            boundary,
+            closing,
+            open: open.clone(),
+        });
+    }
+
+    if let Some(entity) = open.last() {
+        return Err(MarkdownV2Error {
+            position: text.len(),
+            reason: match entity {
+                Entity::LinkText | Entity::LinkUrl => "unclosed link",
+                Entity::Code | Entity::Pre(_) => "unclosed code",
+                _ => "unclosed entity",
+            },
+        });
+    }
+
+    Ok(units)
+}
+
+/// Length of the escaped character, which follows `\`
+fn escaped(rest: &str) -> Result<usize, &'static str> {
+    match rest[1..].chars().next() {
+        Some(c) if c.is_ascii() && c != '\0' => Ok(1),
+        Some(_) => Err("only ASCII characters can be escaped"),
+        None => Err("trailing `\\`"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validation() {
+        let reason = |text| validate_markdown_v2(text).map_err(|error| error.reason);
+
+        assert_eq!(
+            reason(
+                r"*bold _italic_* __under__ ~s~ ||spoiler|| `a\`b` [link](https://t.me/a\)b) 1\.5"
+            ),
+            Ok(())
+        );
+        assert_eq!(reason("```rust\nlet a = (1 + 2);\n```"), Ok(()));
+        assert_eq!(reason("1.5"), Err("reserved character must be escaped"));
+        assert_eq!(reason("*bold"), Err("unclosed entity"));
+        assert_eq!(reason("*a _b* c_"), Err("entities overlap"));
+        assert_eq!(reason("[link]"), Err("link without URL"));
+        assert_eq!(reason("`code"), Err("unclosed code"));
+        assert_eq!(reason("a|b"), Err("reserved character `|` must be escaped"));
+        assert_eq!(reason("\\"), Err("trailing `\\`"));
+
+        let longest = "a".repeat(MAX_MESSAGE_LEN);
+        assert_eq!(reason(&longest), Ok(()));
+        assert_eq!(
+            validate_markdown_v2(&format!("{longest}a")),
+            Err(MarkdownV2Error {
+                position: MAX_MESSAGE_LEN,
+                reason: "message is too long"
+            })
+        );
+    }
+
+    #[test]
+    fn splitting() {
+        let split = |text, max_len| split_markdown_v2(text, max_len).expect("text is valid");
+
+        assert_eq!(split("short", 10), ["short"]);
+        assert_eq!(
+            split("first line\nsecond line\n\nthird", 25),
+            ["first line\nsecond line\n\n", "third"]
+        );
+        assert_eq!(split("one two three", 9), ["one two ", "three"]);
+        assert_eq!(split("*bold words here*", 10), ["*bold *", "*words here*"]);
+        assert_eq!(
+            split("```py\nline_1\nline_2\n```", 10),
+            ["```py\nline_1\n```", "```py\nline_2\n```"]
+        );
+        assert_eq!(
+            split("see [a long link](https://t.me) now", 8),
+            ["see ", "[a long link](https://t.me)", " now"]
+        );
+        assert_eq!(split("*abcdef*", 3), ["*abc*", "*def*"]);
+    }
+}
@@ -0,0 +1,461 @@
+/// Formatted message text, rendered for Telegram's `MarkdownV2` or `HTML`
+/// [parse mode](https://core.telegram.org/bots/api#formatting-options).
+///
+/// Text is always escaped, so user-provided strings can be safely mixed with formatting:
+///
+/// ```
+/// use ilquentir_messages::Markup;
+///
+/// let markup = Markup::new()
+///     .bold("Note")
+///     .text(": ")
+///     .italic("*not* bold");
+///
+/// assert_eq!(markup.markdown_v2(), r"*Note*: _\*not\* bold_");
+/// assert_eq!(markup.html(), "<b>Note</b>: <i>*not* bold</i>");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Markup {
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Text(String),
+    Bold(Markup),
+    Italic(Markup),
+    Spoiler(Markup),
+    Code(String),
+    Pre {
+        code: String,
+        language: Option<String>,
+    },
+    Link {
+        text: Markup,
+        url: String,
+    },
+}
+
+/// Entities, the node is nested in: Telegram doesn't allow to nest an entity into itself
+#[derive(Debug, Clone, Copy, Default)]
+struct Context {
+    bold: bool,
+    italic: bool,
+    spoiler: bool,
+    link: bool,
+}
+
+const LIST_BULLET: &str = "• ";
+
+impl Markup {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        let text = text.into();
+
+        match self.nodes.last_mut() {
+            Some(Node::Text(last)) => last.push_str(&text),
+            _ if !text.is_empty() => self.nodes.push(Node::Text(text)),
+            _ => {}
+        }
+
+        self
+    }
+
+    /// Starts a new line
+    pub fn line(self) -> Self {
+        self.text("\n")
+    }
+
+    pub fn bold(self, content: impl Into<Markup>) -> Self {
+        self.entity(content.into(), Node::Bold)
+    }
+
+    pub fn italic(self, content: impl Into<Markup>) -> Self {
+        self.entity(content.into(), Node::Italic)
+    }
+
+    pub fn spoiler(self, content: impl Into<Markup>) -> Self {
+        self.entity(content.into(), Node::Spoiler)
+    }
+
+    /// Inline monospace text
+    pub fn code(mut self, code: impl Into<String>) -> Self {
+        self.nodes.push(Node::Code(code.into()));
+
+        self
+    }
+
+    /// Monospace block, optionally highlighted as the given programming language
+    pub fn pre(mut self, code: impl Into<String>, language: Option<&str>) -> Self {
+        self.nodes.push(Node::Pre {
+            code: code.into(),
+            language: language.map(str::to_owned),
+        });
+
+        self
+    }
+
+    pub fn link(mut self, text: impl Into<Markup>, url: impl Into<String>) -> Self {
+        self.nodes.push(Node::Link {
+            text: text.into(),
+            url: url.into(),
+        });
+
+        self
+    }
+
+    /// Appends other markup as is
+    pub fn append(mut self, other: impl Into<Markup>) -> Self {
+        for node in other.into().nodes {
+            self = match node {
+                Node::Text(text) => self.text(text),
+                node => {
+                    self.nodes.push(node);
+
+                    self
+                }
+            };
+        }
+
+        self
+    }
+
+    /// Bulleted list, every item starts from a new line
+    pub fn list<T: Into<Markup>>(self, items: impl IntoIterator<Item = T>) -> Self {
+        items.into_iter().fold(self, |markup, item| {
+            markup.item_line().text(LIST_BULLET).append(item)
+        })
+    }
+
+    /// Numbered list, every item starts from a new line
+    pub fn numbered_list<T: Into<Markup>>(self, items: impl IntoIterator<Item = T>) -> Self {
+        items
+            .into_iter()
+            .enumerate()
+            .fold(self, |markup, (idx, item)| {
+                markup
+                    .item_line()
+                    .text(format!("{}. ", idx + 1))
+                    .append(item)
+            })
+    }
+
+    /// Text for the `MarkdownV2` parse mode
+    pub fn markdown_v2(&self) -> String {
+        let mut result = String::new();
+        self.write_markdown_v2(&mut result, Context::default());
+
+        result
+    }
+
+    /// Text for the `HTML` parse mode
+    pub fn html(&self) -> String {
+        let mut result = String::new();
+        self.write_html(&mut result, Context::default());
+
+        result
+    }
+
+    /// Adds the entity, merging it with the previous one of the same kind:
+    /// otherwise their markers would stick together, e.g. `_a__b_` is read as underline
+    fn entity(mut self, content: Markup, node: fn(Markup) -> Node) -> Self {
+        if content.is_empty() {
+            return self;
+        }
+
+        let last = self.nodes.pop();
+        let node = match (last, node(Markup::new())) {
+            (Some(Node::Bold(last)), Node::Bold(_)) => Node::Bold(last.append(content)),
+            (Some(Node::Italic(last)), Node::Italic(_)) => Node::Italic(last.append(content)),
+            (Some(Node::Spoiler(last)), Node::Spoiler(_)) => Node::Spoiler(last.append(content)),
+            (last, _) => {
+                self.nodes.extend(last);
+
+                node(content)
+            }
+        };
+        self.nodes.push(node);
+
+        self
+    }
+
+    fn item_line(self) -> Self {
+        match self.nodes.last() {
+            None => self,
+            Some(Node::Text(text)) if text.ends_with('\n') => self,
+            Some(_) => self.line(),
+        }
+    }
+
+    fn write_markdown_v2(&self, result: &mut String, context: Context) {
+        for node in &self.nodes {
+            match node {
+                Node::Text(text) => escape_markdown_v2(result, text, MARKDOWN_V2_SPECIAL),
+                Node::Bold(content) => {
+                    let nested = Context {
+                        bold: true,
+                        ..context
+                    };
+                    wrap_markdown_v2(result, "*", content, context.bold, nested);
+                }
+                Node::Italic(content) => {
+                    let nested = Context {
+                        italic: true,
+                        ..context
+                    };
+                    wrap_markdown_v2(result, "_", content, context.italic, nested);
+                }
+                Node::Spoiler(content) => {
+                    let nested = Context {
+                        spoiler: true,
+                        ..context
+                    };
+                    wrap_markdown_v2(result, "||", content, context.spoiler, nested);
+                }
+                Node::Code(code) => {
+                    result.push('`');
+                    escape_markdown_v2(result, code, MARKDOWN_V2_CODE_SPECIAL);
+                    result.push('`');
+                }
+                Node::Pre { code, language } => {
+                    result.push_str("```");
+                    if let Some(language) = language {
+                        result.extend(language.chars().filter(is_language_char));
+                    }
+                    result.push('\n');
+                    escape_markdown_v2(result, code, MARKDOWN_V2_CODE_SPECIAL);
+                    result.push_str("\n```");
+                }
+                Node::Link { text, url } if !context.link => {
+                    result.push('[');
+                    text.write_markdown_v2(
+                        result,
+                        Context {
+                            link: true,
+                            ..context
+                        },
+                    );
+                    result.push_str("](");
+                    escape_markdown_v2(result, url, MARKDOWN_V2_URL_SPECIAL);
+                    result.push(')');
+                }
+                Node::Link { text, .. } => text.write_markdown_v2(result, context),
+            }
+        }
+    }
+
+    fn write_html(&self, result: &mut String, context: Context) {
+        for node in &self.nodes {
+            match node {
+                Node::Text(text) => escape_html(result, text),
+                Node::Bold(content) => {
+                    let nested = Context {
+                        bold: true,
+                        ..context
+                    };
+                    wrap_html(result, "b", content, context.bold, nested);
+                }
+                Node::Italic(content) => {
+                    let nested = Context {
+                        italic: true,
+                        ..context
+                    };
+                    wrap_html(result, "i", content, context.italic, nested);
+                }
+                Node::Spoiler(content) => {
+                    let nested = Context {
+                        spoiler: true,
+                        ..context
+                    };
+                    wrap_html(result, "tg-spoiler", content, context.spoiler, nested);
+                }
+                Node::Code(code) => {
+                    result.push_str("<code>");
+                    escape_html(result, code);
+                    result.push_str("</code>");
+                }
+                Node::Pre { code, language } => {
+                    result.push_str("<pre>");
+                    match language {
+                        Some(language) => {
+                            result.push_str("<code class=\"language-");
+                            result.extend(language.chars().filter(is_language_char));
+                            result.push_str("\">");
+                            escape_html(result, code);
+                            result.push_str("</code>");
+                        }
+                        None => escape_html(result, code),
+                    }
+                    result.push_str("</pre>");
+                }
+                Node::Link { text, url } if !context.link => {
+                    result.push_str("<a href=\"");
+                    escape_html(result, url);
+                    result.push_str("\">");
+                    text.write_html(
+                        result,
+                        Context {
+                            link: true,
+                            ..context
+                        },
+                    );
+                    result.push_str("</a>");
+                }
+                Node::Link { text, .. } => text.write_html(result, context),
+            }
+        }
+    }
+}
+
+impl From<&str> for Markup {
+    fn from(text: &str) -> Self {
+        Self::new().text(text)
+    }
+}
+
+impl From<String> for Markup {
+    fn from(text: String) -> Self {
+        Self::new().text(text)
+    }
+}
+
+impl From<&String> for Markup {
+    fn from(text: &String) -> Self {
+        Self::new().text(text.as_str())
+    }
+}
+
+/// Characters, which must be escaped in `MarkdownV2` text
+pub(crate) const MARKDOWN_V2_SPECIAL: &[char] = &[
+    '_', '*', '[', ']', '(', ')', '~', '`', '>', '#', '+', '-', '=', '|', '{', '}', '.', '!', '\\',
+];
+const MARKDOWN_V2_CODE_SPECIAL: &[char] = &['`', '\\'];
+const MARKDOWN_V2_URL_SPECIAL: &[char] = &[')', '\\'];
+
+pub(crate) fn escape_markdown_v2(result: &mut String, text: &str, special: &[char]) {
+    for c in text.chars() {
+        if special.contains(&c) {
+            result.push('\\');
+        }
+        result.push(c);
+    }
+}
+
+/// Entity, which is nested into the same one, is written without its markers
+fn wrap_markdown_v2(
+    result: &mut String,
+    marker: &str,
+    content: &Markup,
+    is_nested: bool,
+    context: Context,
+) {
+    if !is_nested {
+        result.push_str(marker);
+    }
+    content.write_markdown_v2(result, context);
+    if !is_nested {
+        result.push_str(marker);
+    }
+}
+
+fn escape_html(result: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '&' => result.push_str("&amp;"),
+            '"' => result.push_str("&quot;"),
+            c => result.push(c),
+        }
+    }
+}
+
+fn wrap_html(result: &mut String, tag: &str, content: &Markup, is_nested: bool, context: Context) {
+    if !is_nested {
+        result.push('<');
+        result.push_str(tag);
+        result.push('>');
+    }
+    content.write_html(result, context);
+    if !is_nested {
+        result.push_str("</");
+        result.push_str(tag);
+        result.push('>');
+    }
+}
+
+fn is_language_char(c: &char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '-' | '#')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escaping() {
+        let markup = Markup::new()
+            .text("1 + 1 = 2. ")
+            .code("let s = `a\\b`;")
+            .text(" ")
+            .link("[docs]", "https://example.com/a_(b)");
+
+        assert_eq!(
+            markup.markdown_v2(),
+            r"1 \+ 1 \= 2\. `let s = \`a\\b\`;` [\[docs\]](https://example.com/a_(b\))"
+        );
+        assert_eq!(
+            markup.html(),
+            r#"1 + 1 = 2. <code>let s = `a\b`;</code> <a href="https://example.com/a_(b)">[docs]</a>"#
+        );
+    }
+
+    #[test]
+    fn nesting() {
+        let markup = Markup::new()
+            .bold(Markup::new().text("a ").bold("b").italic("c"))
+            .italic("d")
+            .italic("e")
+            .spoiler(
+                Markup::new()
+                    .link("f", "https://t.me")
+                    .link("g", "https://t.me"),
+            )
+            .bold("");
+
+        assert_eq!(
+            markup.markdown_v2(),
+            "*a b_c_*_de_||[f](https://t.me)[g](https://t.me)||"
+        );
+        assert_eq!(
+            markup.html(),
+            "<b>a b<i>c</i></b><i>de</i><tg-spoiler><a href=\"https://t.me\">f</a><a href=\"https://t.me\">g</a></tg-spoiler>"
+        );
+    }
+
+    #[test]
+    fn lists() {
+        let markup = Markup::new()
+            .text("Items:")
+            .list(["a-1", "b"])
+            .line()
+            .numbered_list([Markup::new().bold("c")])
+            .pre("x < y", Some("rust"));
+
+        assert_eq!(
+            markup.markdown_v2(),
+            "Items:\n• a\\-1\n• b\n1\\. *c*```rust\nx < y\n```"
+        );
+        assert_eq!(
+            markup.html(),
+            "Items:\n• a-1\n• b\n1. <b>c</b><pre><code class=\"language-rust\">x &lt; y</code></pre>"
+        );
+    }
+}
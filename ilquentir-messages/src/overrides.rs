@@ -0,0 +1,151 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{PoisonError, RwLock},
+};
+
+use once_cell::sync::Lazy;
+
+use crate::{
+    build::list_templates,
+    template::{Template, CONTEXT_VARIABLES},
+    Locale,
+};
+
+/// Templates, loaded at runtime, by locale and path
+static OVERRIDES: Lazy<RwLock<HashMap<Locale, HashMap<String, String>>>> =
+    Lazy::new(Default::default);
+
+/// Paths of the embedded templates with their placeholders and whether they use the
+/// [`CONTEXT_VARIABLES`], see [`template_index!`](crate::template_index)
+pub type TemplateIndex = &'static [(&'static str, &'static [&'static str], bool)];
+
+/// Outcome of [`load_overrides`]
+#[derive(Debug, Default)]
+pub struct LoadReport {
+    /// Number of templates, which override the embedded ones
+    pub loaded: usize,
+    /// Files, which were skipped, with the reasons
+    pub rejected: Vec<(PathBuf, String)>,
+}
+
+/// Loads templates from `<dir>/<locale>/<path>`, which override the embedded ones,
+/// replacing previously loaded overrides.
+///
+/// Templates are validated as the build script does: only embedded templates can be overridden,
+/// and they must keep the same placeholders. Context variables can be used only in the templates,
+/// which already use them, as only these are rendered with the user's context. Invalid ones
+/// are rejected, so the embedded versions stay in use.
+pub fn load_overrides(dir: &Path, index: TemplateIndex) -> io::Result<LoadReport> {
+    let mut report = LoadReport::default();
+    let mut overrides = HashMap::new();
+
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+
+        let locale = match entry.file_name().to_str().and_then(Locale::from_code) {
+            Some(locale) if entry.file_type()?.is_dir() => locale,
+            _ => {
+                report
+                    .rejected
+                    .push((path, "not a locale directory".to_owned()));
+
+                continue;
+            }
+        };
+
+        let templates: &mut HashMap<_, _> = overrides.entry(locale).or_default();
+        for template_path in list_templates(&path, Path::new(""))? {
+            let file = path.join(&template_path);
+            let name = template_path
+                .iter()
+                .map(|part| part.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+
+            match fs::read_to_string(&file)
+                .map_err(|error| error.to_string())
+                .and_then(|text| check_override(&name, &text, locale, index).map(|_| text))
+            {
+                Ok(text) => {
+                    templates.insert(name, text);
+                    report.loaded += 1;
+                }
+                Err(reason) => report.rejected.push((file, reason)),
+            }
+        }
+    }
+
+    *OVERRIDES.write().unwrap_or_else(PoisonError::into_inner) = overrides;
+
+    Ok(report)
+}
+
+/// Drops every loaded override, so only embedded templates are used
+pub fn clear_overrides() {
+    OVERRIDES
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+}
+
+fn check_override(
+    name: &str,
+    text: &str,
+    locale: Locale,
+    index: TemplateIndex,
+) -> Result<(), String> {
+    let (expected, uses_context) = if let Some((_, placeholders, uses_context)) =
+        index.iter().find(|(path, _, _)| *path == name)
+    {
+        (placeholders, *uses_context)
+    } else {
+        return Err("not an embedded template".to_owned());
+    };
+
+    let template = Template::parse(text).map_err(|error| error.to_string())?;
+    template
+        .check_plural_forms(locale)
+        .map_err(|error| error.to_string())?;
+
+    if template.arguments() != expected.iter().copied().collect::<BTreeSet<_>>() {
+        return Err("placeholders differ from the embedded template".to_owned());
+    }
+    if !uses_context
+        && template
+            .placeholders()
+            .iter()
+            .any(|name| CONTEXT_VARIABLES.contains(name))
+    {
+        return Err("context variables aren't provided to this template".to_owned());
+    }
+
+    Ok(())
+}
+
+/// Calls `f` with the overriding template of the requested locale, or of the default one,
+/// if the embedded template is its fallback as well
+pub(crate) fn with_override<T>(
+    path: &str,
+    requested: Locale,
+    embedded: Locale,
+    f: impl FnOnce(&str, Locale) -> T,
+) -> Option<T> {
+    let overrides = OVERRIDES.read().unwrap_or_else(PoisonError::into_inner);
+
+    for locale in [requested, Locale::default()] {
+        if let Some(text) = overrides
+            .get(&locale)
+            .and_then(|templates| templates.get(path))
+        {
+            return Some(f(text, locale));
+        }
+        if locale == embedded {
+            break;
+        }
+    }
+
+    None
+}
@@ -0,0 +1,106 @@
+use crate::Locale;
+
+/// Plural category of a number, as defined by
+/// [CLDR](https://www.unicode.org/cldr/charts/latest/supplemental/language_plural_rules.html).
+///
+/// Every language uses only some of them, e.g. Russian has `one` (1, 21), `few` (2, 22)
+/// and `many` (5, 11) for integers, and `other` for fractions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PluralCategory {
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
+}
+
+impl PluralCategory {
+    pub const ALL: [Self; 6] = [
+        Self::Zero,
+        Self::One,
+        Self::Two,
+        Self::Few,
+        Self::Many,
+        Self::Other,
+    ];
+
+    /// Name of the category, as it's written in the templates
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Zero => "zero",
+            Self::One => "one",
+            Self::Two => "two",
+            Self::Few => "few",
+            Self::Many => "many",
+            Self::Other => "other",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|category| category.name() == name)
+    }
+}
+
+impl Locale {
+    /// Categories, which integers fall into, so every plural form in the templates must have them
+    pub const fn plural_categories(self) -> &'static [PluralCategory] {
+        match self {
+            Self::Ru => &[
+                PluralCategory::One,
+                PluralCategory::Few,
+                PluralCategory::Many,
+            ],
+            Self::En => &[PluralCategory::One, PluralCategory::Other],
+        }
+    }
+
+    /// Plural category of the integer, fractions are `other` in every supported language
+    pub fn plural_category(self, n: i64) -> PluralCategory {
+        let n = n.unsigned_abs();
+
+        match self {
+            Self::Ru => match (n % 10, n % 100) {
+                (1, rem100) if rem100 != 11 => PluralCategory::One,
+                (2..=4, rem100) if !(12..=14).contains(&rem100) => PluralCategory::Few,
+                _ => PluralCategory::Many,
+            },
+            Self::En => match n {
+                1 => PluralCategory::One,
+                _ => PluralCategory::Other,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn russian_rules() {
+        let category = |n| Locale::Ru.plural_category(n);
+
+        for n in [1, 21, 101, 1001, -1] {
+            assert_eq!(category(n), PluralCategory::One, "{n}");
+        }
+        for n in [2, 3, 4, 22, 34, 102] {
+            assert_eq!(category(n), PluralCategory::Few, "{n}");
+        }
+        for n in [0, 5, 11, 12, 14, 19, 20, 111, 112, 1000] {
+            assert_eq!(category(n), PluralCategory::Many, "{n}");
+        }
+    }
+
+    #[test]
+    fn english_rules() {
+        assert_eq!(Locale::En.plural_category(1), PluralCategory::One);
+        assert_eq!(Locale::En.plural_category(-1), PluralCategory::One);
+
+        for n in [0, 2, 11, 21, 101] {
+            assert_eq!(Locale::En.plural_category(n), PluralCategory::Other, "{n}");
+        }
+    }
+}
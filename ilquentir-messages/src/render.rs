@@ -0,0 +1,368 @@
+use std::borrow::Cow;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use time::{Date, Time};
+
+use crate::{
+    markup::{escape_markdown_v2, MARKDOWN_V2_SPECIAL},
+    overrides::with_override,
+    template::{Segment, Template, CONTEXT_VARIABLES},
+    Locale, PluralCategory, RenderContext, TemplateError,
+};
+
+/// Value of a placeholder, numbers and dates are formatted according to the template's language
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value<'a> {
+    Text(&'a str),
+    Integer(i64),
+    Decimal(f64),
+    Date(Date),
+    Time(Time),
+}
+
+/// Digits after the decimal separator, fractions are rounded to
+const DECIMAL_DIGITS: usize = 2;
+
+/// Syntax of the rendered message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    /// Telegram's `MarkdownV2`, converted by [`tg_escape`](crate::tg_escape) from the template's
+    /// Markdown, texts of the context variables (e.g. the user's name) stay literal in it
+    Markdown,
+}
+
+/// Context texts are rendered as markers with the index of the variable in Markdown, which are
+/// replaced with the escaped texts after the template's Markdown is converted
+const MARKER_START: char = '\u{E000}';
+const MARKER_END: char = '\u{E001}';
+static MARKER: Lazy<Regex> = regex!("\u{E000}([0-9]+)\u{E001}");
+
+impl Value<'_> {
+    fn plural_category(self, locale: Locale) -> PluralCategory {
+        match self {
+            Self::Integer(n) => locale.plural_category(n),
+            Self::Text(_) | Self::Decimal(_) | Self::Date(_) | Self::Time(_) => {
+                PluralCategory::Other
+            }
+        }
+    }
+
+    fn write(self, result: &mut String, locale: Locale) {
+        match self {
+            Self::Text(text) => result.push_str(text),
+            Self::Integer(n) => result.push_str(&locale.format_integer(n)),
+            Self::Decimal(n) => result.push_str(&locale.format_decimal(n, DECIMAL_DIGITS)),
+            Self::Date(date) => result.push_str(&locale.format_date(date)),
+            Self::Time(time) => result.push_str(&locale.format_time(time)),
+        }
+    }
+}
+
+/// Types, which can be passed to [`message!`](crate::message)
+pub trait ToValue {
+    fn to_value(&self) -> Value<'_>;
+}
+
+impl ToValue for str {
+    fn to_value(&self) -> Value<'_> {
+        Value::Text(self)
+    }
+}
+
+impl ToValue for String {
+    fn to_value(&self) -> Value<'_> {
+        Value::Text(self)
+    }
+}
+
+impl ToValue for Cow<'_, str> {
+    fn to_value(&self) -> Value<'_> {
+        Value::Text(self)
+    }
+}
+
+macro_rules! impl_to_value_for_integers {
+    ($($integer:ty),*) => {
+        $(
+            impl ToValue for $integer {
+                fn to_value(&self) -> Value<'_> {
+                    Value::Integer(i64::try_from(*self).unwrap_or(i64::MAX))
+                }
+            }
+        )*
+    };
+}
+
+impl_to_value_for_integers!(i8, i16, i32, i64, u8, u16, u32, u64, usize);
+
+impl ToValue for f64 {
+    fn to_value(&self) -> Value<'_> {
+        Value::Decimal(*self)
+    }
+}
+
+impl ToValue for f32 {
+    fn to_value(&self) -> Value<'_> {
+        Value::Decimal((*self).into())
+    }
+}
+
+impl ToValue for Date {
+    fn to_value(&self) -> Value<'_> {
+        Value::Date(*self)
+    }
+}
+
+impl ToValue for Time {
+    fn to_value(&self) -> Value<'_> {
+        Value::Time(*self)
+    }
+}
+
+impl<T: ToValue + ?Sized> ToValue for &T {
+    fn to_value(&self) -> Value<'_> {
+        (**self).to_value()
+    }
+}
+
+/// Template, embedded into the binary by [`message!`](crate::message)
+#[derive(Debug, Clone, Copy)]
+pub struct Embedded {
+    /// Path of the template, relative to the locale directory
+    pub path: &'static str,
+    pub text: &'static str,
+    /// Code of the template's language, untranslated templates are in the default one
+    pub locale: &'static str,
+}
+
+/// Renders the template in the context's locale with the given values of the placeholders,
+/// the embedded template is used, unless it's overridden by [`load_overrides`](crate::load_overrides).
+///
+/// Overrides, which use context variables the context doesn't provide, fall back to the embedded
+/// template. Embedded templates with context variables only compile with a [`UserValues`] context,
+/// so the function panics only if such context misses a value.
+pub fn render(
+    context: &dyn RenderContext,
+    template: Embedded,
+    args: &[(&str, Value<'_>)],
+    format: Format,
+) -> String {
+    let locale = Locale::from_code(template.locale).unwrap_or_default();
+
+    let overridden = with_override(template.path, context.locale(), locale, |text, locale| {
+        Template::parse(text).ok().and_then(|template| {
+            template
+                .render_in_context(context, locale, args, format)
+                .ok()
+        })
+    });
+    let result = match overridden {
+        Some(Some(result)) => result,
+        _ => Template::parse(template.text)
+            .expect("embedded templates are checked by the build script")
+            .render_in_context(context, locale, args, format)
+            .unwrap_or_else(|error| panic!("failed to render {}: {error}", template.path)),
+    };
+
+    match format {
+        Format::Text => result,
+        Format::Markdown => {
+            let escaped = crate::tg_escape(&result);
+            let text_of = |index: &str| {
+                let name = CONTEXT_VARIABLES.get(index.parse::<usize>().ok()?)?;
+                match context.value(name)? {
+                    Value::Text(text) => Some(text),
+                    _ => None,
+                }
+            };
+
+            MARKER
+                .replace_all(&escaped, |captures: &regex::Captures| {
+                    let mut text = String::new();
+                    if let Some(value) = text_of(&captures[1]) {
+                        escape_markdown_v2(&mut text, value, MARKDOWN_V2_SPECIAL);
+                    }
+                    text
+                })
+                .into_owned()
+        }
+    }
+}
+
+impl Template<'_> {
+    /// Placeholders without values are left as is, plural forms without a number are `other`,
+    /// but context variables must be passed explicitly
+    pub fn render(
+        &self,
+        locale: Locale,
+        args: &[(&str, Value<'_>)],
+    ) -> Result<String, TemplateError> {
+        self.render_in_context(&locale, locale, args, Format::Text)
+    }
+
+    /// Values of the context variables are taken from the context, unless they are passed
+    /// explicitly; the ones, which the context doesn't provide, are an error
+    pub fn render_in_context(
+        &self,
+        context: &dyn RenderContext,
+        locale: Locale,
+        args: &[(&str, Value<'_>)],
+        format: Format,
+    ) -> Result<String, TemplateError> {
+        let mut result = String::new();
+        self.render_to(&mut result, context, locale, args, format, None)?;
+
+        Ok(result)
+    }
+
+    fn render_to(
+        &self,
+        result: &mut String,
+        context: &dyn RenderContext,
+        locale: Locale,
+        args: &[(&str, Value<'_>)],
+        format: Format,
+        number: Option<Value<'_>>,
+    ) -> Result<(), TemplateError> {
+        let arg = |name: &str| {
+            args.iter()
+                .find(|(arg_name, _)| *arg_name == name)
+                .map(|(_, value)| *value)
+        };
+        let value_of = |name: &str| arg(name).or_else(|| context.value(name));
+
+        let missing = |name: &str| TemplateError::MissingContextVariable {
+            name: name.to_owned(),
+        };
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => result.push_str(text),
+                Segment::Placeholder(name) => match value_of(name) {
+                    // texts of the context, e.g. the user's name, aren't written by the template's author
+                    Some(Value::Text(_)) if format == Format::Markdown && arg(name).is_none() => {
+                        let index = CONTEXT_VARIABLES
+                            .iter()
+                            .position(|variable| variable == name);
+                        if let Some(index) = index {
+                            result.push(MARKER_START);
+                            result.push_str(&index.to_string());
+                            result.push(MARKER_END);
+                        }
+                    }
+                    Some(value) => value.write(result, locale),
+                    None if CONTEXT_VARIABLES.contains(name) => return Err(missing(name)),
+                    None => {
+                        result.push('{');
+                        result.push_str(name);
+                        result.push('}');
+                    }
+                },
+                Segment::Number => match number {
+                    Some(value) => value.write(result, locale),
+                    None => result.push('#'),
+                },
+                Segment::Plural { name, .. }
+                    if value_of(name).is_none() && CONTEXT_VARIABLES.contains(name) =>
+                {
+                    return Err(missing(name))
+                }
+                Segment::Plural { name, forms } => {
+                    let value = value_of(name);
+                    let category =
+                        value.map_or(PluralCategory::Other, |value| value.plural_category(locale));
+
+                    // `other` is a fallback for fractions in languages, which don't use it for integers
+                    let form = forms
+                        .iter()
+                        .find(|(form_category, _)| *form_category == category)
+                        .or_else(|| {
+                            forms
+                                .iter()
+                                .find(|(form_category, _)| *form_category == PluralCategory::Other)
+                        })
+                        .or_else(|| forms.last());
+
+                    if let Some((_, form)) = form {
+                        form.render_to(result, context, locale, args, format, value)?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use super::*;
+
+    #[test]
+    fn plural_forms() {
+        let template = Template::parse(
+            "Ты с нами {days, plural, one {# день} few {# дня} many {# дней} other {# дня}}",
+        )
+        .expect("template is valid");
+        let render = |days: &dyn ToValue| {
+            template
+                .render(Locale::Ru, &[("days", days.to_value())])
+                .unwrap()
+        };
+
+        assert_eq!(render(&1), "Ты с нами 1 день");
+        assert_eq!(render(&22), "Ты с нами 22 дня");
+        assert_eq!(render(&11), "Ты с нами 11 дней");
+        assert_eq!(render(&12345), "Ты с нами 12\u{a0}345 дней");
+        assert_eq!(render(&1.5), "Ты с нами 1,5 дня");
+    }
+
+    #[test]
+    fn values() {
+        let template = Template::parse("{name}, {date}: {missing}").expect("template is valid");
+
+        assert_eq!(
+            template.render(
+                Locale::En,
+                &[
+                    ("name", "Ilquentir".to_value()),
+                    ("date", date!(2023 - 06 - 05).to_value())
+                ]
+            ),
+            Ok("Ilquentir, June 5, 2023: {missing}".to_owned())
+        );
+    }
+
+    #[test]
+    fn missing_context_variables() {
+        let template = Template::parse(
+            "{user_name}, {streak, plural, one {# день} few {# дня} many {# дней}} подряд",
+        )
+        .expect("template is valid");
+        let missing = |name: &str| {
+            Err(TemplateError::MissingContextVariable {
+                name: name.to_owned(),
+            })
+        };
+
+        assert_eq!(template.render(Locale::Ru, &[]), missing("user_name"));
+        assert_eq!(
+            template.render(Locale::Ru, &[("user_name", "Ильквентир".to_value())]),
+            missing("streak")
+        );
+        assert_eq!(
+            template.render(
+                Locale::Ru,
+                &[
+                    ("user_name", "Ильквентир".to_value()),
+                    ("streak", 5.to_value())
+                ]
+            ),
+            Ok("Ильквентир, 5 дней подряд".to_owned())
+        );
+    }
+}
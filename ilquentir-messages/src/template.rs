@@ -0,0 +1,352 @@
+use std::{collections::BTreeSet, fmt};
+
+use crate::{Locale, PluralCategory};
+
+/// Placeholders, which every template can use without them being passed to
+/// [`message!`](crate::message): their values are taken from the [`RenderContext`](crate::RenderContext)
+pub const CONTEXT_VARIABLES: [&str; 5] = [
+    "user_name",
+    "streak",
+    "answered_days",
+    "local_time",
+    "days_since_start",
+];
+
+/// Parsed message template.
+///
+/// Placeholders are written as in `format!`, e.g. `{name}`, braces are escaped by doubling them.
+/// Words, which depend on a number, are written as its plural forms, chosen by the CLDR category
+/// of the number: `{days, plural, one {# день} few {# дня} many {# дней}}`, where `#` stands for
+/// the number itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template<'a> {
+    pub(crate) segments: Vec<Segment<'a>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Segment<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+    /// `#`, the number of the enclosing plural form
+    Number,
+    Plural {
+        name: &'a str,
+        forms: Vec<(PluralCategory, Template<'a>)>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    Syntax {
+        /// Byte offset in the template
+        position: usize,
+        reason: &'static str,
+    },
+    MissingPluralForm {
+        name: String,
+        category: PluralCategory,
+    },
+    /// Template uses the context variable, which the context doesn't provide
+    MissingContextVariable { name: String },
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax { position, reason } => write!(f, "{reason} at byte {position}"),
+            Self::MissingPluralForm { name, category } => write!(
+                f,
+                "plural forms of `{name}` have no `{}` form",
+                category.name()
+            ),
+            Self::MissingContextVariable { name } => {
+                write!(f, "context variable `{name}` has no value")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+impl<'a> Template<'a> {
+    pub fn parse(source: &'a str) -> Result<Self, TemplateError> {
+        Parser {
+            source,
+            position: 0,
+        }
+        .template(false)
+    }
+
+    /// Names of the values, the template needs, including the numbers of plural forms
+    pub fn placeholders(&self) -> BTreeSet<&'a str> {
+        let mut placeholders = BTreeSet::new();
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Placeholder(name) => {
+                    placeholders.insert(*name);
+                }
+                Segment::Plural { name, forms } => {
+                    placeholders.insert(*name);
+
+                    for (_, form) in forms {
+                        placeholders.extend(form.placeholders());
+                    }
+                }
+                Segment::Text(_) | Segment::Number => {}
+            }
+        }
+
+        placeholders
+    }
+
+    /// Placeholders, which must be passed to [`message!`](crate::message),
+    /// i.e. all but the [`CONTEXT_VARIABLES`]
+    pub fn arguments(&self) -> BTreeSet<&'a str> {
+        let mut arguments = self.placeholders();
+        arguments.retain(|name| !CONTEXT_VARIABLES.contains(name));
+
+        arguments
+    }
+
+    /// Checks that every plural has the forms for all integers in the given language
+    pub fn check_plural_forms(&self, locale: Locale) -> Result<(), TemplateError> {
+        for segment in &self.segments {
+            if let Segment::Plural { name, forms } = segment {
+                for &category in locale.plural_categories() {
+                    if !forms
+                        .iter()
+                        .any(|(form_category, _)| *form_category == category)
+                    {
+                        return Err(TemplateError::MissingPluralForm {
+                            name: (*name).to_owned(),
+                            category,
+                        });
+                    }
+                }
+
+                for (_, form) in forms {
+                    form.check_plural_forms(locale)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// Parses the segments till the end of the source, or till the end of the plural form
+    fn template(&mut self, in_form: bool) -> Result<Template<'a>, TemplateError> {
+        let mut segments = vec![];
+
+        loop {
+            let rest = &self.source[self.position..];
+            let text_len = rest
+                .find(|c| c == '{' || c == '}' || (in_form && c == '#'))
+                .unwrap_or(rest.len());
+
+            if text_len > 0 {
+                segments.push(Segment::Text(&rest[..text_len]));
+                self.position += text_len;
+            }
+
+            let rest = &rest[text_len..];
+            if rest.is_empty() {
+                if in_form {
+                    return Err(self.error("unclosed plural form"));
+                }
+
+                break;
+            }
+
+            if rest.starts_with("{{") {
+                segments.push(Segment::Text("{"));
+                self.position += 2;
+            } else if rest.starts_with('{') {
+                segments.push(self.placeholder()?);
+            } else if in_form && rest.starts_with('}') {
+                self.position += 1;
+
+                break;
+            } else if rest.starts_with("}}") {
+                segments.push(Segment::Text("}"));
+                self.position += 2;
+            } else if rest.starts_with('}') {
+                return Err(self.error("unmatched `}`, write `}}` for a literal brace"));
+            } else {
+                segments.push(Segment::Number);
+                self.position += 1;
+            }
+        }
+
+        Ok(Template { segments })
+    }
+
+    fn placeholder(&mut self) -> Result<Segment<'a>, TemplateError> {
+        self.position += 1;
+        let name = self.identifier("expected a placeholder name")?;
+
+        self.skip_whitespace();
+        if self.eat("}") {
+            return Ok(Segment::Placeholder(name));
+        }
+        if !self.eat(",") {
+            return Err(self.error("expected `}` or `, plural,`"));
+        }
+        self.skip_whitespace();
+        if !self.eat("plural") {
+            return Err(self.error("expected `plural`"));
+        }
+        self.skip_whitespace();
+        if !self.eat(",") {
+            return Err(self.error("expected `,` after `plural`"));
+        }
+
+        let mut forms: Vec<(PluralCategory, Template)> = vec![];
+        loop {
+            self.skip_whitespace();
+            if self.eat("}") {
+                break;
+            }
+
+            let position = self.position;
+            let category = self.identifier("expected a plural category")?;
+            let category = if let Some(category) = PluralCategory::from_name(category) {
+                category
+            } else {
+                return Err(TemplateError::Syntax {
+                    position,
+                    reason: "unknown plural category",
+                });
+            };
+            if forms.iter().any(|(known, _)| *known == category) {
+                return Err(TemplateError::Syntax {
+                    position,
+                    reason: "duplicate plural category",
+                });
+            }
+
+            self.skip_whitespace();
+            if !self.eat("{") {
+                return Err(self.error("expected `{` after the plural category"));
+            }
+            forms.push((category, self.template(true)?));
+        }
+
+        if forms.is_empty() {
+            return Err(self.error("plural without forms"));
+        }
+
+        Ok(Segment::Plural { name, forms })
+    }
+
+    fn identifier(&mut self, expected: &'static str) -> Result<&'a str, TemplateError> {
+        let rest = &self.source[self.position..];
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+
+        if len == 0 || rest.starts_with(|c: char| c.is_ascii_digit()) {
+            return Err(self.error(expected));
+        }
+        self.position += len;
+
+        Ok(&rest[..len])
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.source[self.position..];
+
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        let found = self.source[self.position..].starts_with(token);
+        if found {
+            self.position += token.len();
+        }
+
+        found
+    }
+
+    fn error(&self, reason: &'static str) -> TemplateError {
+        TemplateError::Syntax {
+            position: self.position,
+            reason,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let template = Template::parse("{{{name}}}: {days, plural, one {# день} many {# дней}}")
+            .expect("template is valid");
+
+        assert_eq!(
+            template.segments,
+            [
+                Segment::Text("{"),
+                Segment::Placeholder("name"),
+                Segment::Text("}"),
+                Segment::Text(": "),
+                Segment::Plural {
+                    name: "days",
+                    forms: vec![
+                        (
+                            PluralCategory::One,
+                            Template {
+                                segments: vec![Segment::Number, Segment::Text(" день")]
+                            }
+                        ),
+                        (
+                            PluralCategory::Many,
+                            Template {
+                                segments: vec![Segment::Number, Segment::Text(" дней")]
+                            }
+                        ),
+                    ],
+                },
+            ]
+        );
+        assert_eq!(
+            template.placeholders().into_iter().collect::<Vec<_>>(),
+            ["days", "name"]
+        );
+        assert_eq!(
+            template.check_plural_forms(Locale::Ru),
+            Err(TemplateError::MissingPluralForm {
+                name: "days".to_owned(),
+                category: PluralCategory::Few
+            })
+        );
+    }
+
+    #[test]
+    fn syntax_errors() {
+        let reason = |source| match Template::parse(source) {
+            Err(TemplateError::Syntax { reason, .. }) => reason,
+            other => panic!("{source:?} parsed as {other:?}"),
+        };
+
+        assert_eq!(reason("{}"), "expected a placeholder name");
+        assert_eq!(reason("{0}"), "expected a placeholder name");
+        assert_eq!(reason("{name"), "expected `}` or `, plural,`");
+        assert_eq!(reason("}"), "unmatched `}`, write `}}` for a literal brace");
+        assert_eq!(reason("{n, plural, some {#}}"), "unknown plural category");
+        assert_eq!(
+            reason("{n, plural, one {#} one {#}}"),
+            "duplicate plural category"
+        );
+        assert_eq!(reason("{n, plural, one {#"), "unclosed plural form");
+    }
+}
@@ -2,4 +2,6 @@
 fn main() {
     // trigger recompilation when a new migration is added
     println!("cargo:rerun-if-changed=migrations");
+
+    ilquentir_messages::build::prepare_templates().expect("failed to prepare message templates");
 }
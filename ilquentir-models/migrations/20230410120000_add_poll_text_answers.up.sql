@@ -0,0 +1,12 @@
+-- Add up migration script here
+CREATE TABLE poll_text_answers (
+    id BIGSERIAL PRIMARY KEY NOT NULL,
+    poll_id BIGINT NOT NULL UNIQUE,
+    text TEXT NOT NULL,
+
+-- meta fields
+    date_created TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT fk_poll
+        FOREIGN KEY(poll_id) REFERENCES polls(id)
+);
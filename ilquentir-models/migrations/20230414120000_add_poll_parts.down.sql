@@ -0,0 +1,59 @@
+-- Add down migration script here
+ALTER TABLE polls ADD COLUMN tg_id VARCHAR(50) UNIQUE;
+ALTER TABLE polls ADD COLUMN tg_message_id INTEGER;
+
+-- the first part is stored in the poll itself
+UPDATE polls SET
+    tg_id = first_parts.tg_id,
+    tg_message_id = first_parts.tg_message_id
+FROM (
+    SELECT DISTINCT ON (poll_id)
+        poll_id,
+        tg_id,
+        tg_message_id
+    FROM poll_parts
+    ORDER BY
+        poll_id, id
+) first_parts
+WHERE
+    polls.id = first_parts.poll_id;
+
+-- every other part becomes a separate published poll, as it was before
+INSERT INTO polls (
+    tg_id,
+    tg_message_id,
+    chat_tg_id,
+    kind,
+    publication_date,
+    published,
+    overdue
+)
+SELECT
+    poll_parts.tg_id,
+    poll_parts.tg_message_id,
+    polls.chat_tg_id,
+    polls.kind,
+    polls.publication_date,
+    polls.published,
+    polls.overdue
+FROM poll_parts
+JOIN polls
+ON
+    poll_parts.poll_id = polls.id
+WHERE
+    poll_parts.tg_id IS DISTINCT FROM polls.tg_id;
+
+-- selected values become indices of options in the part again
+UPDATE poll_answers SET selected_value = poll_answers.selected_value - poll_parts.options_offset
+FROM poll_parts
+WHERE
+    poll_answers.poll_tg_id = poll_parts.tg_id;
+
+DROP INDEX IF EXISTS poll_answers_logical_poll_fk;
+ALTER TABLE poll_answers DROP CONSTRAINT fk_poll_part;
+ALTER TABLE poll_answers DROP CONSTRAINT fk_poll;
+ALTER TABLE poll_answers DROP COLUMN poll_id;
+ALTER TABLE poll_answers ADD CONSTRAINT fk_poll
+    FOREIGN KEY(poll_tg_id) REFERENCES polls(tg_id);
+
+DROP TABLE poll_parts;
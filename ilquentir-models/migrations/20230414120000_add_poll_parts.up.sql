@@ -0,0 +1,78 @@
+-- Add up migration script here
+-- one logical poll can be sent as several Telegram messages (polls have <= 10 options)
+CREATE TABLE poll_parts (
+    id BIGSERIAL PRIMARY KEY NOT NULL,
+    poll_id BIGINT NOT NULL,
+    tg_id VARCHAR(50) UNIQUE,
+    tg_message_id INTEGER,
+    -- index of the first option of this part in the logical poll
+    options_offset INT NOT NULL DEFAULT 0,
+
+-- meta fields
+    date_created TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT fk_poll
+        FOREIGN KEY(poll_id) REFERENCES polls(id)
+);
+
+CREATE INDEX poll_parts_poll_fk ON poll_parts (poll_id);
+
+-- previously every extra part was saved as a separate published poll,
+-- the earliest one is treated as the logical poll
+CREATE TEMPORARY TABLE poll_parts_migration ON COMMIT DROP AS
+SELECT
+    polls.id AS part_poll_id,
+    MIN(polls.id) OVER (
+        PARTITION BY polls.chat_tg_id, polls.kind, polls.publication_date
+    ) AS poll_id,
+    polls.tg_id,
+    polls.tg_message_id
+FROM polls
+WHERE
+    polls.published;
+
+-- the offset of a part is the number of options in the preceding parts; options were split
+-- into chunks of the same size, only the last part could be shorter, and chunks had 10 options
+-- (the Telegram limit), unless that left a single option to the last part, which happened
+-- rarely enough to accept gaps between option indices of such polls
+INSERT INTO poll_parts (poll_id, tg_id, tg_message_id, options_offset)
+SELECT
+    poll_id,
+    tg_id,
+    tg_message_id,
+    10 * (ROW_NUMBER() OVER (PARTITION BY poll_id ORDER BY part_poll_id) - 1)
+FROM poll_parts_migration
+WHERE
+    tg_id IS NOT NULL
+    OR tg_message_id IS NOT NULL
+ORDER BY
+    part_poll_id;
+
+-- answers now reference the logical poll
+ALTER TABLE poll_answers ADD COLUMN poll_id BIGINT;
+
+-- selected values become indices of options in the logical poll
+UPDATE poll_answers SET
+    poll_id = poll_parts.poll_id,
+    selected_value = poll_answers.selected_value + poll_parts.options_offset
+FROM poll_parts
+WHERE
+    poll_answers.poll_tg_id = poll_parts.tg_id;
+
+ALTER TABLE poll_answers ALTER COLUMN poll_id SET NOT NULL;
+ALTER TABLE poll_answers DROP CONSTRAINT fk_poll;
+ALTER TABLE poll_answers ADD CONSTRAINT fk_poll
+    FOREIGN KEY(poll_id) REFERENCES polls(id);
+ALTER TABLE poll_answers ADD CONSTRAINT fk_poll_part
+    FOREIGN KEY(poll_tg_id) REFERENCES poll_parts(tg_id);
+
+CREATE INDEX poll_answers_logical_poll_fk ON poll_answers (poll_id);
+
+DELETE FROM polls
+USING poll_parts_migration
+WHERE
+    polls.id = poll_parts_migration.part_poll_id
+    AND poll_parts_migration.part_poll_id <> poll_parts_migration.poll_id;
+
+ALTER TABLE polls DROP COLUMN tg_id;
+ALTER TABLE polls DROP COLUMN tg_message_id;
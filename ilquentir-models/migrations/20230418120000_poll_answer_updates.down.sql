@@ -0,0 +1,7 @@
+-- Add down migration script here
+DROP TABLE poll_answers_history;
+
+ALTER TABLE poll_answers DROP CONSTRAINT poll_answers_poll_tg_id_user_tg_id_selected_value_key;
+ALTER TABLE poll_answers DROP COLUMN user_tg_id;
+
+ALTER TABLE poll_parts DROP COLUMN options;
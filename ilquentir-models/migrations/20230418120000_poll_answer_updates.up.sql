@@ -0,0 +1,47 @@
+-- Add up migration script here
+-- option texts are needed to save answers from PollAnswer updates, which contain only indices
+ALTER TABLE poll_parts ADD COLUMN options VARCHAR(100)[] NOT NULL DEFAULT '{}';
+
+-- answers are attributed to the user
+ALTER TABLE poll_answers ADD COLUMN user_tg_id BIGINT;
+
+UPDATE poll_answers SET user_tg_id = polls.chat_tg_id
+FROM polls
+WHERE
+    poll_answers.poll_id = polls.id;
+
+ALTER TABLE poll_answers ALTER COLUMN user_tg_id SET NOT NULL;
+ALTER TABLE poll_answers ADD CONSTRAINT fk_users
+    FOREIGN KEY(user_tg_id) REFERENCES users(tg_id);
+
+-- re-voting used to duplicate answers, keep the first one; answers to different parts
+-- are different options, even if they share the index
+DELETE FROM poll_answers
+USING poll_answers AS duplicate
+WHERE
+    poll_answers.poll_tg_id = duplicate.poll_tg_id
+    AND poll_answers.user_tg_id = duplicate.user_tg_id
+    AND poll_answers.selected_value = duplicate.selected_value
+    AND poll_answers.id > duplicate.id;
+
+ALTER TABLE poll_answers ADD CONSTRAINT poll_answers_poll_tg_id_user_tg_id_selected_value_key
+    UNIQUE (poll_tg_id, user_tg_id, selected_value);
+
+-- every change of user's selection, empty selection means the vote was retracted
+CREATE TABLE poll_answers_history (
+    id BIGSERIAL PRIMARY KEY NOT NULL,
+    poll_id BIGINT NOT NULL,
+    poll_tg_id VARCHAR(50) NOT NULL,
+    user_tg_id BIGINT NOT NULL,
+    selected_values INT[] NOT NULL,
+
+-- meta fields
+    date_created TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT fk_poll
+        FOREIGN KEY(poll_id) REFERENCES polls(id),
+    CONSTRAINT fk_users
+        FOREIGN KEY(user_tg_id) REFERENCES users(tg_id)
+);
+
+CREATE INDEX poll_answers_history_poll_fk ON poll_answers_history (poll_id);
@@ -0,0 +1,14 @@
+-- Add down migration script here
+DELETE FROM poll_answers_history WHERE poll_tg_id IS NULL;
+ALTER TABLE poll_answers_history ALTER COLUMN poll_tg_id SET NOT NULL;
+ALTER TABLE poll_answers_history DROP COLUMN poll_part_id;
+
+DELETE FROM poll_answers WHERE poll_tg_id IS NULL;
+ALTER TABLE poll_answers ALTER COLUMN poll_tg_id SET NOT NULL;
+ALTER TABLE poll_answers DROP CONSTRAINT poll_answers_poll_part_id_user_tg_id_selected_value_key;
+ALTER TABLE poll_answers ADD CONSTRAINT poll_answers_poll_tg_id_user_tg_id_selected_value_key
+    UNIQUE (poll_tg_id, user_tg_id, selected_value);
+DROP INDEX IF EXISTS poll_answers_poll_part_fk;
+ALTER TABLE poll_answers DROP COLUMN poll_part_id;
+
+ALTER TABLE poll_settings DROP COLUMN answer_mode;
@@ -0,0 +1,34 @@
+-- Add up migration script here
+ALTER TABLE poll_settings ADD COLUMN answer_mode VARCHAR(20) NOT NULL DEFAULT 'native_poll';
+
+-- polls rendered as inline keyboards don't have Telegram poll id,
+-- so answers reference the poll part directly
+ALTER TABLE poll_answers ADD COLUMN poll_part_id BIGINT;
+
+UPDATE poll_answers SET poll_part_id = poll_parts.id
+FROM poll_parts
+WHERE
+    poll_answers.poll_tg_id = poll_parts.tg_id;
+
+ALTER TABLE poll_answers ALTER COLUMN poll_part_id SET NOT NULL;
+ALTER TABLE poll_answers ALTER COLUMN poll_tg_id DROP NOT NULL;
+ALTER TABLE poll_answers ADD CONSTRAINT fk_poll_part_id
+    FOREIGN KEY(poll_part_id) REFERENCES poll_parts(id);
+
+CREATE INDEX poll_answers_poll_part_fk ON poll_answers (poll_part_id);
+
+ALTER TABLE poll_answers DROP CONSTRAINT poll_answers_poll_tg_id_user_tg_id_selected_value_key;
+ALTER TABLE poll_answers ADD CONSTRAINT poll_answers_poll_part_id_user_tg_id_selected_value_key
+    UNIQUE (poll_part_id, user_tg_id, selected_value);
+
+ALTER TABLE poll_answers_history ADD COLUMN poll_part_id BIGINT;
+
+UPDATE poll_answers_history SET poll_part_id = poll_parts.id
+FROM poll_parts
+WHERE
+    poll_answers_history.poll_tg_id = poll_parts.tg_id;
+
+ALTER TABLE poll_answers_history ALTER COLUMN poll_part_id SET NOT NULL;
+ALTER TABLE poll_answers_history ALTER COLUMN poll_tg_id DROP NOT NULL;
+ALTER TABLE poll_answers_history ADD CONSTRAINT fk_poll_part
+    FOREIGN KEY(poll_part_id) REFERENCES poll_parts(id);
@@ -0,0 +1,16 @@
+-- Add up migration script here
+CREATE TABLE poll_answer_notes (
+    id BIGSERIAL PRIMARY KEY NOT NULL,
+    poll_id BIGINT NOT NULL UNIQUE,
+    -- message with the follow-up question, to which user replies
+    tg_message_id INTEGER NOT NULL,
+    -- NULL until user answers the follow-up question
+    text TEXT,
+
+-- meta fields
+    date_created TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    date_answered TIMESTAMP WITH TIME ZONE,
+
+    CONSTRAINT fk_poll
+        FOREIGN KEY(poll_id) REFERENCES polls(id)
+);
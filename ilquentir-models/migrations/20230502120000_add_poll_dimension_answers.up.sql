@@ -0,0 +1,22 @@
+-- Add up migration script here
+CREATE TABLE poll_dimension_answers (
+    id BIGSERIAL PRIMARY KEY NOT NULL,
+    poll_id BIGINT NOT NULL,
+    poll_part_id BIGINT NOT NULL,
+    user_tg_id BIGINT NOT NULL,
+    dimension VARCHAR(100) NOT NULL,
+    value INTEGER NOT NULL CHECK (value BETWEEN 1 AND 5),
+
+-- meta fields
+    date_created TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    date_updated TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    UNIQUE (poll_id, dimension),
+
+    CONSTRAINT fk_poll
+        FOREIGN KEY(poll_id) REFERENCES polls(id),
+    CONSTRAINT fk_poll_part
+        FOREIGN KEY(poll_part_id) REFERENCES poll_parts(id),
+    CONSTRAINT fk_user
+        FOREIGN KEY(user_tg_id) REFERENCES users(tg_id)
+);
@@ -0,0 +1,5 @@
+-- Add down migration script here
+ALTER TABLE poll_answers DROP COLUMN normalized_value;
+
+ALTER TABLE poll_settings DROP COLUMN scale_labels;
+ALTER TABLE poll_settings DROP COLUMN scale_points;
@@ -0,0 +1,15 @@
+-- Add up migration script here
+ALTER TABLE poll_settings ADD COLUMN scale_points INTEGER;
+-- empty means default labels for the scale
+ALTER TABLE poll_settings ADD COLUMN scale_labels VARCHAR(100)[] NOT NULL DEFAULT '{}';
+
+-- position of the answer on the scale, from -1 (worst) to 1 (best),
+-- so answers on scales of different resolution are comparable
+ALTER TABLE poll_answers ADD COLUMN normalized_value DOUBLE PRECISION;
+
+-- all the historic mood answers were given on a 5-point scale, where 0 is the best option
+UPDATE poll_answers SET normalized_value = 1 - selected_value / 2.0
+FROM polls
+WHERE
+    poll_answers.poll_id = polls.id
+    AND polls.kind = 'how_was_your_day';
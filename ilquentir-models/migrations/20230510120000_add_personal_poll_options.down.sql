@@ -0,0 +1,7 @@
+-- Add down migration script here
+DROP TABLE personal_option_edits;
+
+DELETE FROM poll_custom_options WHERE is_personal OR NOT enabled;
+
+ALTER TABLE poll_custom_options DROP COLUMN enabled;
+ALTER TABLE poll_custom_options DROP COLUMN is_personal;
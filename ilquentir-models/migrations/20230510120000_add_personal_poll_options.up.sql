@@ -0,0 +1,22 @@
+-- Add up migration script here
+-- personal options are added by the user, catalog ones come from the bot;
+-- disabled personal options are kept, so user doesn't have to type them again
+ALTER TABLE poll_custom_options ADD COLUMN is_personal BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE poll_custom_options ADD COLUMN enabled BOOLEAN NOT NULL DEFAULT TRUE;
+
+-- personal option, which user is adding (option_id is NULL) or renaming right now
+CREATE TABLE personal_option_edits (
+    user_tg_id BIGINT PRIMARY KEY NOT NULL,
+    poll_kind VARCHAR(20) NOT NULL,
+    option_id BIGINT,
+    -- message asking for the option text, to which user replies
+    tg_message_id INTEGER NOT NULL,
+
+-- meta fields
+    date_created TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT fk_user
+        FOREIGN KEY(user_tg_id) REFERENCES users(tg_id),
+    CONSTRAINT fk_option
+        FOREIGN KEY(option_id) REFERENCES poll_custom_options(id) ON DELETE CASCADE
+);
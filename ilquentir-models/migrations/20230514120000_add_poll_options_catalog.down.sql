@@ -0,0 +1,60 @@
+-- Add down migration script here
+DELETE FROM personal_option_edits WHERE option_id IS NOT NULL;
+ALTER TABLE personal_option_edits DROP CONSTRAINT fk_option;
+ALTER TABLE personal_option_edits ADD CONSTRAINT fk_option
+    FOREIGN KEY(option_id) REFERENCES poll_custom_options(id) ON DELETE CASCADE;
+
+ALTER TABLE poll_dimension_answers DROP COLUMN option_id;
+ALTER TABLE poll_answers DROP COLUMN option_id;
+ALTER TABLE poll_parts DROP COLUMN option_ids;
+
+ALTER TABLE poll_custom_options DROP CONSTRAINT poll_custom_options_poll_kind_user_tg_id_option_id_key;
+ALTER TABLE poll_custom_options ALTER COLUMN option_id DROP NOT NULL;
+ALTER TABLE poll_custom_options ADD COLUMN option_text VARCHAR;
+ALTER TABLE poll_custom_options ADD COLUMN is_personal BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE poll_custom_options ADD COLUMN enabled BOOLEAN NOT NULL DEFAULT TRUE;
+
+UPDATE poll_custom_options SET
+    option_text = poll_option_latest_texts.text,
+    is_personal = poll_options.owner_tg_id IS NOT NULL
+FROM poll_options
+JOIN poll_option_latest_texts
+ON
+    poll_options.id = poll_option_latest_texts.option_id
+WHERE
+    poll_custom_options.option_id = poll_options.id;
+
+ALTER TABLE poll_custom_options ADD CONSTRAINT poll_custom_options_poll_kind_user_tg_id_option_text_key
+    UNIQUE (poll_kind, user_tg_id, option_text);
+
+-- personal options, which aren't chosen, were stored as disabled
+INSERT INTO poll_custom_options (poll_kind, user_tg_id, option_text, is_personal, enabled)
+SELECT
+    poll_options.poll_kind,
+    poll_options.owner_tg_id,
+    poll_option_latest_texts.text,
+    TRUE,
+    FALSE
+FROM poll_options
+JOIN poll_option_latest_texts
+ON
+    poll_options.id = poll_option_latest_texts.option_id
+WHERE
+    poll_options.owner_tg_id IS NOT NULL
+    AND NOT poll_options.retired
+    AND NOT EXISTS (
+        SELECT
+            1
+        FROM poll_custom_options
+        WHERE
+            poll_custom_options.option_id = poll_options.id
+    )
+ON CONFLICT (poll_kind, user_tg_id, option_text) DO NOTHING;
+
+ALTER TABLE poll_custom_options ALTER COLUMN option_text SET NOT NULL;
+ALTER TABLE poll_custom_options DROP COLUMN option_id;
+
+DROP VIEW poll_option_latest_texts;
+
+DROP TABLE poll_option_texts;
+DROP TABLE poll_options;
@@ -0,0 +1,248 @@
+-- Add up migration script here
+-- options are identified by id, so fixing a typo in the text doesn't orphan selections and answers
+CREATE TABLE poll_options (
+    id BIGSERIAL PRIMARY KEY NOT NULL,
+    poll_kind VARCHAR(20) NOT NULL,
+    -- NULL for catalog options, author for personal ones
+    owner_tg_id BIGINT,
+    -- order of the option in the catalog
+    position INTEGER NOT NULL DEFAULT 0,
+    -- enabled for users, who haven't chosen anything themselves
+    is_default BOOLEAN NOT NULL DEFAULT FALSE,
+    -- retired options are kept for historic answers, but can't be chosen anymore
+    retired BOOLEAN NOT NULL DEFAULT FALSE,
+
+-- meta fields
+    date_created TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT fk_owner
+        FOREIGN KEY(owner_tg_id) REFERENCES users(tg_id)
+);
+
+CREATE INDEX poll_options_owner_fk ON poll_options (owner_tg_id);
+
+-- every edit of the option text is a new version, the latest one is shown to users
+CREATE TABLE poll_option_texts (
+    id BIGSERIAL PRIMARY KEY NOT NULL,
+    option_id BIGINT NOT NULL,
+    version INTEGER NOT NULL,
+    text VARCHAR(100) NOT NULL,
+
+-- meta fields
+    date_created TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    UNIQUE (option_id, version),
+
+    CONSTRAINT fk_option
+        FOREIGN KEY(option_id) REFERENCES poll_options(id)
+);
+
+CREATE VIEW poll_option_latest_texts AS
+SELECT DISTINCT ON (option_id)
+    option_id,
+    version,
+    text
+FROM poll_option_texts
+ORDER BY
+    option_id,
+    version DESC;
+
+-- temporary column to map existing texts onto new options
+ALTER TABLE poll_options ADD COLUMN migration_text VARCHAR(100);
+
+INSERT INTO poll_options (poll_kind, position, migration_text)
+SELECT
+    'daily_events',
+    position,
+    text
+FROM unnest(ARRAY[
+    'Выход на улицу (>10 минут)',
+    'Сон >6 часов',
+    'Общение вживую',
+    'Спорт/активность',
+    'Регулярное питание',
+    'Хобби/обучение/свой проект',
+    'Позитивные личные события',
+    'Конфликты с людьми (в том числе онлайн)',
+    'Важные новости о внешнем мире',
+    'Контакты с семьёй',
+    'Солнечно на улице',
+    'Стресс на работе',
+    'Секс',
+    'Алкоголь',
+    'Вещества',
+    'Поездка за город/путешествие',
+    'Проблемы со здоровьем'
+]) WITH ORDINALITY AS catalog(text, position);
+
+INSERT INTO poll_options (poll_kind, position, is_default, migration_text)
+SELECT
+    'mood_check_in',
+    position,
+    position <= 3,
+    text
+FROM unnest(ARRAY[
+    'Энергия',
+    'Тревожность',
+    'Раздражительность',
+    'Концентрация',
+    'Мотивация',
+    'Качество сна',
+    'Физическое самочувствие'
+]) WITH ORDINALITY AS catalog(text, position);
+
+-- personal options and options, which were removed from the catalog, become personal
+INSERT INTO poll_options (poll_kind, owner_tg_id, retired, migration_text)
+SELECT DISTINCT
+    poll_custom_options.poll_kind,
+    poll_custom_options.user_tg_id,
+    FALSE,
+    poll_custom_options.option_text
+FROM poll_custom_options
+WHERE
+    NOT EXISTS (
+        SELECT
+            1
+        FROM poll_options
+        WHERE
+            poll_options.owner_tg_id IS NULL
+            AND poll_options.poll_kind = poll_custom_options.poll_kind
+            AND poll_options.migration_text = poll_custom_options.option_text
+    );
+
+-- answers to options, which don't exist anymore, are kept as retired personal options
+INSERT INTO poll_options (poll_kind, owner_tg_id, retired, migration_text)
+SELECT DISTINCT
+    polls.kind,
+    poll_answers.user_tg_id,
+    TRUE,
+    poll_answers.selected_value_text
+FROM poll_answers
+JOIN polls
+ON
+    poll_answers.poll_id = polls.id
+WHERE
+    polls.kind = 'daily_events'
+    AND poll_answers.selected_value_text <> 'Ничего'
+    AND NOT EXISTS (
+        SELECT
+            1
+        FROM poll_options
+        WHERE
+            poll_options.poll_kind = polls.kind
+            AND (
+                poll_options.owner_tg_id IS NULL
+                OR poll_options.owner_tg_id = poll_answers.user_tg_id
+            )
+            AND poll_options.migration_text = poll_answers.selected_value_text
+    );
+
+INSERT INTO poll_option_texts (option_id, version, text)
+SELECT
+    id,
+    1,
+    migration_text
+FROM poll_options;
+
+-- user's choice references options by id
+ALTER TABLE poll_custom_options ADD COLUMN option_id BIGINT;
+
+UPDATE poll_custom_options SET option_id = (
+    SELECT
+        MIN(poll_options.id)
+    FROM poll_options
+    WHERE
+        poll_options.poll_kind = poll_custom_options.poll_kind
+        AND (
+            poll_options.owner_tg_id IS NULL
+            OR poll_options.owner_tg_id = poll_custom_options.user_tg_id
+        )
+        AND poll_options.migration_text = poll_custom_options.option_text
+);
+
+-- disabled personal options exist in the catalog, but aren't chosen
+DELETE FROM poll_custom_options WHERE NOT enabled;
+
+ALTER TABLE poll_custom_options ALTER COLUMN option_id SET NOT NULL;
+ALTER TABLE poll_custom_options DROP COLUMN option_text;
+ALTER TABLE poll_custom_options DROP COLUMN is_personal;
+ALTER TABLE poll_custom_options DROP COLUMN enabled;
+ALTER TABLE poll_custom_options ADD CONSTRAINT fk_option
+    FOREIGN KEY(option_id) REFERENCES poll_options(id);
+ALTER TABLE poll_custom_options ADD CONSTRAINT poll_custom_options_poll_kind_user_tg_id_option_id_key
+    UNIQUE (poll_kind, user_tg_id, option_id);
+
+-- ids of the options of every part, NULL for options outside of the catalog
+ALTER TABLE poll_parts ADD COLUMN option_ids BIGINT[] NOT NULL DEFAULT '{}';
+
+UPDATE poll_parts SET option_ids = (
+    SELECT
+        COALESCE(ARRAY_AGG(
+            (
+                SELECT
+                    MIN(poll_options.id)
+                FROM poll_options
+                WHERE
+                    poll_options.poll_kind = polls.kind
+                    AND (
+                        poll_options.owner_tg_id IS NULL
+                        OR poll_options.owner_tg_id = polls.chat_tg_id
+                    )
+                    AND poll_options.migration_text = part_options.text
+            )
+            ORDER BY part_options.position
+        ), '{}')
+    FROM unnest(poll_parts.options) WITH ORDINALITY AS part_options(text, position)
+)
+FROM polls
+WHERE
+    poll_parts.poll_id = polls.id;
+
+-- selected option, NULL for options outside of the catalog;
+-- selected_value_text is kept as the text user have seen
+ALTER TABLE poll_answers ADD COLUMN option_id BIGINT;
+
+UPDATE poll_answers SET option_id = (
+    SELECT
+        MIN(poll_options.id)
+    FROM poll_options
+    WHERE
+        poll_options.poll_kind = polls.kind
+        AND (
+            poll_options.owner_tg_id IS NULL
+            OR poll_options.owner_tg_id = poll_answers.user_tg_id
+        )
+        AND poll_options.migration_text = poll_answers.selected_value_text
+)
+FROM polls
+WHERE
+    poll_answers.poll_id = polls.id;
+
+ALTER TABLE poll_answers ADD CONSTRAINT fk_option
+    FOREIGN KEY(option_id) REFERENCES poll_options(id);
+
+ALTER TABLE poll_dimension_answers ADD COLUMN option_id BIGINT;
+
+UPDATE poll_dimension_answers SET option_id = (
+    SELECT
+        MIN(poll_options.id)
+    FROM poll_options
+    WHERE
+        poll_options.poll_kind = 'mood_check_in'
+        AND (
+            poll_options.owner_tg_id IS NULL
+            OR poll_options.owner_tg_id = poll_dimension_answers.user_tg_id
+        )
+        AND poll_options.migration_text = poll_dimension_answers.dimension
+);
+
+ALTER TABLE poll_dimension_answers ADD CONSTRAINT fk_option
+    FOREIGN KEY(option_id) REFERENCES poll_options(id);
+
+-- edits are short-lived, it's easier to drop unfinished renames than to map them
+DELETE FROM personal_option_edits WHERE option_id IS NOT NULL;
+ALTER TABLE personal_option_edits DROP CONSTRAINT fk_option;
+ALTER TABLE personal_option_edits ADD CONSTRAINT fk_option
+    FOREIGN KEY(option_id) REFERENCES poll_options(id);
+
+ALTER TABLE poll_options DROP COLUMN migration_text;
@@ -0,0 +1,27 @@
+-- Add down migration script here
+DROP VIEW poll_option_localized_texts;
+DROP VIEW poll_option_latest_texts;
+
+DELETE FROM poll_option_texts WHERE language <> 'ru';
+ALTER TABLE poll_option_texts DROP CONSTRAINT poll_option_texts_option_id_language_version_key;
+ALTER TABLE poll_option_texts ADD CONSTRAINT poll_option_texts_option_id_version_key
+    UNIQUE (option_id, version);
+ALTER TABLE poll_option_texts DROP COLUMN language;
+
+CREATE VIEW poll_option_latest_texts AS
+SELECT DISTINCT ON (option_id)
+    option_id,
+    version,
+    text
+FROM poll_option_texts
+ORDER BY
+    option_id,
+    version DESC;
+
+-- inactive options can't be expressed anymore, so they are retired
+UPDATE poll_options SET retired = TRUE WHERE NOT active;
+ALTER TABLE poll_options DROP COLUMN active;
+ALTER TABLE poll_options DROP COLUMN category_id;
+
+DROP TABLE poll_option_category_labels;
+DROP TABLE poll_option_categories;
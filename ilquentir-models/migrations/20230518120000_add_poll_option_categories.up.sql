@@ -0,0 +1,180 @@
+-- Add up migration script here
+CREATE TABLE poll_option_categories (
+    id BIGSERIAL PRIMARY KEY NOT NULL,
+    poll_kind VARCHAR(20) NOT NULL,
+    -- short name, used by admins to reference the category
+    slug VARCHAR(50) NOT NULL,
+    -- order of the category in the settings
+    position INTEGER NOT NULL DEFAULT 0,
+
+-- meta fields
+    date_created TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    UNIQUE (poll_kind, slug)
+);
+
+CREATE TABLE poll_option_category_labels (
+    id BIGSERIAL PRIMARY KEY NOT NULL,
+    category_id BIGINT NOT NULL,
+    language VARCHAR(10) NOT NULL,
+    label VARCHAR(100) NOT NULL,
+
+-- meta fields
+    date_created TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    date_updated TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    UNIQUE (category_id, language),
+
+    CONSTRAINT fk_category
+        FOREIGN KEY(category_id) REFERENCES poll_option_categories(id) ON DELETE CASCADE
+);
+
+ALTER TABLE poll_options ADD COLUMN category_id BIGINT;
+ALTER TABLE poll_options ADD CONSTRAINT fk_category
+    FOREIGN KEY(category_id) REFERENCES poll_option_categories(id);
+-- inactive options are temporarily hidden from users, unlike retired ones they can be activated back
+ALTER TABLE poll_options ADD COLUMN active BOOLEAN NOT NULL DEFAULT TRUE;
+
+-- texts are versioned separately for every language
+ALTER TABLE poll_option_texts ADD COLUMN language VARCHAR(10) NOT NULL DEFAULT 'ru';
+ALTER TABLE poll_option_texts DROP CONSTRAINT poll_option_texts_option_id_version_key;
+ALTER TABLE poll_option_texts ADD CONSTRAINT poll_option_texts_option_id_language_version_key
+    UNIQUE (option_id, language, version);
+
+DROP VIEW poll_option_latest_texts;
+
+-- texts in the default language
+CREATE VIEW poll_option_latest_texts AS
+SELECT DISTINCT ON (option_id)
+    option_id,
+    version,
+    text
+FROM poll_option_texts
+WHERE
+    language = 'ru'
+ORDER BY
+    option_id,
+    version DESC;
+
+CREATE VIEW poll_option_localized_texts AS
+SELECT DISTINCT ON (option_id, language)
+    option_id,
+    language,
+    version,
+    text
+FROM poll_option_texts
+ORDER BY
+    option_id,
+    language,
+    version DESC;
+
+INSERT INTO poll_option_categories (poll_kind, slug, position)
+VALUES
+    ('daily_events', 'health', 1),
+    ('daily_events', 'social', 2),
+    ('daily_events', 'work', 3),
+    ('daily_events', 'outside', 4),
+    ('daily_events', 'habits', 5);
+
+INSERT INTO poll_option_category_labels (category_id, language, label)
+SELECT
+    poll_option_categories.id,
+    labels.language,
+    labels.label
+FROM (
+    VALUES
+        ('health', 'ru', 'Здоровье'),
+        ('health', 'en', 'Health'),
+        ('social', 'ru', 'Общение'),
+        ('social', 'en', 'Social'),
+        ('work', 'ru', 'Работа и дела'),
+        ('work', 'en', 'Work'),
+        ('outside', 'ru', 'Мир вокруг'),
+        ('outside', 'en', 'World around'),
+        ('habits', 'ru', 'Привычки и события'),
+        ('habits', 'en', 'Habits and events')
+) AS labels(slug, language, label)
+JOIN poll_option_categories
+ON
+    poll_option_categories.poll_kind = 'daily_events'
+    AND poll_option_categories.slug = labels.slug;
+
+-- catalog options are matched by their original text
+UPDATE poll_options SET
+    category_id = poll_option_categories.id
+FROM (
+    VALUES
+        ('Сон >6 часов', 'health'),
+        ('Спорт/активность', 'health'),
+        ('Регулярное питание', 'health'),
+        ('Проблемы со здоровьем', 'health'),
+        ('Общение вживую', 'social'),
+        ('Конфликты с людьми (в том числе онлайн)', 'social'),
+        ('Контакты с семьёй', 'social'),
+        ('Секс', 'social'),
+        ('Хобби/обучение/свой проект', 'work'),
+        ('Стресс на работе', 'work'),
+        ('Выход на улицу (>10 минут)', 'outside'),
+        ('Важные новости о внешнем мире', 'outside'),
+        ('Солнечно на улице', 'outside'),
+        ('Поездка за город/путешествие', 'outside'),
+        ('Позитивные личные события', 'habits'),
+        ('Алкоголь', 'habits'),
+        ('Вещества', 'habits')
+) AS mapping(text, slug)
+JOIN poll_option_categories
+ON
+    poll_option_categories.poll_kind = 'daily_events'
+    AND poll_option_categories.slug = mapping.slug
+JOIN poll_option_texts
+ON
+    poll_option_texts.version = 1
+    AND poll_option_texts.text = mapping.text
+WHERE
+    poll_options.id = poll_option_texts.option_id
+    AND poll_options.owner_tg_id IS NULL
+    AND poll_options.poll_kind = 'daily_events';
+
+INSERT INTO poll_option_texts (option_id, language, version, text)
+SELECT
+    poll_options.id,
+    'en',
+    1,
+    translations.translation
+FROM (
+    VALUES
+        ('Выход на улицу (>10 минут)', 'Going outside (>10 minutes)'),
+        ('Сон >6 часов', 'Sleep >6 hours'),
+        ('Общение вживую', 'Talking in person'),
+        ('Спорт/активность', 'Sport/activity'),
+        ('Регулярное питание', 'Regular meals'),
+        ('Хобби/обучение/свой проект', 'Hobby/learning/own project'),
+        ('Позитивные личные события', 'Positive personal events'),
+        ('Конфликты с людьми (в том числе онлайн)', 'Conflicts with people (including online)'),
+        ('Важные новости о внешнем мире', 'Important news about the world'),
+        ('Контакты с семьёй', 'Contact with family'),
+        ('Солнечно на улице', 'Sunny outside'),
+        ('Стресс на работе', 'Stress at work'),
+        ('Секс', 'Sex'),
+        ('Алкоголь', 'Alcohol'),
+        ('Вещества', 'Substances'),
+        ('Поездка за город/путешествие', 'Trip out of town/travel'),
+        ('Проблемы со здоровьем', 'Health problems'),
+        ('Энергия', 'Energy'),
+        ('Тревожность', 'Anxiety'),
+        ('Раздражительность', 'Irritability'),
+        ('Концентрация', 'Focus'),
+        ('Мотивация', 'Motivation'),
+        ('Качество сна', 'Sleep quality'),
+        ('Физическое самочувствие', 'Physical well-being')
+) AS translations(text, translation)
+JOIN poll_option_texts
+ON
+    poll_option_texts.version = 1
+    AND poll_option_texts.language = 'ru'
+    AND poll_option_texts.text = translations.text
+JOIN poll_options
+ON
+    poll_options.id = poll_option_texts.option_id
+WHERE
+    poll_options.owner_tg_id IS NULL;
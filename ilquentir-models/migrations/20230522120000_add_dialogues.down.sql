@@ -0,0 +1,32 @@
+-- Add down migration script here
+CREATE TABLE personal_option_edits (
+    user_tg_id BIGINT PRIMARY KEY NOT NULL,
+    poll_kind VARCHAR(20) NOT NULL,
+    option_id BIGINT,
+    -- message asking for the option text, to which user replies
+    tg_message_id INTEGER NOT NULL,
+
+-- meta fields
+    date_created TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT fk_user
+        FOREIGN KEY(user_tg_id) REFERENCES users(tg_id),
+    CONSTRAINT fk_option
+        FOREIGN KEY(option_id) REFERENCES poll_options(id)
+);
+
+INSERT INTO personal_option_edits (
+    user_tg_id, poll_kind, option_id, tg_message_id, date_created
+)
+SELECT
+    chat_tg_id,
+    state->>'poll_kind',
+    (state->>'option_id')::BIGINT,
+    tg_message_id,
+    date_created
+FROM dialogues
+WHERE
+    state->>'step' = 'personal_option_edit'
+    AND tg_message_id IS NOT NULL;
+
+DROP TABLE dialogues;
@@ -0,0 +1,33 @@
+-- Add up migration script here
+-- step of a multi-step conversation, next text message in the chat is an answer to it
+CREATE TABLE dialogues (
+    chat_tg_id BIGINT PRIMARY KEY NOT NULL,
+    -- what the bot is waiting for, tagged by the step name
+    state JSONB NOT NULL,
+    -- message asking the question, replies to it are accepted even after expiration
+    tg_message_id INTEGER,
+
+-- meta fields
+    date_created TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    date_expires TIMESTAMP WITH TIME ZONE NOT NULL,
+
+    CONSTRAINT fk_user
+        FOREIGN KEY(chat_tg_id) REFERENCES users(tg_id)
+);
+
+INSERT INTO dialogues (
+    chat_tg_id, state, tg_message_id, date_created, date_expires
+)
+SELECT
+    user_tg_id,
+    jsonb_build_object(
+        'step', 'personal_option_edit',
+        'poll_kind', poll_kind,
+        'option_id', option_id
+    ),
+    tg_message_id,
+    date_created,
+    date_created + INTERVAL '1 hour'
+FROM personal_option_edits;
+
+DROP TABLE personal_option_edits;
@@ -0,0 +1,3 @@
+-- Add up migration script here
+-- IANA name of the timezone, in which user picks the time of the polls
+ALTER TABLE users ADD COLUMN timezone VARCHAR(64);
@@ -0,0 +1,20 @@
+-- Add up migration script here
+-- where the user came from, parsed from the `/start` deep link; only the first one is kept
+CREATE TABLE user_sources (
+    user_tg_id BIGINT PRIMARY KEY NOT NULL,
+    -- raw deep link payload, kept as is even if it wasn't parsed
+    start_param VARCHAR(64) NOT NULL,
+    referrer_tg_id BIGINT,
+    campaign VARCHAR(64),
+    partner_code VARCHAR(64),
+
+-- meta fields
+    date_created TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT fk_user
+        FOREIGN KEY(user_tg_id) REFERENCES users(tg_id),
+    CONSTRAINT fk_referrer
+        FOREIGN KEY(referrer_tg_id) REFERENCES users(tg_id)
+);
+
+CREATE INDEX user_sources_referrer_fk ON user_sources (referrer_tg_id);
@@ -0,0 +1,7 @@
+-- Add down migration script here
+DROP INDEX users_username;
+
+ALTER TABLE users DROP COLUMN date_last_seen;
+ALTER TABLE users DROP COLUMN language_code;
+ALTER TABLE users DROP COLUMN first_name;
+ALTER TABLE users DROP COLUMN username;
@@ -0,0 +1,8 @@
+-- Add up migration script here
+-- Telegram profile, refreshed on every update from the user
+ALTER TABLE users ADD COLUMN username VARCHAR(32);
+ALTER TABLE users ADD COLUMN first_name VARCHAR(64);
+ALTER TABLE users ADD COLUMN language_code VARCHAR(35);
+ALTER TABLE users ADD COLUMN date_last_seen TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX users_username ON users (LOWER(username));
@@ -0,0 +1,3 @@
+-- Add up migration script here
+-- language, chosen by the user; Telegram client language is used until it is chosen
+ALTER TABLE users ADD COLUMN language VARCHAR(8);
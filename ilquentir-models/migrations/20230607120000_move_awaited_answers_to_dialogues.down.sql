@@ -0,0 +1,4 @@
+-- Add down migration script here
+DELETE FROM dialogues
+WHERE
+    state->>'step' IN ('answer_note', 'journal_prompt');
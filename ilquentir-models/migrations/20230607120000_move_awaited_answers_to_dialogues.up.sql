@@ -0,0 +1,51 @@
+-- Add up migration script here
+-- notes and prompts were awaited by looking for unanswered ones, now they are dialogue steps
+INSERT INTO dialogues (
+    chat_tg_id, state, tg_message_id, date_created, date_expires
+)
+SELECT DISTINCT ON (polls.chat_tg_id)
+    polls.chat_tg_id,
+    jsonb_build_object(
+        'step', 'answer_note',
+        'poll_id', poll_answer_notes.poll_id
+    ),
+    poll_answer_notes.tg_message_id,
+    poll_answer_notes.date_created,
+    poll_answer_notes.date_created + INTERVAL '2 hours'
+FROM poll_answer_notes
+JOIN polls
+ON
+    poll_answer_notes.poll_id = polls.id
+WHERE
+    poll_answer_notes.text IS NULL
+ORDER BY
+    polls.chat_tg_id, poll_answer_notes.date_created DESC
+ON CONFLICT (chat_tg_id) DO NOTHING;
+
+INSERT INTO dialogues (
+    chat_tg_id, state, tg_message_id, date_created, date_expires
+)
+SELECT DISTINCT ON (polls.chat_tg_id)
+    polls.chat_tg_id,
+    jsonb_build_object(
+        'step', 'journal_prompt',
+        'poll_id', polls.id
+    ),
+    poll_parts.tg_message_id,
+    poll_parts.date_created,
+    poll_parts.date_created + INTERVAL '1 day'
+FROM polls
+JOIN poll_parts
+ON
+    poll_parts.poll_id = polls.id
+LEFT JOIN poll_text_answers
+ON
+    poll_text_answers.poll_id = polls.id
+WHERE
+    polls.published
+    AND NOT polls.overdue
+    AND polls.kind = 'gratitude_journal'
+    AND poll_text_answers.id IS NULL
+ORDER BY
+    polls.chat_tg_id, polls.publication_date DESC
+ON CONFLICT (chat_tg_id) DO NOTHING;
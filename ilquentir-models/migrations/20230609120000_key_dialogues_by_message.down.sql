@@ -0,0 +1,15 @@
+-- Add down migration script here
+-- only the latest question in the chat is kept
+DELETE FROM dialogues
+WHERE
+    (chat_tg_id, tg_message_id) NOT IN (
+        SELECT DISTINCT ON (chat_tg_id)
+            chat_tg_id, tg_message_id
+        FROM dialogues
+        ORDER BY
+            chat_tg_id, date_created DESC, tg_message_id DESC
+    );
+
+ALTER TABLE dialogues DROP CONSTRAINT dialogues_pkey;
+ALTER TABLE dialogues ALTER COLUMN tg_message_id DROP NOT NULL;
+ALTER TABLE dialogues ADD PRIMARY KEY (chat_tg_id);
@@ -0,0 +1,10 @@
+-- Add up migration script here
+-- a chat can wait for answers to several questions at once, e.g. a journaling prompt and a note,
+-- so every question is a dialogue of its own
+DELETE FROM dialogues
+WHERE
+    tg_message_id IS NULL;
+
+ALTER TABLE dialogues DROP CONSTRAINT dialogues_pkey;
+ALTER TABLE dialogues ALTER COLUMN tg_message_id SET NOT NULL;
+ALTER TABLE dialogues ADD PRIMARY KEY (chat_tg_id, tg_message_id);
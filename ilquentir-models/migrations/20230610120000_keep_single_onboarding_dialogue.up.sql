@@ -0,0 +1,59 @@
+-- Add up migration script here
+-- onboarding wizard has a dialogue of its own, other questions in the chat don't replace it
+DELETE FROM dialogues
+WHERE
+    state->>'step' IN (
+        'onboarding_timezone', 'onboarding_location', 'onboarding_time', 'onboarding_trackers'
+    )
+    AND (chat_tg_id, tg_message_id) NOT IN (
+        SELECT DISTINCT ON (chat_tg_id)
+            chat_tg_id, tg_message_id
+        FROM dialogues
+        WHERE
+            state->>'step' IN (
+                'onboarding_timezone', 'onboarding_location', 'onboarding_time', 'onboarding_trackers'
+            )
+        ORDER BY
+            chat_tg_id, date_created DESC, tg_message_id DESC
+    );
+
+CREATE UNIQUE INDEX dialogues_onboarding ON dialogues (chat_tg_id)
+WHERE
+    state->>'step' IN (
+        'onboarding_timezone', 'onboarding_location', 'onboarding_time', 'onboarding_trackers'
+    );
+
+-- wizards, replaced by other questions, have left their users without any polls,
+-- they are finished with defaults along with the abandoned ones
+INSERT INTO dialogues (
+    chat_tg_id, state, tg_message_id, date_expires
+)
+SELECT
+    users.tg_id,
+    jsonb_build_object(
+        'step', 'onboarding_timezone',
+        'timezone', NULL,
+        'send_at_local', NULL,
+        'trackers', jsonb_build_array('how_was_your_day')
+    ),
+    -- message with the wizard is unknown, ids of the real messages start from 1
+    0,
+    NOW()
+FROM users
+WHERE
+    users.active
+    AND NOT EXISTS (
+        SELECT 1
+        FROM polls
+        WHERE
+            polls.chat_tg_id = users.tg_id
+    )
+    AND NOT EXISTS (
+        SELECT 1
+        FROM dialogues
+        WHERE
+            dialogues.chat_tg_id = users.tg_id
+            AND dialogues.state->>'step' IN (
+                'onboarding_timezone', 'onboarding_location', 'onboarding_time', 'onboarding_trackers'
+            )
+    );
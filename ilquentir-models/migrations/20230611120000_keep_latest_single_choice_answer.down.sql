@@ -0,0 +1,2 @@
+-- Add down migration script here
+-- removed answers aren't restored, they weren't current anyway
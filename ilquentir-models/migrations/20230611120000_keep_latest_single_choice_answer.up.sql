@@ -0,0 +1,11 @@
+-- Add up migration script here
+-- re-voting in single-choice polls used to add answers next to the previous ones,
+-- only the latest answer of the user is the current one
+DELETE FROM poll_answers
+USING poll_answers AS newer, polls
+WHERE
+    newer.poll_id = poll_answers.poll_id
+    AND newer.user_tg_id = poll_answers.user_tg_id
+    AND newer.id > poll_answers.id
+    AND polls.id = poll_answers.poll_id
+    AND polls.kind = 'how_was_your_day';
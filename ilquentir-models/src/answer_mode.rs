@@ -0,0 +1,13 @@
+/// Describes how the poll is rendered in Telegram
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, sqlx::Type, strum::EnumIter, strum::Display,
+)]
+#[sqlx(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum AnswerMode {
+    /// Native Telegram poll, split in several polls if there are more than 10 options
+    #[default]
+    NativePoll,
+    /// Message with options as inline keyboard buttons
+    InlineKeyboard,
+}
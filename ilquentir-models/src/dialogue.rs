@@ -0,0 +1,525 @@
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use time::{ext::NumericalDuration, Duration, OffsetDateTime, Time};
+
+use crate::{PgTransaction, PollKind};
+
+/// Personal option (see [`crate::PollOption`]), which user is adding or renaming right now
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersonalOptionEdit {
+    pub poll_kind: PollKind,
+    /// Option being renamed, `None` if user adds a new one
+    pub option_id: Option<i64>,
+}
+
+/// Label of the [`crate::MoodScale`], which user is renaming right now
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoodScaleLabelEdit {
+    pub label_idx: usize,
+}
+
+/// Follow-up question on why the day was like that, see [`crate::PollAnswerNote`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnswerNoteQuestion {
+    pub poll_id: i64,
+}
+
+/// Journaling prompt (see [`PollKind::is_prompt`]), which awaits a text answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalPrompt {
+    pub poll_id: i64,
+}
+
+/// Answers to the onboarding wizard of a new user, given so far,
+/// `None` means the question wasn't answered yet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OnboardingAnswers {
+    /// IANA name of the timezone
+    pub timezone: Option<String>,
+    /// Time of the polls in the chosen timezone
+    pub send_at_local: Option<Time>,
+    /// Polls to subscribe to
+    pub trackers: Vec<PollKind>,
+}
+
+impl Default for OnboardingAnswers {
+    fn default() -> Self {
+        Self {
+            timezone: None,
+            send_at_local: None,
+            trackers: vec![PollKind::HowWasYourDay],
+        }
+    }
+}
+
+/// What the bot is waiting for from the user
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum DialogueState {
+    PersonalOptionEdit(PersonalOptionEdit),
+    MoodScaleLabelEdit(MoodScaleLabelEdit),
+    AnswerNote(AnswerNoteQuestion),
+    JournalPrompt(JournalPrompt),
+    /// Onboarding wizard asks for the timezone, no polls are scheduled until it's finished
+    OnboardingTimezone(OnboardingAnswers),
+    /// User is asked to share the location to detect the timezone
+    OnboardingLocation(OnboardingAnswers),
+    OnboardingTime(OnboardingAnswers),
+    OnboardingTrackers(OnboardingAnswers),
+}
+
+impl DialogueState {
+    /// Steps of the onboarding wizard, as they are stored
+    const ONBOARDING_STEPS: [&'static str; 4] = [
+        "onboarding_timezone",
+        "onboarding_location",
+        "onboarding_time",
+        "onboarding_trackers",
+    ];
+
+    /// Answers without explicit reply to the question are accepted only for this long
+    fn ttl(&self) -> Duration {
+        match self {
+            Self::PersonalOptionEdit(_) | Self::MoodScaleLabelEdit(_) => 1.hours(),
+            Self::AnswerNote(_) => 2.hours(),
+            // prompt is answered at any time until the next one
+            Self::JournalPrompt(_) => 1.days(),
+            // abandoned wizard is finished with defaults after that
+            Self::OnboardingTimezone(_)
+            | Self::OnboardingLocation(_)
+            | Self::OnboardingTime(_)
+            | Self::OnboardingTrackers(_) => 1.hours(),
+        }
+    }
+
+    /// Answers of the onboarding wizard, `None` if the dialogue isn't an onboarding
+    pub fn onboarding_answers(&self) -> Option<&OnboardingAnswers> {
+        match self {
+            Self::OnboardingTimezone(answers)
+            | Self::OnboardingLocation(answers)
+            | Self::OnboardingTime(answers)
+            | Self::OnboardingTrackers(answers) => Some(answers),
+            Self::PersonalOptionEdit(_)
+            | Self::MoodScaleLabelEdit(_)
+            | Self::AnswerNote(_)
+            | Self::JournalPrompt(_) => None,
+        }
+    }
+
+    /// When the dialogue in this state, started at `now`, stops accepting answers without reply
+    fn expires_at(&self, now: OffsetDateTime) -> OffsetDateTime {
+        now + self.ttl()
+    }
+}
+
+/// Multi-step conversation in the chat: the next text message is treated as an answer
+/// to the question, instead of a diary entry.
+///
+/// Chat can wait for answers to several questions at once, they are told apart by the message
+/// with the question.
+#[derive(Debug, Clone)]
+pub struct Dialogue {
+    pub chat_tg_id: i64,
+    pub state: DialogueState,
+    /// Message with the question
+    pub tg_message_id: i32,
+    pub date_expires: OffsetDateTime,
+}
+
+impl Dialogue {
+    /// Starts the dialogue, asked by the given message, or moves it to the given state,
+    /// other questions in the chat keep waiting for their answers.
+    ///
+    /// Onboarding wizard is the only one in the chat, it continues from the given message.
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn start(
+        txn: &mut PgTransaction<'_>,
+        chat_tg_id: i64,
+        state: DialogueState,
+        tg_message_id: i32,
+    ) -> Result<Self> {
+        let date_expires = state.expires_at(OffsetDateTime::now_utc());
+
+        if state.onboarding_answers().is_some() {
+            sqlx::query!(
+                r#"
+DELETE FROM dialogues
+WHERE
+    chat_tg_id = $1
+    AND tg_message_id <> $2
+    AND state->>'step' = ANY($3)
+                "#,
+                chat_tg_id,
+                tg_message_id,
+                &DialogueState::ONBOARDING_STEPS[..] as _,
+            )
+            .execute(&mut *txn)
+            .await?;
+        }
+
+        sqlx::query!(
+            r#"
+INSERT INTO dialogues (
+    chat_tg_id, state, tg_message_id, date_expires
+)
+VALUES ($1, $2, $3, $4)
+ON CONFLICT (chat_tg_id, tg_message_id) DO UPDATE
+SET
+    state = EXCLUDED.state,
+    date_created = NOW(),
+    date_expires = EXCLUDED.date_expires
+            "#,
+            chat_tg_id,
+            Json(&state) as _,
+            tg_message_id,
+            date_expires,
+        )
+        .execute(txn)
+        .await?;
+
+        Ok(Self {
+            chat_tg_id,
+            state,
+            tg_message_id,
+            date_expires,
+        })
+    }
+
+    /// Finds the dialogue, which is waiting for an answer.
+    ///
+    /// Reply to the question always matches, otherwise the latest not expired dialogue is taken.
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn get_active(
+        txn: &mut PgTransaction<'_>,
+        chat_tg_id: i64,
+        reply_to_message_id: Option<i32>,
+    ) -> Result<Option<Self>> {
+        let dialogue = sqlx::query!(
+            r#"
+SELECT
+    chat_tg_id,
+    state as "state: Json<DialogueState>",
+    tg_message_id,
+    date_expires
+FROM dialogues
+WHERE
+    chat_tg_id = $1
+    AND (
+        tg_message_id = $2
+        OR date_expires > NOW()
+    )
+ORDER BY
+    tg_message_id = $2 DESC NULLS LAST,
+    date_created DESC,
+    tg_message_id DESC
+LIMIT 1
+            "#,
+            chat_tg_id,
+            reply_to_message_id,
+        )
+        .fetch_optional(txn)
+        .await?;
+
+        Ok(dialogue.map(|dialogue| Self {
+            chat_tg_id: dialogue.chat_tg_id,
+            state: dialogue.state.0,
+            tg_message_id: dialogue.tg_message_id,
+            date_expires: dialogue.date_expires,
+        }))
+    }
+
+    /// Unfinished onboarding wizard of the user, whichever questions are asked after it
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn get_onboarding(
+        txn: &mut PgTransaction<'_>,
+        chat_tg_id: i64,
+    ) -> Result<Option<Self>> {
+        let dialogue = sqlx::query!(
+            r#"
+SELECT
+    chat_tg_id,
+    state as "state: Json<DialogueState>",
+    tg_message_id,
+    date_expires
+FROM dialogues
+WHERE
+    chat_tg_id = $1
+    AND state->>'step' = ANY($2)
+            "#,
+            chat_tg_id,
+            &DialogueState::ONBOARDING_STEPS[..] as _,
+        )
+        .fetch_optional(txn)
+        .await?;
+
+        Ok(dialogue.map(|dialogue| Self {
+            chat_tg_id: dialogue.chat_tg_id,
+            state: dialogue.state.0,
+            tg_message_id: dialogue.tg_message_id,
+            date_expires: dialogue.date_expires,
+        }))
+    }
+
+    /// Onboarding wizards, abandoned by the users
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn get_expired_onboardings(txn: &mut PgTransaction<'_>) -> Result<Vec<Self>> {
+        let dialogues = sqlx::query!(
+            r#"
+SELECT
+    chat_tg_id,
+    state as "state: Json<DialogueState>",
+    tg_message_id,
+    date_expires
+FROM dialogues
+WHERE
+    date_expires < NOW()
+    AND state->>'step' = ANY($1)
+            "#,
+            &DialogueState::ONBOARDING_STEPS[..] as _,
+        )
+        .fetch_all(txn)
+        .await?;
+
+        Ok(dialogues
+            .into_iter()
+            .map(|dialogue| Self {
+                chat_tg_id: dialogue.chat_tg_id,
+                state: dialogue.state.0,
+                tg_message_id: dialogue.tg_message_id,
+                date_expires: dialogue.date_expires,
+            })
+            .collect())
+    }
+
+    /// Finishes the dialogue, if it's still in the given state, e.g. user has skipped the question.
+    ///
+    /// Returns whether the dialogue was finished.
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn cancel(
+        txn: &mut PgTransaction<'_>,
+        chat_tg_id: i64,
+        state: &DialogueState,
+    ) -> Result<bool> {
+        Ok(sqlx::query!(
+            r#"
+DELETE FROM dialogues
+WHERE
+    chat_tg_id = $1
+    AND state = $2
+            "#,
+            chat_tg_id,
+            Json(state) as _,
+        )
+        .execute(txn)
+        .await?
+        .rows_affected()
+            > 0)
+    }
+
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn finish(self, txn: &mut PgTransaction<'_>) -> Result<()> {
+        sqlx::query!(
+            r#"
+DELETE FROM dialogues
+WHERE
+    chat_tg_id = $1
+    AND tg_message_id = $2
+            "#,
+            self.chat_tg_id,
+            self.tg_message_id,
+        )
+        .execute(txn)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::PgPool;
+    use time::macros::{datetime, time};
+
+    use crate::User;
+
+    use super::*;
+
+    fn states() -> Vec<DialogueState> {
+        vec![
+            DialogueState::PersonalOptionEdit(PersonalOptionEdit {
+                poll_kind: PollKind::DailyEvents,
+                option_id: Some(42),
+            }),
+            DialogueState::PersonalOptionEdit(PersonalOptionEdit {
+                poll_kind: PollKind::DailyEvents,
+                option_id: None,
+            }),
+            DialogueState::MoodScaleLabelEdit(MoodScaleLabelEdit { label_idx: 3 }),
+            DialogueState::AnswerNote(AnswerNoteQuestion { poll_id: 7 }),
+            DialogueState::JournalPrompt(JournalPrompt { poll_id: 8 }),
+            DialogueState::OnboardingTimezone(OnboardingAnswers::default()),
+            DialogueState::OnboardingLocation(OnboardingAnswers::default()),
+            DialogueState::OnboardingTime(OnboardingAnswers {
+                timezone: Some("Europe/Belgrade".to_owned()),
+                ..OnboardingAnswers::default()
+            }),
+            DialogueState::OnboardingTrackers(OnboardingAnswers {
+                timezone: Some("Europe/Belgrade".to_owned()),
+                send_at_local: Some(time!(21:30)),
+                trackers: vec![PollKind::DailyEvents, PollKind::MoodCheckIn],
+            }),
+        ]
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for state in states() {
+            let json = serde_json::to_value(&state).unwrap();
+
+            assert_eq!(
+                serde_json::from_value::<DialogueState>(json).unwrap(),
+                state
+            );
+        }
+    }
+
+    #[test]
+    fn test_step_names() {
+        // migrations and stored dialogues rely on these names
+        let steps: Vec<_> = states()
+            .iter()
+            .map(|state| serde_json::to_value(state).unwrap()["step"].clone())
+            .collect();
+
+        assert_eq!(
+            steps,
+            [
+                "personal_option_edit",
+                "personal_option_edit",
+                "mood_scale_label_edit",
+                "answer_note",
+                "journal_prompt",
+                "onboarding_timezone",
+                "onboarding_location",
+                "onboarding_time",
+                "onboarding_trackers",
+            ]
+        );
+        assert_eq!(
+            steps[5..],
+            DialogueState::ONBOARDING_STEPS,
+            "expired onboardings are looked up by these names"
+        );
+        assert_eq!(
+            serde_json::to_value(DialogueState::AnswerNote(AnswerNoteQuestion { poll_id: 7 }))
+                .unwrap(),
+            serde_json::json!({"step": "answer_note", "poll_id": 7}),
+        );
+    }
+
+    #[test]
+    fn test_recovered_onboarding() {
+        // wizards, lost before onboarding had a dialogue of its own, are recovered by the migration
+        let json = serde_json::json!({
+            "step": "onboarding_timezone",
+            "timezone": null,
+            "send_at_local": null,
+            "trackers": ["how_was_your_day"],
+        });
+
+        assert_eq!(
+            serde_json::from_value::<DialogueState>(json).unwrap(),
+            DialogueState::OnboardingTimezone(OnboardingAnswers::default())
+        );
+    }
+
+    #[test]
+    fn test_expiry() {
+        let now = datetime!(2023-05-22 23:30 UTC);
+
+        for state in states() {
+            // match is here to be sure that it's impossible
+            // to add new state without testing it :)
+            let expected = match state {
+                DialogueState::PersonalOptionEdit(_) | DialogueState::MoodScaleLabelEdit(_) => {
+                    datetime!(2023-05-23 00:30 UTC)
+                }
+                DialogueState::AnswerNote(_) => datetime!(2023-05-23 01:30 UTC),
+                DialogueState::JournalPrompt(_) => datetime!(2023-05-23 23:30 UTC),
+                DialogueState::OnboardingTimezone(_)
+                | DialogueState::OnboardingLocation(_)
+                | DialogueState::OnboardingTime(_)
+                | DialogueState::OnboardingTrackers(_) => datetime!(2023-05-23 00:30 UTC),
+            };
+
+            assert_eq!(state.expires_at(now), expected, "{state:?}");
+        }
+    }
+
+    #[sqlx::test]
+    async fn test_pending_questions(pool: PgPool) -> Result<()> {
+        let chat_tg_id = 42;
+        let prompt = DialogueState::JournalPrompt(JournalPrompt { poll_id: 1 });
+        let note = DialogueState::AnswerNote(AnswerNoteQuestion { poll_id: 2 });
+
+        let mut txn = pool.begin().await?;
+        User::activate(&mut txn, chat_tg_id).await?;
+        Dialogue::start(&mut txn, chat_tg_id, prompt.clone(), 10).await?;
+        // the note is asked later, it doesn't replace the prompt
+        Dialogue::start(&mut txn, chat_tg_id, note.clone(), 11).await?;
+
+        let replied = Dialogue::get_active(&mut txn, chat_tg_id, Some(10)).await?;
+        assert_eq!(replied.map(|dialogue| dialogue.state), Some(prompt.clone()));
+
+        // message without a reply answers the latest question
+        for reply_to_message_id in [None, Some(9)] {
+            let latest = Dialogue::get_active(&mut txn, chat_tg_id, reply_to_message_id).await?;
+            assert_eq!(latest.map(|dialogue| dialogue.state), Some(note.clone()));
+        }
+
+        // answered note doesn't finish the prompt
+        let answered = Dialogue::get_active(&mut txn, chat_tg_id, Some(11)).await?;
+        answered.expect("note is waiting").finish(&mut txn).await?;
+
+        let latest = Dialogue::get_active(&mut txn, chat_tg_id, None).await?;
+        assert_eq!(latest.map(|dialogue| dialogue.state), Some(prompt));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_onboarding_is_kept(pool: PgPool) -> Result<()> {
+        let chat_tg_id = 42;
+        let timezone = DialogueState::OnboardingTimezone(OnboardingAnswers::default());
+        let time = DialogueState::OnboardingTime(OnboardingAnswers {
+            timezone: Some("Europe/Belgrade".to_owned()),
+            ..OnboardingAnswers::default()
+        });
+        let edit = DialogueState::PersonalOptionEdit(PersonalOptionEdit {
+            poll_kind: PollKind::DailyEvents,
+            option_id: None,
+        });
+
+        let mut txn = pool.begin().await?;
+        User::activate(&mut txn, chat_tg_id).await?;
+        Dialogue::start(&mut txn, chat_tg_id, timezone, 10).await?;
+        // other questions, asked during the onboarding, don't replace it
+        Dialogue::start(&mut txn, chat_tg_id, edit.clone(), 11).await?;
+
+        let onboarding = Dialogue::get_onboarding(&mut txn, chat_tg_id).await?;
+        assert_eq!(onboarding.map(|dialogue| dialogue.tg_message_id), Some(10));
+
+        // wizard continues from the new message
+        Dialogue::start(&mut txn, chat_tg_id, time.clone(), 12).await?;
+        let onboarding = Dialogue::get_onboarding(&mut txn, chat_tg_id).await?;
+        assert_eq!(
+            onboarding.map(|dialogue| (dialogue.state, dialogue.tg_message_id)),
+            Some((time, 12))
+        );
+        let replied = Dialogue::get_active(&mut txn, chat_tg_id, Some(11)).await?;
+        assert_eq!(replied.map(|dialogue| dialogue.state), Some(edit));
+
+        Ok(())
+    }
+}
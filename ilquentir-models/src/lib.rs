@@ -2,26 +2,65 @@ use sqlx::{Postgres, Transaction};
 
 pub type PgTransaction<'t> = Transaction<'t, Postgres>;
 
+mod answer_mode;
+pub use answer_mode::AnswerMode;
+
+mod dialogue;
+pub use dialogue::{
+    AnswerNoteQuestion, Dialogue, DialogueState, JournalPrompt, MoodScaleLabelEdit,
+    OnboardingAnswers, PersonalOptionEdit,
+};
+
 mod diary_entry;
 pub use diary_entry::DiaryEntry;
 
+mod mood_scale;
+pub use mood_scale::MoodScale;
+
+mod poll_answer_note;
+pub use poll_answer_note::PollAnswerNote;
+
 mod poll_answer;
-pub use poll_answer::PollAnswer;
+pub use poll_answer::{PollAnswer, SavedSelection};
 
 mod poll_custom_options;
 pub use poll_custom_options::PollCustomOptions;
 
+mod poll_dimension_answer;
+pub use poll_dimension_answer::PollDimensionAnswer;
+
 mod poll_kind;
 pub use poll_kind::PollKind;
 
+mod poll_option;
+pub use poll_option::{PollChoice, PollOption};
+
+mod poll_option_category;
+pub use poll_option_category::PollOptionCategory;
+
+mod poll_part;
+pub use poll_part::PollPart;
+
 mod poll_settings;
 pub use poll_settings::PollSettings;
 
+mod poll_text_answer;
+pub use poll_text_answer::PollTextAnswer;
+
 mod poll;
 pub use poll::Poll;
 
 mod user;
-pub use user::User;
+pub use user::{AnswerStats, User};
+
+mod user_profile;
+pub use user_profile::UserProfile;
+
+mod user_source;
+pub use user_source::{SourceStats, UserSource};
 
 mod wide_how_was_your_day;
 pub use wide_how_was_your_day::WideHowWasYourDay;
+
+mod wide_mood_check_in;
+pub use wide_mood_check_in::WideMoodCheckIn;
@@ -0,0 +1,285 @@
+use color_eyre::{eyre::ensure, Result};
+
+use ilquentir_messages::{message, Locale};
+
+use crate::{PgTransaction, PollKind};
+
+/// Scale of [`PollKind::HowWasYourDay`] chosen by the user.
+///
+/// Labels go from the best option to the worst one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoodScale {
+    pub points: i32,
+    pub labels: Vec<String>,
+}
+
+impl MoodScale {
+    pub const SUPPORTED_POINTS: [i32; 4] = [3, 5, 7, 10];
+    pub const DEFAULT_POINTS: i32 = 5;
+    /// Telegram doesn't allow longer poll options
+    pub const MAX_LABEL_LEN: usize = 100;
+
+    const POLL_KIND: PollKind = PollKind::HowWasYourDay;
+
+    /// Scale with default labels in the given language
+    ///
+    /// ```rust
+    /// # use ilquentir_messages::Locale;
+    /// # use ilquentir_models::MoodScale;
+    /// assert_eq!(
+    ///     MoodScale::with_default_labels(5, Locale::Ru).labels,
+    ///     ["+2 (супер!)", "+1", "0", "-1", "-2 (отвратительно)"],
+    /// );
+    /// assert_eq!(
+    ///     MoodScale::with_default_labels(10, Locale::En).labels[9],
+    ///     "1 (awful)"
+    /// );
+    /// ```
+    pub fn with_default_labels(points: i32, locale: Locale) -> Self {
+        let best = |value: String| message!(locale, "mood_scale/best.md", value = value);
+        let worst = |value: String| message!(locale, "mood_scale/worst.md", value = value);
+
+        let labels = match points {
+            3 => vec![
+                message!(locale, "mood_scale/good.md"),
+                message!(locale, "mood_scale/so_so.md"),
+                message!(locale, "mood_scale/bad.md"),
+            ],
+            10 => (1..=10)
+                .rev()
+                .map(|value| match value {
+                    10 => best(value.to_string()),
+                    1 => worst(value.to_string()),
+                    value => value.to_string(),
+                })
+                .collect(),
+            points => {
+                let max = points / 2;
+
+                (-max..=max)
+                    .rev()
+                    .map(|value| match value {
+                        0 => "0".to_owned(),
+                        value if value == max => best(format!("+{value}")),
+                        value if value == -max => worst(value.to_string()),
+                        value if value > 0 => format!("+{value}"),
+                        value => value.to_string(),
+                    })
+                    .collect()
+            }
+        };
+
+        Self { points, labels }
+    }
+
+    /// Position of the option on the scale, from -1 (the worst) to 1 (the best)
+    ///
+    /// ```rust
+    /// # use ilquentir_models::MoodScale;
+    /// assert_eq!(MoodScale::normalize(0, 5), 1.);
+    /// assert_eq!(MoodScale::normalize(2, 5), 0.);
+    /// assert_eq!(MoodScale::normalize(9, 10), -1.);
+    /// ```
+    pub fn normalize(option_idx: i32, points: i32) -> f64 {
+        if points < 2 {
+            return 0.;
+        }
+
+        1. - 2. * f64::from(option_idx) / f64::from(points - 1)
+    }
+
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn get(txn: &mut PgTransaction<'_>, user_tg_id: i64, locale: Locale) -> Result<Self> {
+        let settings = sqlx::query!(
+            r#"
+SELECT
+    scale_points,
+    scale_labels
+FROM
+    poll_settings
+WHERE
+    user_tg_id = $1
+    AND poll_kind = $2
+            "#,
+            user_tg_id,
+            Self::POLL_KIND.to_string(),
+        )
+        .fetch_optional(txn)
+        .await?;
+
+        let (points, labels) = if let Some(settings) = settings {
+            (settings.scale_points, settings.scale_labels)
+        } else {
+            (None, vec![])
+        };
+        let mut scale = Self::with_default_labels(points.unwrap_or(Self::DEFAULT_POINTS), locale);
+
+        if labels.len() == scale.labels.len() {
+            scale.labels = labels;
+        }
+
+        Ok(scale)
+    }
+
+    /// Changes resolution of the scale, custom labels are reset
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn set_points(
+        txn: &mut PgTransaction<'_>,
+        user_tg_id: i64,
+        points: i32,
+        locale: Locale,
+    ) -> Result<Self> {
+        ensure!(
+            Self::SUPPORTED_POINTS.contains(&points),
+            "unsupported scale resolution {points}"
+        );
+
+        sqlx::query!(
+            r#"
+INSERT INTO poll_settings (
+    user_tg_id,
+    poll_kind,
+    scale_points
+)
+VALUES ($1, $2, $3)
+ON CONFLICT ON CONSTRAINT poll_settings_poll_kind_user_tg_id_key DO
+UPDATE SET
+    scale_points = $3,
+    scale_labels = '{}'
+            "#,
+            user_tg_id,
+            Self::POLL_KIND.to_string(),
+            points,
+        )
+        .execute(txn)
+        .await?;
+
+        Ok(Self::with_default_labels(points, locale))
+    }
+
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn rename_label(
+        self,
+        txn: &mut PgTransaction<'_>,
+        user_tg_id: i64,
+        label_idx: usize,
+        label: &str,
+    ) -> Result<Self> {
+        let label = label.trim();
+        ensure!(
+            !label.is_empty() && label.chars().count() <= Self::MAX_LABEL_LEN,
+            "label must be from 1 to {} characters long",
+            Self::MAX_LABEL_LEN
+        );
+        ensure!(label_idx < self.labels.len(), "unknown label {label_idx}");
+
+        let mut labels = self.labels;
+        labels[label_idx] = label.to_owned();
+
+        Self::save_labels(txn, user_tg_id, self.points, labels).await
+    }
+
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn reset_labels(
+        self,
+        txn: &mut PgTransaction<'_>,
+        user_tg_id: i64,
+        locale: Locale,
+    ) -> Result<Self> {
+        Self::save_labels(txn, user_tg_id, self.points, vec![]).await?;
+
+        Ok(Self::with_default_labels(self.points, locale))
+    }
+
+    #[tracing::instrument(skip(txn), err)]
+    async fn save_labels(
+        txn: &mut PgTransaction<'_>,
+        user_tg_id: i64,
+        points: i32,
+        labels: Vec<String>,
+    ) -> Result<Self> {
+        sqlx::query!(
+            r#"
+INSERT INTO poll_settings (
+    user_tg_id,
+    poll_kind,
+    scale_points,
+    scale_labels
+)
+VALUES ($1, $2, $3, $4)
+ON CONFLICT ON CONSTRAINT poll_settings_poll_kind_user_tg_id_key DO
+UPDATE SET
+    scale_points = $3,
+    scale_labels = $4
+            "#,
+            user_tg_id,
+            Self::POLL_KIND.to_string(),
+            points,
+            &labels,
+        )
+        .execute(txn)
+        .await?;
+
+        Ok(Self { points, labels })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        for points in MoodScale::SUPPORTED_POINTS {
+            let values: Vec<_> = (0..points)
+                .map(|idx| MoodScale::normalize(idx, points))
+                .collect();
+
+            assert_eq!(values[0], 1., "the best option of {points}-point scale");
+            assert_eq!(
+                values[points as usize - 1],
+                -1.,
+                "the worst option of {points}-point scale"
+            );
+            assert!(
+                values.windows(2).all(|pair| pair[0] > pair[1]),
+                "{points}-point scale isn't decreasing: {values:?}"
+            );
+        }
+
+        assert_eq!(MoodScale::normalize(1, 3), 0.);
+        assert_eq!(MoodScale::normalize(3, 7), 0.);
+        assert_eq!(MoodScale::normalize(1, 5), 0.5);
+        assert!((MoodScale::normalize(4, 10) - 1. / 9.).abs() < 1e-9);
+        assert!((MoodScale::normalize(5, 10) + 1. / 9.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_normalize_historic() {
+        // migration maps historic answers of the 5-point scale with `1 - selected_value / 2.0`
+        for idx in 0..5 {
+            assert_eq!(
+                MoodScale::normalize(idx, 5),
+                1. - f64::from(idx) / 2.,
+                "option {idx}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_default_labels() {
+        for points in MoodScale::SUPPORTED_POINTS {
+            for locale in Locale::ALL {
+                assert_eq!(
+                    MoodScale::with_default_labels(points, locale).labels.len(),
+                    points as usize
+                );
+            }
+        }
+
+        assert_eq!(
+            MoodScale::with_default_labels(3, Locale::En).labels,
+            ["Good", "So-so", "Bad"]
+        );
+    }
+}
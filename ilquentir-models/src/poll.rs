@@ -10,13 +10,11 @@ use tracing::{debug, error, info, warn};
 
 use teloxide::types::{MediaKind, Message, MessageKind};
 
-use crate::{PgTransaction, PollKind, User};
+use crate::{PgTransaction, PollChoice, PollKind, PollPart};
 
 #[derive(Debug, Clone, FromRow)]
 pub struct Poll {
     pub id: Option<i64>,
-    pub tg_id: Option<String>,
-    pub tg_message_id: Option<i32>,
     pub chat_tg_id: i64,
     pub kind: PollKind,
     pub publication_date: time::OffsetDateTime,
@@ -33,8 +31,6 @@ impl Poll {
     ) -> Result<Self> {
         Self {
             id: None,
-            tg_id: None,
-            tg_message_id: None,
             chat_tg_id: user_tg_id,
             kind,
             publication_date: publication_date.unwrap_or_else(OffsetDateTime::now_utc),
@@ -44,31 +40,29 @@ impl Poll {
         .await
     }
 
+    /// Schedules the next poll of the given kind at the time, chosen by the user,
+    /// returns already scheduled poll if there is one
     #[tracing::instrument(skip(txn), err)]
-    pub async fn create_for_user(txn: &mut PgTransaction<'_>, user: &'_ User) -> Result<Vec<Self>> {
-        let publication_date = time::OffsetDateTime::now_utc();
-
-        let polls = user
-            .subscribed_for_polls()
+    pub async fn schedule_for_user(
+        txn: &mut PgTransaction<'_>,
+        user_tg_id: i64,
+        kind: PollKind,
+    ) -> Result<Self> {
+        if let Some(poll) = Self::get_scheduled_for_user(&mut *txn, user_tg_id, kind)
+            .await?
             .into_iter()
-            .map(|kind| Self {
-                id: None,
-                tg_id: None,
-                tg_message_id: None,
-                chat_tg_id: user.tg_id,
-                kind,
-                publication_date,
-                published: false,
-            })
-            .collect::<Vec<_>>();
-
-        let mut inserted = Vec::with_capacity(polls.len());
-
-        for poll in polls {
-            inserted.push(poll.insert(&mut *txn).await?);
+            .next()
+        {
+            return Ok(poll);
         }
 
-        Ok(inserted)
+        let now = OffsetDateTime::now_utc();
+        let publication_date = kind
+            .schedule_next_custom(&mut *txn, user_tg_id, now)
+            .await?
+            .unwrap_or_else(|| kind.schedule_next(now));
+
+        Self::create(txn, user_tg_id, kind, Some(publication_date)).await
     }
 
     #[tracing::instrument(skip(txn), err)]
@@ -78,25 +72,19 @@ impl Poll {
             r#"
 INSERT INTO polls (
     chat_tg_id,
-    tg_id,
-    tg_message_id,
     kind,
     publication_date,
     published
 )
-VALUES ($1, $2, $3, $4, $5, $6)
+VALUES ($1, $2, $3, $4)
 RETURNING
     id as "id?",
-    tg_id,
-    tg_message_id,
     chat_tg_id,
     kind as "kind: PollKind",
     publication_date,
     published
 "#,
             self.chat_tg_id,
-            self.tg_id,
-            self.tg_message_id,
             self.kind.to_string(),
             self.publication_date,
             self.published,
@@ -148,8 +136,6 @@ WHERE
             id: None,
             publication_date: next_at,
             published: false,
-            tg_id: None,
-            tg_message_id: None,
             chat_tg_id: self.chat_tg_id,
             kind: self.kind,
         };
@@ -172,25 +158,19 @@ WHERE
                 r#"
 UPDATE polls
 SET
-    tg_id = $2,
-    tg_message_id = $3,
-    chat_tg_id = $4,
-    kind = $5,
-    publication_date = $6,
-    published = $7
+    chat_tg_id = $2,
+    kind = $3,
+    publication_date = $4,
+    published = $5
 WHERE id = $1
 RETURNING
     id as "id?",
-    tg_id,
-    tg_message_id,
     chat_tg_id,
     kind as "kind: PollKind",
     publication_date,
     published
             "#,
                 id,
-                self.tg_id,
-                self.tg_message_id,
                 self.chat_tg_id,
                 self.kind.to_string(),
                 self.publication_date,
@@ -201,6 +181,28 @@ RETURNING
         ))
     }
 
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn get_by_id(txn: &mut PgTransaction<'_>, id: i64) -> Result<Poll> {
+        Ok(sqlx::query_as!(
+            Poll,
+            r#"
+SELECT
+    id as "id?",
+    chat_tg_id,
+    kind as "kind: PollKind",
+    publication_date,
+    published
+FROM polls
+WHERE
+    id = $1
+            "#,
+            id,
+        )
+        .fetch_one(txn)
+        .await?)
+    }
+
+    /// Get logical poll by the Telegram id of any of its parts
     #[tracing::instrument(skip(txn), err)]
     pub async fn get_by_tg_id<'t, S: AsRef<str> + Debug>(
         txn: &mut PgTransaction<'_>,
@@ -210,16 +212,17 @@ RETURNING
             Poll,
             r#"
 SELECT
-    id as "id?",
-    tg_id,
-    tg_message_id,
+    polls.id as "id?",
     chat_tg_id,
     kind as "kind: PollKind",
     publication_date,
     published
 FROM polls
+JOIN poll_parts
+ON
+    polls.id = poll_parts.poll_id
 WHERE
-    tg_id = $1
+    poll_parts.tg_id = $1
             "#,
             tg_id.as_ref(),
         )
@@ -234,8 +237,6 @@ WHERE
             r#"
 SELECT
     id as "id?",
-    polls.tg_id as tg_id,
-    tg_message_id,
     chat_tg_id,
     kind as "kind: PollKind",
     publication_date,
@@ -268,8 +269,6 @@ WHERE
             r#"
 SELECT
     id as "id?",
-    polls.tg_id as tg_id,
-    tg_message_id,
     chat_tg_id,
     kind as "kind: PollKind",
     publication_date,
@@ -329,24 +328,41 @@ WHERE
             r#"
 SELECT
     polls.id as "id?",
-    tg_id,
-    tg_message_id,
     chat_tg_id,
     kind as "kind: PollKind",
     publication_date,
     published
 FROM
     polls
-LEFT JOIN
-    poll_answers
-ON
-    polls.tg_id = poll_answers.poll_tg_id
 WHERE
     NOT polls.overdue
     AND polls.published
     AND polls.kind = $1
     AND polls.publication_date < (NOW() - $2::interval)
-    AND poll_answers.id IS NULL
+    AND NOT EXISTS (
+        SELECT
+            1
+        FROM
+            poll_answers
+        WHERE
+            poll_answers.poll_id = polls.id
+    )
+    AND NOT EXISTS (
+        SELECT
+            1
+        FROM
+            poll_text_answers
+        WHERE
+            poll_text_answers.poll_id = polls.id
+    )
+    AND NOT EXISTS (
+        SELECT
+            1
+        FROM
+            poll_dimension_answers
+        WHERE
+            poll_dimension_answers.poll_id = polls.id
+    )
             "#,
             kind.to_string(),
             pg_interval
@@ -378,58 +394,157 @@ WHERE
         .rows_affected())
     }
 
+    /// Save that poll is published as given Telegram messages
+    /// (one message per poll part) with given options and schedule the next one
     #[tracing::instrument(skip(txn), err)]
     pub async fn published_to_tg(
         self,
         txn: &mut PgTransaction<'_>,
         poll_messages: &[Message],
+        choices: &[PollChoice],
     ) -> Result<Self> {
-        // schedule new poll
-        let prev_id = self.id;
-        let mut poll = self;
+        let poll_id = self
+            .id
+            .ok_or_else(|| eyre!("trying to publish unsaved poll"))?;
+        let mut options_offset = 0;
 
         for message in poll_messages {
             // FIXME: do not clone here
-            if let MessageKind::Common(message_common) = message.kind.clone() {
-                if let MediaKind::Poll(tg_poll) = message_common.media_kind {
+            let media_kind = if let MessageKind::Common(message_common) = message.kind.clone() {
+                message_common.media_kind
+            } else {
+                error!(
+                    ?message,
+                    "got some weird message in response to SendPoll request"
+                );
+                bail!("got some weird message in response to SendPoll request");
+            };
+
+            let part = match media_kind {
+                MediaKind::Poll(tg_poll) => {
                     debug!(
-                        poll_id = prev_id,
-                        user_tg_id = poll.chat_tg_id,
+                        poll_id,
+                        user_tg_id = self.chat_tg_id,
                         poll_tg_id = tg_poll.poll.id,
-                        "poll sent"
+                        "poll part sent"
                     );
 
-                    // TODO: think about refactoring in two methods
-                    // save that poll is published
-                    poll.published = true;
-                    poll.tg_message_id = Some(message.id.0);
-                    poll.tg_id = Some(tg_poll.poll.id.clone());
-                    if poll.id.is_some() {
-                        poll = poll.update(&mut *txn).await?.expect("post update failed");
-                    } else {
-                        poll = poll.insert(&mut *txn).await?;
-                    }
-                    poll.id = None;
-
+                    let options: Vec<_> = tg_poll
+                        .poll
+                        .options
+                        .iter()
+                        .map(|option| option.text.clone())
+                        .collect();
+                    let option_ids = choices
+                        .iter()
+                        .skip(options_offset as usize)
+                        .take(options.len())
+                        .map(|choice| choice.option_id)
+                        .collect();
+
+                    let part = PollPart {
+                        id: None,
+                        poll_id,
+                        tg_id: Some(tg_poll.poll.id),
+                        tg_message_id: Some(message.id.0),
+                        options_offset,
+                        options,
+                        option_ids,
+                    };
+                    options_offset += tg_poll.poll.options.len() as i32;
+
+                    part
+                }
+                MediaKind::Text(_) if self.kind.is_prompt() => {
                     debug!(
-                        poll_id = poll.id,
-                        user_tg_id = poll.chat_tg_id,
-                        poll_tg_id = tg_poll.poll.id,
-                        "saved that poll is published"
+                        poll_id,
+                        user_tg_id = self.chat_tg_id,
+                        message_id = message.id.0,
+                        "prompt sent"
                     );
+
+                    PollPart {
+                        id: None,
+                        poll_id,
+                        tg_id: None,
+                        tg_message_id: Some(message.id.0),
+                        options_offset,
+                        options: vec![],
+                        option_ids: vec![],
+                    }
                 }
-            } else {
-                error!(
-                    ?message,
-                    "got some weird message in response to SendPoll request"
-                );
-                bail!("got some weird message in response to SendPoll request");
-            }
+                _ => {
+                    error!(
+                        ?message,
+                        "got some weird message in response to SendPoll request"
+                    );
+                    bail!("got some weird message in response to SendPoll request");
+                }
+            };
+
+            part.insert(&mut *txn).await?;
+        }
+
+        self.mark_published(txn).await
+    }
+
+    /// Save that poll is published as a message with inline keyboard
+    /// containing given options and schedule the next one
+    #[tracing::instrument(skip(txn, message), err)]
+    pub async fn published_as_keyboard(
+        self,
+        txn: &mut PgTransaction<'_>,
+        message: &Message,
+        choices: Vec<PollChoice>,
+    ) -> Result<Self> {
+        let poll_id = self
+            .id
+            .ok_or_else(|| eyre!("trying to publish unsaved poll"))?;
+
+        debug!(
+            poll_id,
+            user_tg_id = self.chat_tg_id,
+            message_id = message.id.0,
+            "poll sent as inline keyboard"
+        );
+
+        PollPart {
+            id: None,
+            poll_id,
+            tg_id: None,
+            tg_message_id: Some(message.id.0),
+            options_offset: 0,
+            option_ids: choices.iter().map(|choice| choice.option_id).collect(),
+            options: choices.into_iter().map(|choice| choice.text).collect(),
         }
+        .insert(&mut *txn)
+        .await?;
+
+        self.mark_published(txn).await
+    }
+
+    #[tracing::instrument(skip(txn), err)]
+    async fn mark_published(self, txn: &mut PgTransaction<'_>) -> Result<Self> {
+        let poll_id = self.id;
+
+        // save that poll is published
+        let poll = Self {
+            published: true,
+            ..self
+        }
+        .update(&mut *txn)
+        .await?
+        .expect("post update failed");
+
+        debug!(
+            poll_id,
+            user_tg_id = poll.chat_tg_id,
+            "saved that poll is published"
+        );
 
         let next_poll = poll.schedule_next(&mut *txn).await?;
         info!(
-            poll_id = prev_id,
+            poll_id,
             next_poll_id = next_poll.id,
             user_tg_id = next_poll.chat_tg_id,
             "scheduled new poll"
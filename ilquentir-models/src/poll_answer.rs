@@ -1,43 +1,371 @@
-use color_eyre::Result;
+use color_eyre::{eyre::eyre, Result};
 use sqlx::FromRow;
-use teloxide::types::Poll as TgPoll;
+use teloxide::types::{Poll as TgPoll, PollAnswer as TgPollAnswer};
 use tracing::info;
 
-use crate::{PgTransaction, Poll};
+use crate::{MoodScale, PgTransaction, Poll, PollPart};
 
 #[derive(Debug, Clone, FromRow)]
 pub struct PollAnswer {
-    pub poll_tg_id: String,
+    pub poll_id: i64,
+    pub poll_part_id: i64,
+    pub poll_tg_id: Option<String>,
+    pub user_tg_id: i64,
     pub selected_value: i32,
+    /// Text of the option, as the user have seen it
     pub selected_value_text: String,
+    /// Catalog id of the option, `None` for options outside of the catalog
+    pub option_id: Option<i64>,
+    /// Position on the scale for scale polls, see [`MoodScale::normalize`]
+    pub normalized_value: Option<f64>,
+}
+
+/// Result of saving user's current selection in a poll
+#[derive(Debug, Clone)]
+pub struct SavedSelection {
+    pub poll: Poll,
+    /// Whether any part of the poll was answered before the update
+    pub was_answered: bool,
+    /// Whether any part of the poll is answered after the update
+    pub is_answered: bool,
+}
+
+impl SavedSelection {
+    /// User answered the poll for the first time
+    pub fn first_answer(&self) -> bool {
+        !self.was_answered && self.is_answered
+    }
 }
 
 impl PollAnswer {
+    /// Save results of a poll, received via `Poll` update.
+    ///
+    /// Polls are sent to private chats, so all the votes belong to the chat owner.
     #[tracing::instrument(skip(txn), err)]
-    pub async fn save_answer(txn: &mut PgTransaction<'_>, tg_poll: &TgPoll) -> Result<Poll> {
+    pub async fn save_answer(
+        txn: &mut PgTransaction<'_>,
+        tg_poll: &TgPoll,
+    ) -> Result<SavedSelection> {
         info!(tg_poll = tg_poll.id, "saving results for poll");
-        let options = tg_poll
+        let poll = Poll::get_by_tg_id(&mut *txn, &tg_poll.id).await?;
+        let part = PollPart::get_by_tg_id(&mut *txn, &tg_poll.id).await?;
+        let user_tg_id = poll.chat_tg_id;
+
+        let selected = tg_poll
             .options
             .iter()
             .enumerate()
-            .filter(|(_idx, option)| option.voter_count > 0);
+            .filter(|(_idx, option)| option.voter_count > 0)
+            .map(|(idx, option)| (idx as i32, option.text.clone()))
+            .collect();
+
+        Self::save_selection(txn, poll, part, user_tg_id, selected).await
+    }
+
+    /// Save user's vote, received via `PollAnswer` update (non-anonymous polls only).
+    ///
+    /// Empty selection means that the vote was retracted.
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn save_poll_answer(
+        txn: &mut PgTransaction<'_>,
+        tg_answer: &TgPollAnswer,
+    ) -> Result<SavedSelection> {
+        info!(tg_poll = tg_answer.poll_id, "saving user's vote");
+        let poll = Poll::get_by_tg_id(&mut *txn, &tg_answer.poll_id).await?;
+        let part = PollPart::get_by_tg_id(&mut *txn, &tg_answer.poll_id).await?;
+
+        let selected = tg_answer
+            .option_ids
+            .iter()
+            .map(|&idx| {
+                let text = part
+                    .options
+                    .get(idx as usize)
+                    .cloned()
+                    .ok_or_else(|| eyre!("unknown option {idx} in poll part"))?;
+
+                Ok((idx, text))
+            })
+            .collect::<Result<_>>()?;
+
+        Self::save_selection(txn, poll, part, tg_answer.user.id.0 as i64, selected).await
+    }
+
+    /// Save user's selection in a poll, rendered as inline keyboard.
+    ///
+    /// `selected` are indices of the options in the poll part.
+    #[tracing::instrument(skip(txn, poll, part), err)]
+    pub async fn save_keyboard_answer(
+        txn: &mut PgTransaction<'_>,
+        poll: Poll,
+        part: PollPart,
+        user_tg_id: i64,
+        selected: &[i32],
+    ) -> Result<SavedSelection> {
+        let selected = selected
+            .iter()
+            .map(|&idx| {
+                let text = part
+                    .options
+                    .get(idx as usize)
+                    .cloned()
+                    .ok_or_else(|| eyre!("unknown option {idx} in poll part"))?;
+
+                Ok((idx, text))
+            })
+            .collect::<Result<_>>()?;
+
+        Self::save_selection(txn, poll, part, user_tg_id, selected).await
+    }
+
+    /// Get indices of the options in the poll part, currently selected by the user
+    #[tracing::instrument(skip(txn, part), err)]
+    pub async fn get_selected(
+        txn: &mut PgTransaction<'_>,
+        part: &PollPart,
+        user_tg_id: i64,
+    ) -> Result<Vec<i32>> {
+        let part_id = part
+            .id
+            .ok_or_else(|| eyre!("trying to get answers for unsaved poll part"))?;
+
+        Ok(sqlx::query!(
+            r#"
+SELECT
+    selected_value
+FROM
+    poll_answers
+WHERE
+    poll_part_id = $1
+    AND user_tg_id = $2
+ORDER BY
+    selected_value
+            "#,
+            part_id,
+            user_tg_id,
+        )
+        .fetch_all(txn)
+        .await?
+        .into_iter()
+        .map(|answer| answer.selected_value - part.options_offset)
+        .collect())
+    }
+
+    /// Replaces user's answers in the poll part with the given selection
+    /// of `(option index, option text)` and records the change in history.
+    ///
+    /// Single-choice polls keep only the latest answer, even if it's given in another part.
+    #[tracing::instrument(skip(txn, poll, part), err)]
+    async fn save_selection(
+        txn: &mut PgTransaction<'_>,
+        poll: Poll,
+        part: PollPart,
+        user_tg_id: i64,
+        selected: Vec<(i32, String)>,
+    ) -> Result<SavedSelection> {
+        let part_id = part
+            .id
+            .ok_or_else(|| eyre!("trying to save answers for unsaved poll part"))?;
+        let poll_tg_id = part.tg_id.as_deref();
+        let was_answered = Self::is_answered(&mut *txn, part.poll_id).await?;
+
+        let selected: Vec<_> = selected
+            .into_iter()
+            .map(|(idx, text)| {
+                let option_id = part.option_id(idx as usize);
+
+                (part.options_offset + idx, text, option_id)
+            })
+            .collect();
+        let selected_values: Vec<_> = selected.iter().map(|(value, ..)| *value).collect();
+
+        let scale_points = if poll.kind.is_scale() {
+            let points: usize = PollPart::get_for_poll(&mut *txn, part.poll_id)
+                .await?
+                .iter()
+                .map(|part| part.options.len())
+                .sum();
 
-        for (idx, option) in options {
-            sqlx::query_as!(
-                Self,
+            Some(points as i32)
+        } else {
+            None
+        };
+
+        let removed = sqlx::query!(
+            r#"
+DELETE FROM poll_answers
+WHERE
+    (poll_part_id = $1 OR (poll_id = $4 AND NOT $5))
+    AND user_tg_id = $2
+    AND NOT (selected_value = ANY($3))
+            "#,
+            part_id,
+            user_tg_id,
+            &selected_values,
+            part.poll_id,
+            poll.kind.allows_multiple_answers(),
+        )
+        .execute(&mut *txn)
+        .await?
+        .rows_affected();
+
+        for (value, text, option_id) in selected {
+            sqlx::query!(
                 r#"
-INSERT INTO poll_answers (poll_tg_id, selected_value, selected_value_text)
-VALUES ($1, $2, $3)
-RETURNING poll_tg_id, selected_value, selected_value_text
+INSERT INTO poll_answers (
+    poll_id,
+    poll_part_id,
+    poll_tg_id,
+    user_tg_id,
+    selected_value,
+    selected_value_text,
+    normalized_value,
+    option_id
+)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+ON CONFLICT ON CONSTRAINT poll_answers_poll_part_id_user_tg_id_selected_value_key DO NOTHING
                 "#,
-                tg_poll.id,
-                idx as i32,
-                option.text,
+                part.poll_id,
+                part_id,
+                poll_tg_id,
+                user_tg_id,
+                value,
+                text,
+                scale_points.map(|points| MoodScale::normalize(value, points)),
+                option_id,
             )
-            .fetch_one(&mut *txn)
+            .execute(&mut *txn)
             .await?;
         }
 
-        Poll::get_by_tg_id(txn, &tg_poll.id).await
+        sqlx::query!(
+            r#"
+INSERT INTO poll_answers_history (
+    poll_id, poll_part_id, poll_tg_id, user_tg_id, selected_values
+)
+VALUES ($1, $2, $3, $4, $5)
+            "#,
+            part.poll_id,
+            part_id,
+            poll_tg_id,
+            user_tg_id,
+            &selected_values,
+        )
+        .execute(&mut *txn)
+        .await?;
+
+        let is_answered = Self::is_answered(&mut *txn, part.poll_id).await?;
+        info!(
+            poll_id = part.poll_id,
+            user_tg_id,
+            removed,
+            ?selected_values,
+            "saved user's selection"
+        );
+
+        Ok(SavedSelection {
+            poll,
+            was_answered,
+            is_answered,
+        })
+    }
+
+    #[tracing::instrument(skip(txn), err, ret)]
+    async fn is_answered(txn: &mut PgTransaction<'_>, poll_id: i64) -> Result<bool> {
+        Ok(sqlx::query!(
+            r#"
+SELECT
+    id
+FROM
+    poll_answers
+WHERE
+    poll_id = $1
+LIMIT 1
+            "#,
+            poll_id,
+        )
+        .fetch_optional(txn)
+        .await?
+        .is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::PgPool;
+
+    use crate::{PollKind, User};
+
+    use super::*;
+
+    async fn keyboard_part(
+        txn: &mut PgTransaction<'_>,
+        poll_id: i64,
+        options_offset: i32,
+        options: &[&str],
+    ) -> Result<PollPart> {
+        PollPart {
+            id: None,
+            poll_id,
+            tg_id: None,
+            tg_message_id: Some(options_offset + 1),
+            options_offset,
+            options: options.iter().map(|&option| option.to_owned()).collect(),
+            option_ids: vec![None; options.len()],
+        }
+        .insert(txn)
+        .await
+    }
+
+    async fn current_answers(txn: &mut PgTransaction<'_>, poll_id: i64) -> Result<Vec<String>> {
+        Ok(sqlx::query!(
+            "SELECT selected_value_text FROM poll_answers WHERE poll_id = $1 ORDER BY id",
+            poll_id,
+        )
+        .fetch_all(txn)
+        .await?
+        .into_iter()
+        .map(|answer| answer.selected_value_text)
+        .collect())
+    }
+
+    #[sqlx::test]
+    async fn test_single_choice_keeps_latest(pool: PgPool) -> color_eyre::Result<()> {
+        let mut txn = pool.begin().await?;
+        User::activate(&mut txn, 1).await?;
+
+        let poll = Poll::create(&mut txn, 1, PollKind::HowWasYourDay, None).await?;
+        let poll_id = poll.id.unwrap();
+        let first = keyboard_part(&mut txn, poll_id, 0, &["+1", "0"]).await?;
+        let second = keyboard_part(&mut txn, poll_id, 2, &["-1"]).await?;
+
+        PollAnswer::save_keyboard_answer(&mut txn, poll.clone(), first.clone(), 1, &[1]).await?;
+        PollAnswer::save_keyboard_answer(&mut txn, poll.clone(), first, 1, &[0]).await?;
+        assert_eq!(current_answers(&mut txn, poll_id).await?, ["+1"]);
+
+        PollAnswer::save_keyboard_answer(&mut txn, poll, second, 1, &[0]).await?;
+        assert_eq!(current_answers(&mut txn, poll_id).await?, ["-1"]);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_multiple_choice_keeps_other_parts(pool: PgPool) -> color_eyre::Result<()> {
+        let mut txn = pool.begin().await?;
+        User::activate(&mut txn, 1).await?;
+
+        let poll = Poll::create(&mut txn, 1, PollKind::DailyEvents, None).await?;
+        let poll_id = poll.id.unwrap();
+        let first = keyboard_part(&mut txn, poll_id, 0, &["Работа", "Спорт"]).await?;
+        let second = keyboard_part(&mut txn, poll_id, 2, &["Ничего"]).await?;
+
+        PollAnswer::save_keyboard_answer(&mut txn, poll.clone(), first, 1, &[0, 1]).await?;
+        PollAnswer::save_keyboard_answer(&mut txn, poll, second, 1, &[0]).await?;
+        assert_eq!(
+            current_answers(&mut txn, poll_id).await?,
+            ["Работа", "Спорт", "Ничего"]
+        );
+
+        Ok(())
     }
 }
@@ -0,0 +1,100 @@
+use color_eyre::Result;
+use sqlx::FromRow;
+use time::OffsetDateTime;
+
+use crate::PgTransaction;
+
+/// Short note on why the poll was answered the way it was.
+///
+/// Created when follow-up question is sent (see [`crate::AnswerNoteQuestion`]),
+/// `text` is filled in when user replies to it.
+#[derive(Debug, Clone, FromRow)]
+pub struct PollAnswerNote {
+    pub poll_id: i64,
+    pub tg_message_id: i32,
+    pub text: Option<String>,
+    pub date_created: OffsetDateTime,
+    pub date_answered: Option<OffsetDateTime>,
+}
+
+impl PollAnswerNote {
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn create(
+        txn: &mut PgTransaction<'_>,
+        poll_id: i64,
+        tg_message_id: i32,
+    ) -> Result<Self> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"
+INSERT INTO poll_answer_notes (
+    poll_id, tg_message_id
+)
+VALUES ($1, $2)
+RETURNING
+    poll_id,
+    tg_message_id,
+    text,
+    date_created,
+    date_answered
+            "#,
+            poll_id,
+            tg_message_id,
+        )
+        .fetch_one(txn)
+        .await?)
+    }
+
+    #[tracing::instrument(skip(txn, text), err)]
+    pub async fn save_text(txn: &mut PgTransaction<'_>, poll_id: i64, text: &str) -> Result<Self> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"
+UPDATE poll_answer_notes
+SET
+    text = $2,
+    date_answered = NOW()
+WHERE
+    poll_id = $1
+RETURNING
+    poll_id,
+    tg_message_id,
+    text,
+    date_created,
+    date_answered
+            "#,
+            poll_id,
+            text,
+        )
+        .fetch_one(txn)
+        .await?)
+    }
+
+    /// Removes unanswered note, user decided to skip the follow-up question
+    ///
+    /// Returns whether anything was removed.
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn skip(
+        txn: &mut PgTransaction<'_>,
+        chat_tg_id: i64,
+        tg_message_id: i32,
+    ) -> Result<bool> {
+        Ok(sqlx::query!(
+            r#"
+DELETE FROM poll_answer_notes
+USING polls
+WHERE
+    poll_answer_notes.poll_id = polls.id
+    AND polls.chat_tg_id = $1
+    AND poll_answer_notes.tg_message_id = $2
+    AND poll_answer_notes.text IS NULL
+            "#,
+            chat_tg_id,
+            tg_message_id,
+        )
+        .execute(txn)
+        .await?
+        .rows_affected()
+            > 0)
+    }
+}
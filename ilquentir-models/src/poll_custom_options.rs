@@ -1,19 +1,13 @@
 use color_eyre::Result;
-use sqlx::FromRow;
 use tracing::{info, warn};
 
-use crate::{PgTransaction, PollKind};
-
-#[derive(Debug, Clone, FromRow)]
-struct PollCustomOptionsRaw {
-    option_text: String,
-}
+use crate::{PgTransaction, PollKind, PollOption};
 
 #[derive(Debug, Clone)]
 pub struct PollCustomOptions {
     pub poll_kind: PollKind,
     pub user_tg_id: i64,
-    pub options: Vec<String>,
+    pub options: Vec<PollOption>,
 }
 
 impl PollCustomOptions {
@@ -24,24 +18,40 @@ impl PollCustomOptions {
         poll_kind: PollKind,
     ) -> Result<Self> {
         let options = sqlx::query_as!(
-            PollCustomOptionsRaw,
+            PollOption,
             r#"
 SELECT
-    option_text
+    poll_options.id,
+    poll_options.poll_kind as "poll_kind: PollKind",
+    poll_options.owner_tg_id,
+    poll_options.category_id,
+    poll_options.position,
+    poll_options.is_default,
+    poll_options.active,
+    poll_option_latest_texts.text as "text!"
 FROM
     poll_custom_options
+JOIN poll_options
+ON
+    poll_custom_options.option_id = poll_options.id
+JOIN poll_option_latest_texts
+ON
+    poll_options.id = poll_option_latest_texts.option_id
 WHERE
-    user_tg_id = $1
-    AND poll_kind = $2
+    poll_custom_options.user_tg_id = $1
+    AND poll_custom_options.poll_kind = $2
+    AND poll_options.active
+    AND NOT poll_options.retired
+ORDER BY
+    poll_options.owner_tg_id NULLS FIRST,
+    poll_options.position,
+    poll_options.id
             "#,
             user_tg_id,
             poll_kind.to_string(),
         )
         .fetch_all(txn)
-        .await?
-        .into_iter()
-        .map(|option| option.option_text)
-        .collect();
+        .await?;
 
         Ok(Self {
             poll_kind,
@@ -50,77 +60,61 @@ WHERE
         })
     }
 
+    pub fn contains(&self, option_id: i64) -> bool {
+        self.options.iter().any(|option| option.id == option_id)
+    }
+
     #[tracing::instrument(skip(txn), err)]
     pub async fn toggle_option(
         txn: &mut PgTransaction<'_>,
         user_tg_id: i64,
         poll_kind: PollKind,
-        option: &str,
+        option_id: i64,
     ) -> Result<()> {
         let poll_kind = poll_kind.to_string();
 
         info!("checking, if option is enabled or not");
 
-        let exists = sqlx::query!(
+        let deleted = sqlx::query!(
             r#"
-SELECT
-    id
-FROM
-    poll_custom_options
+DELETE FROM poll_custom_options
 WHERE
     poll_kind = $1
     AND user_tg_id = $2
-    AND option_text = $3
+    AND option_id = $3
             "#,
             poll_kind,
             user_tg_id,
-            option,
+            option_id,
         )
-        .fetch_optional(&mut *txn)
+        .execute(&mut *txn)
         .await?
-        .is_some();
+        .rows_affected();
 
-        if exists {
-            info!("option exists, removing");
+        if deleted > 0 {
+            info!("option existed, removed");
 
-            let deleted = sqlx::query!(
-                r#"
-DELETE FROM poll_custom_options
-WHERE
-    poll_kind = $1
-    AND user_tg_id = $2
-    AND option_text = $3
-                "#,
-                poll_kind,
-                user_tg_id,
-                option,
-            )
-            .execute(txn)
-            .await?
-            .rows_affected();
+            return Ok(());
+        }
 
-            if deleted == 0 {
-                warn!("tried to enable already enabled option")
-            }
-        } else {
-            let inserted = sqlx::query!(
-                r#"
+        let inserted = sqlx::query!(
+            r#"
 INSERT INTO poll_custom_options (
-    poll_kind, user_tg_id, option_text
+    poll_kind, user_tg_id, option_id
 )
 VALUES ($1, $2, $3)
-                "#,
-                poll_kind.to_string(),
-                user_tg_id,
-                option,
-            )
-            .execute(txn)
-            .await?
-            .rows_affected();
+ON CONFLICT (poll_kind, user_tg_id, option_id) DO NOTHING
+            "#,
+            poll_kind,
+            user_tg_id,
+            option_id,
+        )
+        .execute(txn)
+        .await?
+        .rows_affected();
 
-            if inserted == 0 {
-                warn!("tried to enable already enabled option")
-            }
+        if inserted == 0 {
+            warn!("tried to enable already enabled option")
         }
 
         Ok(())
@@ -132,6 +126,7 @@ VALUES ($1, $2, $3)
         user_tg_id: i64,
         poll_kind: PollKind,
     ) -> Result<()> {
+        // personal options are kept in the catalog, so they can be chosen back later
         sqlx::query!(
             r#"
 DELETE FROM poll_custom_options
@@ -0,0 +1,92 @@
+use color_eyre::{eyre::eyre, Result};
+use sqlx::FromRow;
+
+use crate::{PgTransaction, PollPart};
+
+/// Rating of a single dimension (energy, anxiety, etc.) in a multi-dimensional check-in
+#[derive(Debug, Clone, FromRow)]
+pub struct PollDimensionAnswer {
+    pub poll_id: i64,
+    pub poll_part_id: i64,
+    pub user_tg_id: i64,
+    /// Text of the dimension, as the user have seen it
+    pub dimension: String,
+    /// Catalog id of the dimension
+    pub option_id: Option<i64>,
+    pub value: i32,
+}
+
+impl PollDimensionAnswer {
+    pub const MIN_VALUE: i32 = 1;
+    pub const MAX_VALUE: i32 = 5;
+
+    /// Saves user's rating of the dimension with the given index in the part,
+    /// replacing the previous one
+    #[tracing::instrument(skip(txn, part), err)]
+    pub async fn save(
+        txn: &mut PgTransaction<'_>,
+        part: &PollPart,
+        user_tg_id: i64,
+        dimension_idx: usize,
+        value: i32,
+    ) -> Result<Self> {
+        let part_id = part
+            .id
+            .ok_or_else(|| eyre!("trying to save answer for unsaved poll part"))?;
+        let dimension = part
+            .options
+            .get(dimension_idx)
+            .ok_or_else(|| eyre!("unknown dimension {dimension_idx} in poll part"))?;
+
+        Ok(sqlx::query_as!(
+            Self,
+            r#"
+INSERT INTO poll_dimension_answers (
+    poll_id, poll_part_id, user_tg_id, dimension, option_id, value
+)
+VALUES ($1, $2, $3, $4, $5, $6)
+ON CONFLICT (poll_id, dimension) DO UPDATE
+SET
+    value = EXCLUDED.value,
+    date_updated = NOW()
+RETURNING
+    poll_id,
+    poll_part_id,
+    user_tg_id,
+    dimension,
+    option_id,
+    value
+            "#,
+            part.poll_id,
+            part_id,
+            user_tg_id,
+            dimension,
+            part.option_id(dimension_idx),
+            value,
+        )
+        .fetch_one(txn)
+        .await?)
+    }
+
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn get_for_poll(txn: &mut PgTransaction<'_>, poll_id: i64) -> Result<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"
+SELECT
+    poll_id,
+    poll_part_id,
+    user_tg_id,
+    dimension,
+    option_id,
+    value
+FROM poll_dimension_answers
+WHERE
+    poll_id = $1
+            "#,
+            poll_id,
+        )
+        .fetch_all(txn)
+        .await?)
+    }
+}
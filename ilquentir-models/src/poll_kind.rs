@@ -2,14 +2,28 @@ use color_eyre::Result;
 use time::{ext::NumericalDuration, macros::time, Duration, OffsetDateTime, Time};
 use tracing::error;
 
-use crate::{PgTransaction, PollCustomOptions, PollSettings};
+use ilquentir_messages::Locale;
+
+use crate::{MoodScale, PgTransaction, PollChoice, PollCustomOptions, PollOption, PollSettings};
 
 /// Describes possible kind of polls
 #[derive(
-    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, sqlx::Type, strum::EnumIter, strum::Display,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    sqlx::Type,
+    strum::EnumIter,
+    strum::Display,
+    serde::Serialize,
+    serde::Deserialize,
 )]
 #[sqlx(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
 pub enum PollKind {
     /// Ask user, how was his day
     HowWasYourDay,
@@ -17,6 +31,10 @@ pub enum PollKind {
     FoodAllergy,
     /// Ask user about events that happened to him during his day
     DailyEvents,
+    /// Ask user, what he is grateful for today, answer is a free text
+    GratitudeJournal,
+    /// Ask user to rate several dimensions of his mood (energy, anxiety, etc.) in one message
+    MoodCheckIn,
 }
 
 impl PollKind {
@@ -28,6 +46,10 @@ impl PollKind {
             Self::DailyEvents => Some(time!(19:00)),
             // 21:00 MSK = 18:00 UTC
             Self::FoodAllergy => Some(time!(18:00)),
+            // 22:00 MSK = 19:00 UTC
+            Self::GratitudeJournal => Some(time!(19:00)),
+            // 22:00 MSK = 19:00 UTC
+            Self::MoodCheckIn => Some(time!(19:00)),
         }
     }
 
@@ -81,13 +103,24 @@ impl PollKind {
         }
     }
 
-    pub fn question(self) -> String {
-        match self {
-            Self::HowWasYourDay => "Как прошёл твой день?",
-            Self::FoodAllergy => {
+    pub fn question(self, locale: Locale) -> String {
+        match (self, locale) {
+            (Self::HowWasYourDay, Locale::Ru) => "Как прошёл твой день?",
+            (Self::HowWasYourDay, Locale::En) => "How was your day?",
+            (Self::FoodAllergy, Locale::Ru) => {
+                "Испытывал(а) ли ты что-то из этого после еды сегодня?"
+            }
+            (Self::FoodAllergy, Locale::En) => {
                 "Had you encountered any of described feelings after the meal today?"
             }
-            Self::DailyEvents => "Что было сегодня?",
+            (Self::DailyEvents, Locale::Ru) => "Что было сегодня?",
+            (Self::DailyEvents, Locale::En) => "What happened today?",
+            (Self::GratitudeJournal, Locale::Ru) => {
+                "За что ты можешь сказать спасибо сегодняшнему дню?"
+            }
+            (Self::GratitudeJournal, Locale::En) => "What are you grateful to this day for?",
+            (Self::MoodCheckIn, Locale::Ru) => "Оцени своё состояние сегодня от 1 до 5",
+            (Self::MoodCheckIn, Locale::En) => "Rate how you feel today from 1 to 5",
         }
         .to_owned()
     }
@@ -97,9 +130,34 @@ impl PollKind {
             Self::HowWasYourDay => false,
             Self::FoodAllergy => true,
             Self::DailyEvents => true,
+            Self::GratitudeJournal => false,
+            Self::MoodCheckIn => false,
         }
     }
 
+    /// Prompt-style polls are sent as a plain message
+    /// and answered with the user's next text reply instead of poll options
+    pub fn is_prompt(self) -> bool {
+        match self {
+            Self::HowWasYourDay | Self::FoodAllergy | Self::DailyEvents | Self::MoodCheckIn => {
+                false
+            }
+            Self::GratitudeJournal => true,
+        }
+    }
+
+    /// Options of scale polls are ordered from the best to the worst,
+    /// answers are additionally saved as a normalized value (see [`MoodScale::normalize`])
+    pub fn is_scale(self) -> bool {
+        matches!(self, Self::HowWasYourDay)
+    }
+
+    /// Check-in polls are sent as a single message, where each option
+    /// is a dimension to be rated separately
+    pub fn is_check_in(self) -> bool {
+        matches!(self, Self::MoodCheckIn)
+    }
+
     /// How much time must pass after its publication
     /// to discount it as obsolete
     pub fn overdue_interval(self) -> Duration {
@@ -107,42 +165,99 @@ impl PollKind {
             Self::HowWasYourDay => (2 * 24 - 1).hours(),
             Self::DailyEvents => (2 * 24 - 1).hours(),
             Self::FoodAllergy => 23.hours(),
+            Self::GratitudeJournal => (2 * 24 - 1).hours(),
+            Self::MoodCheckIn => (2 * 24 - 1).hours(),
         }
     }
 
+    /// Options of the poll for the user, catalog options are translated when possible
     #[tracing::instrument(skip(txn), err)]
     pub async fn options(
         self,
         txn: &mut PgTransaction<'_>,
         user_tg_id: i64,
-    ) -> Result<Vec<String>> {
-        Ok(match self {
-            Self::HowWasYourDay => ["+2 (супер!)", "+1", "0", "-1", "-2 (отвратительно)"][..]
-                .iter()
-                .map(|&option| option.to_string())
-                .collect(),
-            Self::FoodAllergy => [
-                "Shortness of breath",
-                "Itching",
-                "Bloating",
-                "Anxiety",
-                "Nope, nothing :)",
-            ][..]
-                .iter()
-                .map(|&option| option.to_string())
+        locale: Locale,
+    ) -> Result<Vec<PollChoice>> {
+        let mut options = match self {
+            Self::HowWasYourDay => MoodScale::get(txn, user_tg_id, locale)
+                .await?
+                .labels
+                .into_iter()
+                .map(PollChoice::text_only)
                 .collect(),
+            Self::FoodAllergy => match locale {
+                Locale::Ru => [
+                    "Одышка",
+                    "Зуд",
+                    "Вздутие живота",
+                    "Тревожность",
+                    "Нет, ничего :)",
+                ],
+                Locale::En => [
+                    "Shortness of breath",
+                    "Itching",
+                    "Bloating",
+                    "Anxiety",
+                    "Nope, nothing :)",
+                ],
+            }
+            .into_iter()
+            .map(PollChoice::text_only)
+            .collect(),
             Self::DailyEvents => {
-                const NOTHING_OPTION: &str = "Ничего";
+                let nothing_option = match locale {
+                    Locale::Ru => "Ничего",
+                    Locale::En => "Nothing",
+                };
 
-                let mut chosen =
+                let mut chosen: Vec<_> =
                     PollCustomOptions::get_for_user(txn, user_tg_id, Self::DailyEvents)
                         .await?
-                        .options;
-                chosen.push(NOTHING_OPTION.to_owned());
+                        .options
+                        .into_iter()
+                        .map(PollChoice::from)
+                        .collect();
+                chosen.push(PollChoice::text_only(nothing_option));
 
                 chosen
             }
-        })
+            Self::GratitudeJournal => vec![],
+            Self::MoodCheckIn => {
+                let chosen =
+                    PollCustomOptions::get_for_user(&mut *txn, user_tg_id, Self::MoodCheckIn)
+                        .await?
+                        .options;
+
+                let chosen = if chosen.is_empty() {
+                    PollOption::get_catalog(txn, Self::MoodCheckIn)
+                        .await?
+                        .into_iter()
+                        .filter(|option| option.is_default)
+                        .collect()
+                } else {
+                    chosen
+                };
+
+                chosen.into_iter().map(PollChoice::from).collect()
+            }
+        };
+
+        if locale != Locale::default() {
+            let option_ids: Vec<_> = options
+                .iter()
+                .filter_map(|option| option.option_id)
+                .collect();
+            let mut translations =
+                PollOption::get_translations(txn, &option_ids, locale.code()).await?;
+
+            for option in &mut options {
+                if let Some(text) = option.option_id.and_then(|id| translations.remove(&id)) {
+                    option.text = text;
+                }
+            }
+        }
+
+        Ok(options)
     }
 }
 
@@ -173,11 +288,16 @@ mod tests {
             PollKind::HowWasYourDay,
             PollKind::FoodAllergy,
             PollKind::DailyEvents,
+            PollKind::GratitudeJournal,
+            PollKind::MoodCheckIn,
         ] {
             // match is here to be sure that it's impossible
             // to add new enum kind without testing it :)
             match kind {
-                PollKind::HowWasYourDay | PollKind::DailyEvents => {
+                PollKind::HowWasYourDay
+                | PollKind::DailyEvents
+                | PollKind::GratitudeJournal
+                | PollKind::MoodCheckIn => {
                     // every day at 19:00 UTC
 
                     assert_eq!(
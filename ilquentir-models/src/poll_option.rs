@@ -0,0 +1,684 @@
+use std::collections::HashMap;
+
+use color_eyre::{eyre::ensure, Result};
+use sqlx::FromRow;
+
+use ilquentir_messages::Locale;
+
+use crate::{PgTransaction, PollKind};
+
+/// Option of a poll, identified by a stable id.
+///
+/// Text of the option is versioned, so fixing a typo doesn't orphan
+/// users' selections and answers, which reference the option by id.
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct PollOption {
+    pub id: i64,
+    pub poll_kind: PollKind,
+    /// `None` for catalog options, author of the personal option otherwise
+    pub owner_tg_id: Option<i64>,
+    /// Category in the settings, `None` for uncategorized and personal options
+    pub category_id: Option<i64>,
+    /// Order of the option in the catalog
+    pub position: i32,
+    /// Enabled for users, who haven't chosen anything themselves
+    pub is_default: bool,
+    /// Inactive options are hidden from users, but can be activated back
+    pub active: bool,
+    /// Latest version of the text in the default language
+    pub text: String,
+}
+
+/// Option as it is sent to the user, options outside of the catalog have no id
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PollChoice {
+    pub option_id: Option<i64>,
+    pub text: String,
+}
+
+impl PollChoice {
+    pub fn text_only(text: impl Into<String>) -> Self {
+        Self {
+            option_id: None,
+            text: text.into(),
+        }
+    }
+}
+
+impl From<PollOption> for PollChoice {
+    fn from(option: PollOption) -> Self {
+        Self {
+            option_id: Some(option.id),
+            text: option.text,
+        }
+    }
+}
+
+impl PollOption {
+    /// Telegram doesn't allow longer poll options
+    pub const MAX_TEXT_LEN: usize = 100;
+    /// Language of the texts, which are shown to users without a translation
+    pub const DEFAULT_LANGUAGE: &'static str = Locale::Ru.code();
+    /// Languages, which option texts and category labels can be written in
+    pub const LANGUAGES: [&'static str; 2] = [Locale::Ru.code(), Locale::En.code()];
+
+    /// Options of the catalog, which are available to all users
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn get_catalog(
+        txn: &mut PgTransaction<'_>,
+        poll_kind: PollKind,
+    ) -> Result<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"
+SELECT
+    poll_options.id,
+    poll_kind as "poll_kind: PollKind",
+    owner_tg_id,
+    category_id,
+    position,
+    is_default,
+    active,
+    poll_option_latest_texts.text as "text!"
+FROM poll_options
+JOIN poll_option_latest_texts
+ON
+    poll_options.id = poll_option_latest_texts.option_id
+WHERE
+    poll_kind = $1
+    AND owner_tg_id IS NULL
+    AND active
+    AND NOT retired
+ORDER BY
+    position,
+    poll_options.id
+            "#,
+            poll_kind.to_string(),
+        )
+        .fetch_all(txn)
+        .await?)
+    }
+
+    /// Options, added by the user, both chosen and not
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn get_personal(
+        txn: &mut PgTransaction<'_>,
+        user_tg_id: i64,
+        poll_kind: PollKind,
+    ) -> Result<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"
+SELECT
+    poll_options.id,
+    poll_kind as "poll_kind: PollKind",
+    owner_tg_id,
+    category_id,
+    position,
+    is_default,
+    active,
+    poll_option_latest_texts.text as "text!"
+FROM poll_options
+JOIN poll_option_latest_texts
+ON
+    poll_options.id = poll_option_latest_texts.option_id
+WHERE
+    poll_kind = $1
+    AND owner_tg_id = $2
+    AND NOT retired
+ORDER BY
+    poll_options.id
+            "#,
+            poll_kind.to_string(),
+            user_tg_id,
+        )
+        .fetch_all(txn)
+        .await?)
+    }
+
+    /// Adds personal option and chooses it for the user.
+    ///
+    /// Returns `None` if the user already has an option with the same text.
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn create_personal(
+        txn: &mut PgTransaction<'_>,
+        user_tg_id: i64,
+        poll_kind: PollKind,
+        text: &str,
+    ) -> Result<Option<Self>> {
+        ensure_text_len(text)?;
+
+        if Self::text_exists(&mut *txn, user_tg_id, poll_kind, text, None).await? {
+            return Ok(None);
+        }
+
+        let option_id = sqlx::query!(
+            r#"
+INSERT INTO poll_options (
+    poll_kind, owner_tg_id
+)
+VALUES ($1, $2)
+RETURNING
+    id
+            "#,
+            poll_kind.to_string(),
+            user_tg_id,
+        )
+        .fetch_one(&mut *txn)
+        .await?
+        .id;
+
+        Self::add_text_version(&mut *txn, option_id, Self::DEFAULT_LANGUAGE, text).await?;
+
+        sqlx::query!(
+            r#"
+INSERT INTO poll_custom_options (
+    poll_kind, user_tg_id, option_id
+)
+VALUES ($1, $2, $3)
+            "#,
+            poll_kind.to_string(),
+            user_tg_id,
+            option_id,
+        )
+        .execute(&mut *txn)
+        .await?;
+
+        Ok(Some(Self {
+            id: option_id,
+            poll_kind,
+            owner_tg_id: Some(user_tg_id),
+            category_id: None,
+            position: 0,
+            is_default: false,
+            active: true,
+            text: text.to_owned(),
+        }))
+    }
+
+    /// Adds a new version of the personal option text.
+    ///
+    /// Returns `None` if there is no such option or the user already has an option with the same text.
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn rename_personal(
+        txn: &mut PgTransaction<'_>,
+        user_tg_id: i64,
+        option_id: i64,
+        text: &str,
+    ) -> Result<Option<Self>> {
+        ensure_text_len(text)?;
+
+        let option = if let Some(option) =
+            Self::get_personal_by_id(&mut *txn, user_tg_id, option_id).await?
+        {
+            option
+        } else {
+            return Ok(None);
+        };
+
+        if Self::text_exists(
+            &mut *txn,
+            user_tg_id,
+            option.poll_kind,
+            text,
+            Some(option_id),
+        )
+        .await?
+        {
+            return Ok(None);
+        }
+
+        Self::add_text_version(&mut *txn, option_id, Self::DEFAULT_LANGUAGE, text).await?;
+
+        Ok(Some(Self {
+            text: text.to_owned(),
+            ..option
+        }))
+    }
+
+    /// Retires personal option: it can't be chosen anymore, but is kept for historic answers
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn retire_personal(
+        txn: &mut PgTransaction<'_>,
+        user_tg_id: i64,
+        option_id: i64,
+    ) -> Result<bool> {
+        let retired = sqlx::query!(
+            r#"
+UPDATE poll_options
+SET
+    retired = TRUE
+WHERE
+    id = $2
+    AND owner_tg_id = $1
+    AND NOT retired
+            "#,
+            user_tg_id,
+            option_id,
+        )
+        .execute(&mut *txn)
+        .await?
+        .rows_affected()
+            > 0;
+
+        sqlx::query!(
+            r#"
+DELETE FROM poll_custom_options
+WHERE
+    user_tg_id = $1
+    AND option_id = $2
+            "#,
+            user_tg_id,
+            option_id,
+        )
+        .execute(txn)
+        .await?;
+
+        Ok(retired)
+    }
+
+    /// All the catalog options, including inactive ones, for admins to manage
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn get_catalog_for_admin(
+        txn: &mut PgTransaction<'_>,
+        poll_kind: PollKind,
+    ) -> Result<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"
+SELECT
+    poll_options.id,
+    poll_kind as "poll_kind: PollKind",
+    owner_tg_id,
+    category_id,
+    position,
+    is_default,
+    active,
+    poll_option_latest_texts.text as "text!"
+FROM poll_options
+JOIN poll_option_latest_texts
+ON
+    poll_options.id = poll_option_latest_texts.option_id
+WHERE
+    poll_kind = $1
+    AND owner_tg_id IS NULL
+    AND NOT retired
+ORDER BY
+    position,
+    poll_options.id
+            "#,
+            poll_kind.to_string(),
+        )
+        .fetch_all(txn)
+        .await?)
+    }
+
+    /// Adds option to the end of the catalog.
+    ///
+    /// Returns `None` if the catalog already has an option with the same text.
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn create_catalog(
+        txn: &mut PgTransaction<'_>,
+        poll_kind: PollKind,
+        category_id: Option<i64>,
+        text: &str,
+    ) -> Result<Option<Self>> {
+        ensure_text_len(text)?;
+
+        let exists = Self::get_catalog_for_admin(&mut *txn, poll_kind)
+            .await?
+            .iter()
+            .any(|option| option.text == text);
+        if exists {
+            return Ok(None);
+        }
+
+        let created = sqlx::query!(
+            r#"
+INSERT INTO poll_options (
+    poll_kind, category_id, position
+)
+SELECT
+    $1::VARCHAR,
+    $2,
+    COALESCE(MAX(position), 0) + 1
+FROM poll_options
+WHERE
+    poll_kind = $1::VARCHAR
+    AND owner_tg_id IS NULL
+RETURNING
+    id,
+    position
+            "#,
+            poll_kind.to_string(),
+            category_id,
+        )
+        .fetch_one(&mut *txn)
+        .await?;
+
+        Self::add_text_version(&mut *txn, created.id, Self::DEFAULT_LANGUAGE, text).await?;
+
+        Ok(Some(Self {
+            id: created.id,
+            poll_kind,
+            owner_tg_id: None,
+            category_id,
+            position: created.position,
+            is_default: false,
+            active: true,
+            text: text.to_owned(),
+        }))
+    }
+
+    /// Adds a new version of the catalog option text in the given language
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn rename_catalog(
+        txn: &mut PgTransaction<'_>,
+        option_id: i64,
+        language: &str,
+        text: &str,
+    ) -> Result<bool> {
+        ensure_text_len(text)?;
+        ensure!(
+            Self::LANGUAGES.contains(&language),
+            "unsupported language {language}"
+        );
+
+        let exists = sqlx::query!(
+            r#"
+SELECT
+    id
+FROM poll_options
+WHERE
+    id = $1
+    AND owner_tg_id IS NULL
+    AND NOT retired
+            "#,
+            option_id,
+        )
+        .fetch_optional(&mut *txn)
+        .await?
+        .is_some();
+        if !exists {
+            return Ok(false);
+        }
+
+        Self::add_text_version(&mut *txn, option_id, language, text).await?;
+
+        Ok(true)
+    }
+
+    /// Hides catalog option from users or shows it back
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn set_active(
+        txn: &mut PgTransaction<'_>,
+        option_id: i64,
+        active: bool,
+    ) -> Result<bool> {
+        Ok(sqlx::query!(
+            r#"
+UPDATE poll_options
+SET
+    active = $2
+WHERE
+    id = $1
+    AND owner_tg_id IS NULL
+    AND NOT retired
+            "#,
+            option_id,
+            active,
+        )
+        .execute(txn)
+        .await?
+        .rows_affected()
+            > 0)
+    }
+
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn set_position(
+        txn: &mut PgTransaction<'_>,
+        option_id: i64,
+        position: i32,
+    ) -> Result<bool> {
+        Ok(sqlx::query!(
+            r#"
+UPDATE poll_options
+SET
+    position = $2
+WHERE
+    id = $1
+    AND owner_tg_id IS NULL
+    AND NOT retired
+            "#,
+            option_id,
+            position,
+        )
+        .execute(txn)
+        .await?
+        .rows_affected()
+            > 0)
+    }
+
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn set_category(
+        txn: &mut PgTransaction<'_>,
+        option_id: i64,
+        category_id: Option<i64>,
+    ) -> Result<bool> {
+        Ok(sqlx::query!(
+            r#"
+UPDATE poll_options
+SET
+    category_id = $2
+WHERE
+    id = $1
+    AND owner_tg_id IS NULL
+    AND NOT retired
+            "#,
+            option_id,
+            category_id,
+        )
+        .execute(txn)
+        .await?
+        .rows_affected()
+            > 0)
+    }
+
+    /// Retires catalog option: it disappears from users' choice, but is kept for historic answers
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn retire_catalog(txn: &mut PgTransaction<'_>, option_id: i64) -> Result<bool> {
+        let retired = sqlx::query!(
+            r#"
+UPDATE poll_options
+SET
+    retired = TRUE
+WHERE
+    id = $1
+    AND owner_tg_id IS NULL
+    AND NOT retired
+            "#,
+            option_id,
+        )
+        .execute(&mut *txn)
+        .await?
+        .rows_affected()
+            > 0;
+
+        sqlx::query!(
+            r#"
+DELETE FROM poll_custom_options
+WHERE
+    option_id = $1
+            "#,
+            option_id,
+        )
+        .execute(txn)
+        .await?;
+
+        Ok(retired)
+    }
+
+    #[tracing::instrument(skip(txn), err)]
+    async fn get_personal_by_id(
+        txn: &mut PgTransaction<'_>,
+        user_tg_id: i64,
+        option_id: i64,
+    ) -> Result<Option<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"
+SELECT
+    poll_options.id,
+    poll_kind as "poll_kind: PollKind",
+    owner_tg_id,
+    category_id,
+    position,
+    is_default,
+    active,
+    poll_option_latest_texts.text as "text!"
+FROM poll_options
+JOIN poll_option_latest_texts
+ON
+    poll_options.id = poll_option_latest_texts.option_id
+WHERE
+    poll_options.id = $2
+    AND owner_tg_id = $1
+    AND NOT retired
+            "#,
+            user_tg_id,
+            option_id,
+        )
+        .fetch_optional(txn)
+        .await?)
+    }
+
+    /// Checks, if the user already sees an option with the given text,
+    /// either from the catalog or a personal one
+    #[tracing::instrument(skip(txn), err, ret)]
+    async fn text_exists(
+        txn: &mut PgTransaction<'_>,
+        user_tg_id: i64,
+        poll_kind: PollKind,
+        text: &str,
+        except_option_id: Option<i64>,
+    ) -> Result<bool> {
+        Ok(sqlx::query!(
+            r#"
+SELECT
+    poll_options.id
+FROM poll_options
+JOIN poll_option_latest_texts
+ON
+    poll_options.id = poll_option_latest_texts.option_id
+WHERE
+    poll_kind = $1
+    AND (owner_tg_id IS NULL OR owner_tg_id = $2)
+    AND NOT retired
+    AND poll_option_latest_texts.text = $3
+    AND poll_options.id IS DISTINCT FROM $4
+LIMIT 1
+            "#,
+            poll_kind.to_string(),
+            user_tg_id,
+            text,
+            except_option_id,
+        )
+        .fetch_optional(txn)
+        .await?
+        .is_some())
+    }
+
+    /// Replaces texts of the options with their translations, untranslated ones are kept as is
+    #[tracing::instrument(skip(txn, options), err)]
+    pub async fn translate(
+        txn: &mut PgTransaction<'_>,
+        options: &mut [Self],
+        language: &str,
+    ) -> Result<()> {
+        if language == Self::DEFAULT_LANGUAGE {
+            return Ok(());
+        }
+
+        let option_ids: Vec<_> = options.iter().map(|option| option.id).collect();
+        let mut translations = Self::get_translations(txn, &option_ids, language).await?;
+
+        for option in options {
+            if let Some(text) = translations.remove(&option.id) {
+                option.text = text;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Latest texts of the options in the given language, untranslated options are omitted
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn get_translations(
+        txn: &mut PgTransaction<'_>,
+        option_ids: &[i64],
+        language: &str,
+    ) -> Result<HashMap<i64, String>> {
+        Ok(sqlx::query!(
+            r#"
+SELECT
+    option_id,
+    text
+FROM poll_option_localized_texts
+WHERE
+    option_id = ANY($1)
+    AND language = $2
+            "#,
+            option_ids,
+            language,
+        )
+        .fetch_all(txn)
+        .await?
+        .into_iter()
+        .filter_map(|row| Some((row.option_id?, row.text?)))
+        .collect())
+    }
+
+    #[tracing::instrument(skip(txn), err)]
+    async fn add_text_version(
+        txn: &mut PgTransaction<'_>,
+        option_id: i64,
+        language: &str,
+        text: &str,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+INSERT INTO poll_option_texts (
+    option_id, language, version, text
+)
+SELECT
+    $1,
+    $2,
+    COALESCE(MAX(version), 0) + 1,
+    $3
+FROM poll_option_texts
+WHERE
+    option_id = $1
+    AND language = $2::VARCHAR
+            "#,
+            option_id,
+            language,
+            text,
+        )
+        .execute(txn)
+        .await?;
+
+        Ok(())
+    }
+}
+
+fn ensure_text_len(text: &str) -> Result<()> {
+    let len = text.chars().count();
+
+    ensure!(
+        len > 0 && len <= PollOption::MAX_TEXT_LEN,
+        "option must be from 1 to {} characters long, got {len}",
+        PollOption::MAX_TEXT_LEN
+    );
+
+    Ok(())
+}
@@ -0,0 +1,173 @@
+use color_eyre::{eyre::ensure, Result};
+use sqlx::FromRow;
+
+use crate::{PgTransaction, PollKind, PollOption};
+
+/// Group of catalog options (health, social, work...) in the settings
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct PollOptionCategory {
+    pub id: i64,
+    pub poll_kind: PollKind,
+    /// Short name, used by admins to reference the category
+    pub slug: String,
+    pub position: i32,
+    /// Label in the default language, falls back to the slug
+    pub label: String,
+}
+
+impl PollOptionCategory {
+    pub const MAX_SLUG_LEN: usize = 50;
+    pub const MAX_LABEL_LEN: usize = 100;
+
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn get_all(txn: &mut PgTransaction<'_>, poll_kind: PollKind) -> Result<Vec<Self>> {
+        Self::get_all_translated(txn, poll_kind, PollOption::DEFAULT_LANGUAGE).await
+    }
+
+    /// Categories with labels in the given language, falling back to the default one
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn get_all_translated(
+        txn: &mut PgTransaction<'_>,
+        poll_kind: PollKind,
+        language: &str,
+    ) -> Result<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"
+SELECT
+    poll_option_categories.id,
+    poll_kind as "poll_kind: PollKind",
+    slug,
+    position,
+    COALESCE(translated.label, default_label.label, slug) as "label!"
+FROM poll_option_categories
+LEFT JOIN poll_option_category_labels AS translated
+ON
+    poll_option_categories.id = translated.category_id
+    AND translated.language = $2
+LEFT JOIN poll_option_category_labels AS default_label
+ON
+    poll_option_categories.id = default_label.category_id
+    AND default_label.language = $3
+WHERE
+    poll_kind = $1
+ORDER BY
+    position,
+    poll_option_categories.id
+            "#,
+            poll_kind.to_string(),
+            language,
+            PollOption::DEFAULT_LANGUAGE,
+        )
+        .fetch_all(txn)
+        .await?)
+    }
+
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn get_by_slug(
+        txn: &mut PgTransaction<'_>,
+        poll_kind: PollKind,
+        slug: &str,
+    ) -> Result<Option<Self>> {
+        Ok(Self::get_all(txn, poll_kind)
+            .await?
+            .into_iter()
+            .find(|category| category.slug == slug))
+    }
+
+    /// Adds category to the end of the list.
+    ///
+    /// Returns `None` if there is a category with the same slug already.
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn create(
+        txn: &mut PgTransaction<'_>,
+        poll_kind: PollKind,
+        slug: &str,
+        label: &str,
+    ) -> Result<Option<Self>> {
+        ensure!(
+            !slug.is_empty() && slug.len() <= Self::MAX_SLUG_LEN,
+            "slug must be from 1 to {} characters long",
+            Self::MAX_SLUG_LEN
+        );
+
+        let created = sqlx::query!(
+            r#"
+INSERT INTO poll_option_categories (
+    poll_kind, slug, position
+)
+SELECT
+    $1::VARCHAR,
+    $2,
+    COALESCE(MAX(position), 0) + 1
+FROM poll_option_categories
+WHERE
+    poll_kind = $1::VARCHAR
+ON CONFLICT (poll_kind, slug) DO NOTHING
+RETURNING
+    id,
+    position
+            "#,
+            poll_kind.to_string(),
+            slug,
+        )
+        .fetch_optional(&mut *txn)
+        .await?;
+
+        let created = if let Some(created) = created {
+            created
+        } else {
+            return Ok(None);
+        };
+
+        Self::set_label(&mut *txn, created.id, PollOption::DEFAULT_LANGUAGE, label).await?;
+
+        Ok(Some(Self {
+            id: created.id,
+            poll_kind,
+            slug: slug.to_owned(),
+            position: created.position,
+            label: label.to_owned(),
+        }))
+    }
+
+    /// Sets label of the category in the given language
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn set_label(
+        txn: &mut PgTransaction<'_>,
+        category_id: i64,
+        language: &str,
+        label: &str,
+    ) -> Result<()> {
+        let len = label.chars().count();
+        ensure!(
+            len > 0 && len <= Self::MAX_LABEL_LEN,
+            "label must be from 1 to {} characters long, got {len}",
+            Self::MAX_LABEL_LEN
+        );
+        ensure!(
+            PollOption::LANGUAGES.contains(&language),
+            "unsupported language {language}"
+        );
+
+        sqlx::query!(
+            r#"
+INSERT INTO poll_option_category_labels (
+    category_id, language, label
+)
+VALUES ($1, $2, $3)
+ON CONFLICT (category_id, language) DO UPDATE
+SET
+    label = EXCLUDED.label,
+    date_updated = NOW()
+            "#,
+            category_id,
+            language,
+            label,
+        )
+        .execute(txn)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,146 @@
+use color_eyre::Result;
+use sqlx::FromRow;
+
+use crate::PgTransaction;
+
+/// One Telegram message of a logical [`crate::Poll`].
+///
+/// Telegram allows only 10 options per poll, so polls with
+/// more options are sent as several messages.
+#[derive(Debug, Clone, FromRow)]
+pub struct PollPart {
+    pub id: Option<i64>,
+    pub poll_id: i64,
+    /// Telegram poll id, absent for prompt-style polls
+    pub tg_id: Option<String>,
+    pub tg_message_id: Option<i32>,
+    /// Index of the first option of this part in the logical poll
+    pub options_offset: i32,
+    /// Texts of the options of this part
+    pub options: Vec<String>,
+    /// Catalog ids of the options, aligned with `options`,
+    /// `None` for options outside of the catalog
+    pub option_ids: Vec<Option<i64>>,
+}
+
+impl PollPart {
+    /// Catalog id of the option with the given index in this part
+    pub fn option_id(&self, idx: usize) -> Option<i64> {
+        self.option_ids.get(idx).copied().flatten()
+    }
+
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn insert(self, txn: &mut PgTransaction<'_>) -> Result<Self> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"
+INSERT INTO poll_parts (
+    poll_id,
+    tg_id,
+    tg_message_id,
+    options_offset,
+    options,
+    option_ids
+)
+VALUES ($1, $2, $3, $4, $5, $6)
+RETURNING
+    id as "id?",
+    poll_id,
+    tg_id,
+    tg_message_id,
+    options_offset,
+    options,
+    option_ids as "option_ids: Vec<Option<i64>>"
+            "#,
+            self.poll_id,
+            self.tg_id,
+            self.tg_message_id,
+            self.options_offset,
+            &self.options,
+            &self.option_ids as _,
+        )
+        .fetch_one(txn)
+        .await?)
+    }
+
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn get_by_tg_id(txn: &mut PgTransaction<'_>, tg_id: &str) -> Result<Self> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"
+SELECT
+    id as "id?",
+    poll_id,
+    tg_id,
+    tg_message_id,
+    options_offset,
+    options,
+    option_ids as "option_ids: Vec<Option<i64>>"
+FROM poll_parts
+WHERE
+    tg_id = $1
+            "#,
+            tg_id,
+        )
+        .fetch_one(txn)
+        .await?)
+    }
+
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn get_for_poll(txn: &mut PgTransaction<'_>, poll_id: i64) -> Result<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"
+SELECT
+    id as "id?",
+    poll_id,
+    tg_id,
+    tg_message_id,
+    options_offset,
+    options,
+    option_ids as "option_ids: Vec<Option<i64>>"
+FROM poll_parts
+WHERE
+    poll_id = $1
+ORDER BY
+    options_offset
+            "#,
+            poll_id,
+        )
+        .fetch_all(txn)
+        .await?)
+    }
+
+    /// Get poll part, which was sent as a given message
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn get_by_message(
+        txn: &mut PgTransaction<'_>,
+        chat_tg_id: i64,
+        tg_message_id: i32,
+    ) -> Result<Option<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"
+SELECT
+    poll_parts.id as "id?",
+    poll_id,
+    poll_parts.tg_id,
+    tg_message_id,
+    options_offset,
+    options,
+    option_ids as "option_ids: Vec<Option<i64>>"
+FROM poll_parts
+JOIN polls
+ON
+    poll_parts.poll_id = polls.id
+WHERE
+    polls.chat_tg_id = $1
+    AND poll_parts.tg_message_id = $2
+            "#,
+            chat_tg_id,
+            tg_message_id,
+        )
+        .fetch_optional(txn)
+        .await?)
+    }
+}
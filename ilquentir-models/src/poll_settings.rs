@@ -2,13 +2,14 @@ use color_eyre::Result;
 use sqlx::FromRow;
 use time::Time;
 
-use crate::{PgTransaction, PollKind};
+use crate::{AnswerMode, PgTransaction, PollKind};
 
 #[derive(Debug, Clone, FromRow)]
 pub struct PollSettings {
     pub poll_kind: PollKind,
     pub user_tg_id: i64,
     pub send_at_utc: Option<Time>,
+    pub answer_mode: AnswerMode,
 }
 
 impl PollSettings {
@@ -24,7 +25,8 @@ impl PollSettings {
 SELECT
     poll_kind as "poll_kind: PollKind",
     user_tg_id,
-    send_at_utc
+    send_at_utc,
+    answer_mode as "answer_mode: AnswerMode"
 FROM
     poll_settings
 WHERE
@@ -60,7 +62,8 @@ UPDATE SET
 RETURNING
     user_tg_id,
     poll_kind as "poll_kind: PollKind",
-    send_at_utc
+    send_at_utc,
+    answer_mode as "answer_mode: AnswerMode"
             "#,
             user_tg_id,
             poll_kind.to_string(),
@@ -69,4 +72,49 @@ RETURNING
         .fetch_one(txn)
         .await?)
     }
+
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn get_answer_mode(
+        txn: &mut PgTransaction<'_>,
+        user_tg_id: i64,
+        poll_kind: PollKind,
+    ) -> Result<AnswerMode> {
+        Ok(Self::get(txn, user_tg_id, poll_kind)
+            .await?
+            .map(|settings| settings.answer_mode)
+            .unwrap_or_default())
+    }
+
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn set_answer_mode(
+        txn: &mut PgTransaction<'_>,
+        user_tg_id: i64,
+        poll_kind: PollKind,
+        answer_mode: AnswerMode,
+    ) -> Result<Self> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"
+INSERT INTO poll_settings (
+    user_tg_id,
+    poll_kind,
+    answer_mode
+)
+VALUES ($1, $2, $3)
+ON CONFLICT ON CONSTRAINT poll_settings_poll_kind_user_tg_id_key DO
+UPDATE SET
+    answer_mode = $3
+RETURNING
+    user_tg_id,
+    poll_kind as "poll_kind: PollKind",
+    send_at_utc,
+    answer_mode as "answer_mode: AnswerMode"
+            "#,
+            user_tg_id,
+            poll_kind.to_string(),
+            answer_mode.to_string()
+        )
+        .fetch_one(txn)
+        .await?)
+    }
 }
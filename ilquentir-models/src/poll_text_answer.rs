@@ -0,0 +1,36 @@
+use color_eyre::Result;
+use sqlx::FromRow;
+use time::OffsetDateTime;
+
+use crate::PgTransaction;
+
+/// Free-text answer to a prompt-style poll (see [`crate::PollKind::is_prompt`])
+#[derive(Debug, Clone, FromRow)]
+pub struct PollTextAnswer {
+    pub poll_id: i64,
+    pub text: String,
+    pub date_created: OffsetDateTime,
+}
+
+impl PollTextAnswer {
+    #[tracing::instrument(skip(txn, text), err)]
+    pub async fn insert(txn: &mut PgTransaction<'_>, poll_id: i64, text: &str) -> Result<Self> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"
+INSERT INTO poll_text_answers (
+    poll_id, text
+)
+VALUES ($1, $2)
+RETURNING
+    poll_id,
+    text,
+    date_created
+            "#,
+            poll_id,
+            text
+        )
+        .fetch_one(txn)
+        .await?)
+    }
+}
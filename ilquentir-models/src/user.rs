@@ -1,6 +1,7 @@
 use color_eyre::Result;
 use sqlx::FromRow;
 use strum::IntoEnumIterator;
+use time::Date;
 
 use crate::{PgTransaction, Poll, PollKind};
 
@@ -10,6 +11,15 @@ pub struct User {
     pub active: bool,
 }
 
+/// How regularly the user answers the polls, days are local to the user
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AnswerStats {
+    /// Days with at least one answer
+    pub answered_days: i64,
+    /// Consecutive answered days till today, or till yesterday, if there are no answers today yet
+    pub streak: i64,
+}
+
 impl User {
     #[tracing::instrument(skip(txn), err)]
     pub async fn get_user_by_id(
@@ -31,6 +41,20 @@ WHERE
         .await?)
     }
 
+    /// Whether the user has ever started the bot, even if they stopped it since
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn exists(txn: &mut PgTransaction<'_>, user_tg_id: i64) -> Result<bool> {
+        Ok(sqlx::query!(
+            r#"
+SELECT EXISTS (SELECT 1 FROM users WHERE tg_id = $1) AS "exists!"
+            "#,
+            user_tg_id,
+        )
+        .fetch_one(txn)
+        .await?
+        .exists)
+    }
+
     #[tracing::instrument(skip(txn), err)]
     pub async fn activate(txn: &mut PgTransaction<'_>, user_tg_id: i64) -> Result<Self> {
         Ok(sqlx::query_as!(
@@ -80,13 +104,13 @@ RETURNING
         Ok(sqlx::query!(
             r#"
 SELECT
-    COUNT(DISTINCT poll.tg_id) as "n_answered!"
+    COUNT(DISTINCT poll.id) as "n_answered!"
 FROM
     polls AS poll
 JOIN
     poll_answers AS answer
 ON
-    poll.tg_id = answer.poll_tg_id
+    poll.id = answer.poll_id
 WHERE
     poll.chat_tg_id = $1
     AND poll.kind = $2
@@ -99,6 +123,154 @@ WHERE
         .n_answered)
     }
 
+    /// Answer stats of the user, whose UTC offset and local date are given
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn answer_stats(
+        txn: &mut PgTransaction<'_>,
+        user_tg_id: i64,
+        utc_offset_secs: i32,
+        today: Date,
+    ) -> Result<AnswerStats> {
+        let stats = sqlx::query!(
+            r#"
+WITH answer_days AS (
+    SELECT DISTINCT
+        (answer.date_created + make_interval(secs => $2))::DATE AS day
+    FROM (
+        SELECT user_tg_id, date_created
+        FROM poll_answers
+        UNION ALL
+        SELECT user_tg_id, date_created
+        FROM poll_dimension_answers
+        UNION ALL
+        SELECT poll.chat_tg_id, text_answer.date_created
+        FROM poll_text_answers AS text_answer
+        JOIN polls AS poll ON poll.id = text_answer.poll_id
+    ) AS answer
+    WHERE answer.user_tg_id = $1
+),
+streaks AS (
+    -- days of a streak have the same difference with their row number
+    SELECT
+        day,
+        day - (ROW_NUMBER() OVER (ORDER BY day))::INT AS streak_start
+    FROM answer_days
+),
+last_streak AS (
+    SELECT streak_start, MAX(day) AS last_day, COUNT(*) AS days
+    FROM streaks
+    GROUP BY streak_start
+    ORDER BY streak_start DESC
+    LIMIT 1
+)
+SELECT
+    (SELECT COUNT(*) FROM answer_days) AS "answered_days!",
+    COALESCE(
+        (SELECT days FROM last_streak WHERE last_day >= $3::DATE - 1),
+        0
+    ) AS "streak!"
+            "#,
+            user_tg_id,
+            f64::from(utc_offset_secs),
+            today,
+        )
+        .fetch_one(txn)
+        .await?;
+
+        Ok(AnswerStats {
+            answered_days: stats.answered_days,
+            streak: stats.streak,
+        })
+    }
+
+    /// IANA name of the user's timezone, `None` if user hasn't shared it yet
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn get_timezone(
+        txn: &mut PgTransaction<'_>,
+        user_tg_id: i64,
+    ) -> Result<Option<String>> {
+        Ok(sqlx::query!(
+            r#"
+SELECT timezone
+FROM users
+WHERE
+    tg_id = $1
+            "#,
+            user_tg_id,
+        )
+        .fetch_optional(txn)
+        .await?
+        .and_then(|user| user.timezone))
+    }
+
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn set_timezone(
+        txn: &mut PgTransaction<'_>,
+        user_tg_id: i64,
+        timezone: &str,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+UPDATE
+    users
+SET
+    timezone = $2
+WHERE
+    tg_id = $1
+            "#,
+            user_tg_id,
+            timezone,
+        )
+        .execute(txn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Language, chosen by the user, or the language of their Telegram client
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn get_language(
+        txn: &mut PgTransaction<'_>,
+        user_tg_id: i64,
+    ) -> Result<Option<String>> {
+        Ok(sqlx::query!(
+            r#"
+SELECT COALESCE(language, language_code) AS language
+FROM users
+WHERE
+    tg_id = $1
+            "#,
+            user_tg_id,
+        )
+        .fetch_optional(txn)
+        .await?
+        .and_then(|user| user.language))
+    }
+
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn set_language(
+        txn: &mut PgTransaction<'_>,
+        user_tg_id: i64,
+        language: &str,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+UPDATE
+    users
+SET
+    language = $2
+WHERE
+    tg_id = $1
+            "#,
+            user_tg_id,
+            language,
+        )
+        .execute(txn)
+        .await?;
+
+        Ok(())
+    }
+
     #[tracing::instrument(skip(txn), err)]
     pub async fn get_active(txn: &mut PgTransaction<'_>) -> Result<Vec<Self>> {
         Ok(sqlx::query_as!(
@@ -112,9 +284,4 @@ WHERE active
         .fetch_all(txn)
         .await?)
     }
-
-    #[tracing::instrument]
-    pub fn subscribed_for_polls(&self) -> Vec<PollKind> {
-        vec![PollKind::HowWasYourDay]
-    }
 }
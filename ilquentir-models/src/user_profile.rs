@@ -0,0 +1,95 @@
+use color_eyre::Result;
+use sqlx::FromRow;
+use time::OffsetDateTime;
+
+use crate::PgTransaction;
+
+/// Telegram profile of the user, `None` fields weren't shared with the bot or weren't seen yet
+#[derive(Debug, Clone, FromRow)]
+pub struct UserProfile {
+    pub tg_id: i64,
+    pub active: bool,
+    pub username: Option<String>,
+    pub first_name: Option<String>,
+    /// IETF language tag of the user's Telegram client
+    pub language_code: Option<String>,
+    pub date_created: OffsetDateTime,
+    pub date_last_seen: Option<OffsetDateTime>,
+}
+
+impl UserProfile {
+    /// Updates the profile and marks the user as seen right now.
+    ///
+    /// Users, who have never started the bot, are skipped. Returns whether the user was found.
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn refresh(
+        txn: &mut PgTransaction<'_>,
+        tg_id: i64,
+        username: Option<&str>,
+        first_name: &str,
+        language_code: Option<&str>,
+    ) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+UPDATE
+    users
+SET
+    username = $2,
+    first_name = $3,
+    language_code = $4,
+    date_last_seen = NOW()
+WHERE
+    tg_id = $1
+            "#,
+            tg_id,
+            username,
+            first_name,
+            language_code,
+        )
+        .execute(txn)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn get(txn: &mut PgTransaction<'_>, tg_id: i64) -> Result<Option<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"
+SELECT
+    tg_id, active, username, first_name, language_code, date_created, date_last_seen
+FROM users
+WHERE
+    tg_id = $1
+            "#,
+            tg_id,
+        )
+        .fetch_optional(txn)
+        .await?)
+    }
+
+    /// Usernames are case-insensitive and may move between accounts,
+    /// so the most recently seen user goes first
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn find_by_username(
+        txn: &mut PgTransaction<'_>,
+        username: &str,
+    ) -> Result<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"
+SELECT
+    tg_id, active, username, first_name, language_code, date_created, date_last_seen
+FROM users
+WHERE
+    LOWER(username) = LOWER($1)
+ORDER BY
+    date_last_seen DESC NULLS LAST
+            "#,
+            username,
+        )
+        .fetch_all(txn)
+        .await?)
+    }
+}
@@ -0,0 +1,193 @@
+use color_eyre::Result;
+
+use crate::PgTransaction;
+
+/// Where the user came from, parsed from the `/start` deep link payload
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserSource {
+    pub user_tg_id: i64,
+    /// Raw payload, kept even if nothing was parsed out of it
+    pub start_param: String,
+    pub referrer_tg_id: Option<i64>,
+    pub campaign: Option<String>,
+    pub partner_code: Option<String>,
+}
+
+/// Sign-ups and retention of the users, who came from the same source
+#[derive(Debug, Clone)]
+pub struct SourceStats {
+    /// `partner:<code>`, `campaign:<name>`, `referral`, `other` (unparsed payload)
+    /// or `organic` (no payload at all)
+    pub source: String,
+    pub signups: i64,
+    /// Users, who haven't stopped the bot
+    pub active: i64,
+    /// Users, who signed up a week or more ago, i.e. could have been retained
+    pub week_old: i64,
+    /// Users among the week old ones, who answered any poll a week or more after the sign-up
+    pub retained: i64,
+}
+
+impl UserSource {
+    /// Saves the source, unless the user already has one: the first touch wins.
+    ///
+    /// Referrer, who isn't a user of the bot, is dropped. Returns whether the source was saved.
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn save(&self, txn: &mut PgTransaction<'_>) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+INSERT INTO user_sources (
+    user_tg_id, start_param, referrer_tg_id, campaign, partner_code
+)
+VALUES ($1, $2, (SELECT tg_id FROM users WHERE tg_id = $3), $4, $5)
+ON CONFLICT (user_tg_id) DO NOTHING
+            "#,
+            self.user_tg_id,
+            self.start_param,
+            self.referrer_tg_id,
+            self.campaign,
+            self.partner_code,
+        )
+        .execute(txn)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Number of users, who joined via the referrer's invite link
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn count_referred(txn: &mut PgTransaction<'_>, referrer_tg_id: i64) -> Result<i64> {
+        Ok(sqlx::query!(
+            r#"
+SELECT COUNT(*) AS "n_referred!"
+FROM user_sources
+WHERE referrer_tg_id = $1
+            "#,
+            referrer_tg_id,
+        )
+        .fetch_one(txn)
+        .await?
+        .n_referred)
+    }
+
+    /// Stats for every source, the most popular ones go first
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn stats(txn: &mut PgTransaction<'_>) -> Result<Vec<SourceStats>> {
+        Ok(sqlx::query_as!(
+            SourceStats,
+            r#"
+WITH sourced_users AS (
+    SELECT
+        COALESCE(
+            'partner:' || source.partner_code,
+            'campaign:' || source.campaign,
+            CASE WHEN source.referrer_tg_id IS NOT NULL THEN 'referral' END,
+            CASE WHEN source.user_tg_id IS NOT NULL THEN 'other' END,
+            'organic'
+        ) AS source,
+        users.active,
+        users.date_created <= NOW() - INTERVAL '7 days' AS week_old,
+        EXISTS (
+            SELECT 1
+            FROM poll_answers AS answer
+            WHERE
+                answer.user_tg_id = users.tg_id
+                AND answer.date_created >= users.date_created + INTERVAL '7 days'
+        ) OR EXISTS (
+            SELECT 1
+            FROM poll_dimension_answers AS answer
+            WHERE
+                answer.user_tg_id = users.tg_id
+                AND answer.date_created >= users.date_created + INTERVAL '7 days'
+        ) OR EXISTS (
+            SELECT 1
+            FROM poll_text_answers AS answer
+            JOIN polls ON polls.id = answer.poll_id
+            WHERE
+                polls.chat_tg_id = users.tg_id
+                AND answer.date_created >= users.date_created + INTERVAL '7 days'
+        ) AS retained
+    FROM
+        users
+    LEFT JOIN
+        user_sources AS source
+    ON
+        users.tg_id = source.user_tg_id
+)
+SELECT
+    source AS "source!",
+    COUNT(*) AS "signups!",
+    COUNT(*) FILTER (WHERE active) AS "active!",
+    COUNT(*) FILTER (WHERE week_old) AS "week_old!",
+    COUNT(*) FILTER (WHERE week_old AND retained) AS "retained!"
+FROM sourced_users
+GROUP BY source
+ORDER BY 2 DESC, 1
+            "#
+        )
+        .fetch_all(txn)
+        .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::PgPool;
+
+    use crate::{Poll, PollDimensionAnswer, PollKind, PollPart, PollTextAnswer, User};
+
+    use super::*;
+
+    async fn signed_up(txn: &mut PgTransaction<'_>, user_tg_id: i64, days_ago: i32) -> Result<()> {
+        User::activate(&mut *txn, user_tg_id).await?;
+        sqlx::query(
+            "UPDATE users SET date_created = NOW() - make_interval(days => $2) WHERE tg_id = $1",
+        )
+        .bind(user_tg_id)
+        .bind(days_ago)
+        .execute(txn)
+        .await?;
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_retention(pool: PgPool) -> color_eyre::Result<()> {
+        let mut txn = pool.begin().await?;
+
+        // answered the gratitude prompt a week after the sign-up
+        signed_up(&mut txn, 1, 10).await?;
+        let poll = Poll::create(&mut txn, 1, PollKind::GratitudeJournal, None).await?;
+        PollTextAnswer::insert(&mut txn, poll.id.unwrap(), "Спасибо").await?;
+
+        // rated the check-in a week after the sign-up
+        signed_up(&mut txn, 2, 10).await?;
+        let poll = Poll::create(&mut txn, 2, PollKind::MoodCheckIn, None).await?;
+        let part = PollPart {
+            id: None,
+            poll_id: poll.id.unwrap(),
+            tg_id: None,
+            tg_message_id: Some(1),
+            options_offset: 0,
+            options: vec!["Энергия".to_owned()],
+            option_ids: vec![None],
+        }
+        .insert(&mut txn)
+        .await?;
+        PollDimensionAnswer::save(&mut txn, &part, 2, 0, 3).await?;
+
+        // didn't come back
+        signed_up(&mut txn, 3, 10).await?;
+        // too young to be counted
+        signed_up(&mut txn, 4, 2).await?;
+
+        let stats = UserSource::stats(&mut txn).await?;
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].source, "organic");
+        assert_eq!(stats[0].signups, 4);
+        assert_eq!(stats[0].week_old, 3);
+        assert_eq!(stats[0].retained, 2);
+
+        Ok(())
+    }
+}
@@ -9,11 +9,28 @@ pub struct WideHowWasYourDay {
     pub poll_date_about: OffsetDateTime,
     pub user_tg_id: i64,
     pub answer_selected_value: Option<i32>,
+    /// Answer on the -1..1 scale, comparable between scales of different resolution
+    pub answer_normalized_value: Option<f64>,
     pub events: Option<String>,
+    /// User's note on why the day was like that
+    pub note: Option<String>,
 }
 
 impl WideHowWasYourDay {
     pub async fn collect(txn: &mut PgTransaction<'_>) -> Result<Vec<Self>> {
+        Self::fetch(txn, None).await
+    }
+
+    /// Same as [`Self::collect`], but only for the given user
+    #[tracing::instrument(skip(txn), err)]
+    pub async fn collect_for_user(
+        txn: &mut PgTransaction<'_>,
+        user_tg_id: i64,
+    ) -> Result<Vec<Self>> {
+        Self::fetch(txn, Some(user_tg_id)).await
+    }
+
+    async fn fetch(txn: &mut PgTransaction<'_>, user_tg_id: Option<i64>) -> Result<Vec<Self>> {
         Ok(sqlx::query_as!(
             Self,
             r#"
@@ -21,23 +38,37 @@ SELECT
     date_trunc('day', polls.publication_date - INTERVAL '12 hours') as "poll_date_about!",
     polls.chat_tg_id as "user_tg_id!",
     poll_answers.selected_value as "answer_selected_value?",
-    event_polls.events
+    poll_answers.normalized_value as "answer_normalized_value?",
+    event_polls.events,
+    poll_answer_notes.text as "note?"
 FROM polls
 LEFT JOIN
     poll_answers
 ON
-    polls.tg_id = poll_answers.poll_tg_id
+    polls.id = poll_answers.poll_id
+LEFT JOIN
+    poll_answer_notes
+ON
+    polls.id = poll_answer_notes.poll_id
 LEFT JOIN
 (
     SELECT
         polls.publication_date,
         polls.chat_tg_id as chat_tg_id,
-        ARRAY_TO_STRING(ARRAY_AGG('• ' || poll_answers.selected_value_text), ',<br>') as "events"
+        ARRAY_TO_STRING(
+            ARRAY_AGG('• ' || COALESCE(poll_option_latest_texts.text, poll_answers.selected_value_text)),
+            ',<br>'
+        ) as "events"
     FROM polls
     JOIN
         poll_answers
     ON
-        polls.tg_id = poll_answers.poll_tg_id
+        polls.id = poll_answers.poll_id
+    -- options are shown with their current text, so fixed typos are fixed in the history too
+    LEFT JOIN
+        poll_option_latest_texts
+    ON
+        poll_answers.option_id = poll_option_latest_texts.option_id
     WHERE
         polls.published
         AND polls.kind = 'daily_events'
@@ -51,7 +82,11 @@ ON
 WHERE
     polls.published
     AND polls.kind = 'how_was_your_day'
-            "#
+    AND ($1::BIGINT IS NULL OR polls.chat_tg_id = $1)
+ORDER BY
+    polls.publication_date
+            "#,
+            user_tg_id,
         )
         .fetch_all(txn)
         .await?)
@@ -0,0 +1,43 @@
+use color_eyre::Result;
+use time::OffsetDateTime;
+
+use crate::PgTransaction;
+
+/// Export of multi-dimensional check-ins, one row per rated dimension per day
+#[derive(Debug, Clone, sqlx::FromRow, serde::Serialize)]
+pub struct WideMoodCheckIn {
+    #[serde(with = "time::serde::rfc3339")]
+    pub poll_date_about: OffsetDateTime,
+    pub user_tg_id: i64,
+    pub dimension: String,
+    pub value: i32,
+}
+
+impl WideMoodCheckIn {
+    pub async fn collect(txn: &mut PgTransaction<'_>) -> Result<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"
+SELECT
+    date_trunc('day', polls.publication_date - INTERVAL '12 hours') as "poll_date_about!",
+    polls.chat_tg_id as "user_tg_id!",
+    COALESCE(poll_option_latest_texts.text, poll_dimension_answers.dimension) as "dimension!",
+    poll_dimension_answers.value
+FROM polls
+JOIN
+    poll_dimension_answers
+ON
+    polls.id = poll_dimension_answers.poll_id
+LEFT JOIN
+    poll_option_latest_texts
+ON
+    poll_dimension_answers.option_id = poll_option_latest_texts.option_id
+WHERE
+    polls.published
+    AND polls.kind = 'mood_check_in'
+            "#
+        )
+        .fetch_all(txn)
+        .await?)
+    }
+}
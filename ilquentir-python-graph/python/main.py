@@ -1,6 +1,6 @@
-def make_plot(data, user_tg_id, date_start, date_end):
+def make_plot(data, check_ins, user_tg_id, date_start, date_end):
     '''
-    Takes data as pandas DataFrame, user_tg_id as int
+    Takes data and check_ins as pandas DataFrames, user_tg_id as int
     and date_start, date_end as string in format '%Y-%m-%d'.
 
     Returns string with html plot representation.
@@ -12,8 +12,12 @@ def make_plot(data, user_tg_id, date_start, date_end):
 
     DATE_COL = 'poll_date_about'
     USER_COL = 'user_tg_id'
-    ANSW_COL = 'answer_selected_value'
+    ANSW_COL = 'answer_normalized_value'
     EVENTS = 'events'
+    DIMENSION_COL = 'dimension'
+    VALUE_COL = 'value'
+    NOTE = 'note'
+    HOVER = 'hover'
 
     DATE_PLOT_FORMAT = '%d-%m-%y, %a'
 
@@ -62,10 +66,14 @@ def make_plot(data, user_tg_id, date_start, date_end):
         df[DATE_COL],
         # format='%d.%m.%Y %H:%M:%S'
     )
-    df[ANSW_COL] = 2 - df[ANSW_COL]
+    # answers are normalized to -1..1, while the graph uses -2..2 scale
+    df[ANSW_COL] = 2 * df[ANSW_COL]
     df = df.drop_duplicates(subset=[USER_COL, DATE_COL])
     df = df[df[DATE_COL].between(date_start, date_end)]
     df = df.fillna(value={EVENTS: '∅'})
+    df[HOVER] = df[EVENTS] + df[NOTE].apply(
+        lambda note: '' if pd.isna(note) else '<br><br>Почему так:<br>' + format_note(note)
+    )
 
     df_metrics = df.dropna(subset=[ANSW_COL]).groupby(DATE_COL)[ANSW_COL].agg(
         {
@@ -74,7 +82,7 @@ def make_plot(data, user_tg_id, date_start, date_end):
     ).round(2)
 
     df_metrics = df_metrics.join(
-        df[df[USER_COL] == user_tg_id].set_index(DATE_COL)[[ANSW_COL, EVENTS]].round(0)
+        df[df[USER_COL] == user_tg_id].set_index(DATE_COL)[[ANSW_COL, HOVER]].round(2)
     ).reset_index()
 
     df_metrics['answ_normalized'] = df_metrics[ANSW_COL] + 3
@@ -99,7 +107,7 @@ def make_plot(data, user_tg_id, date_start, date_end):
     fig.add_scatter(
         x=df_metrics[DATE_COL],
         y=df_metrics['answ_normalized'],
-        hovertext=df_metrics[EVENTS],
+        hovertext=df_metrics[HOVER],
         hovertemplate='%{y}<br><br>Что было:<br>%{hovertext}',
         hoverinfo='text',
         mode='lines+markers',
@@ -109,6 +117,26 @@ def make_plot(data, user_tg_id, date_start, date_end):
         name='Твоя оценка'
     )
 
+    # add a line for every dimension of user's check-ins,
+    # 1..5 scale matches normalized answers, so they share the axis
+    user_check_ins = check_ins[check_ins[USER_COL] == user_tg_id].copy()
+    user_check_ins[DATE_COL] = pd.to_datetime(user_check_ins[DATE_COL])
+    user_check_ins = user_check_ins[user_check_ins[DATE_COL].between(date_start, date_end)]
+
+    for dimension, dimension_data in user_check_ins.groupby(DIMENSION_COL):
+        dimension_data = dimension_data.sort_values(DATE_COL)
+
+        fig.add_scatter(
+            x=dimension_data[DATE_COL],
+            y=dimension_data[VALUE_COL],
+            hovertemplate='%{y}',
+            mode='lines+markers',
+            line=dict(width=2, dash='dot'),
+            marker=dict(size=8),
+            connectgaps=False,
+            name=dimension,
+        )
+
     fig.update_layout(
         yaxis = dict(
             tickvals = [0, 1, 2, 3, 4, 5, 6],
@@ -129,11 +157,26 @@ def make_plot(data, user_tg_id, date_start, date_end):
         post_script="document.querySelector('body').style.margin = '0px'; window.dispatchEvent(new Event('resize'));"
     )
 
+def format_note(note, line_width=40):
+    '''
+    Escapes user's note and wraps it into lines, so it fits into hover label.
+    '''
+    import html
+    import textwrap
+
+    return '<br>'.join(html.escape(line) for line in textwrap.wrap(note, width=line_width))
+
 if __name__ == '__main__':
     import pandas as pd
     import sys
 
-    wide_file, id_, date_from, date_to = sys.argv[1:]
+    wide_file, check_ins_file, id_, date_from, date_to = sys.argv[1:]
     data = pd.read_csv(wide_file)
 
-    print(make_plot(data, int(id_), date_from, date_to))
+    try:
+        check_ins = pd.read_csv(check_ins_file)
+    except pd.errors.EmptyDataError:
+        # nobody has answered check-ins yet
+        check_ins = pd.DataFrame(columns=['poll_date_about', 'user_tg_id', 'dimension', 'value'])
+
+    print(make_plot(data, check_ins, int(id_), date_from, date_to))
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 
 use aws_sdk_s3::{config::Region, primitives::ByteStream, Client};
 use bytes::Bytes;
@@ -7,6 +7,7 @@ use color_eyre::{
     Result,
 };
 use csv_async::Terminator;
+use serde::Serialize;
 use tokio::{
     fs::{File, OpenOptions},
     process::Command,
@@ -15,7 +16,7 @@ use tokio::{
 use tracing::{debug, info};
 
 use ilquentir_config::Config;
-use ilquentir_models::{PgTransaction, WideHowWasYourDay};
+use ilquentir_models::{PgTransaction, WideHowWasYourDay, WideMoodCheckIn};
 use url::Url;
 
 #[derive(Debug, Clone)]
@@ -24,6 +25,7 @@ pub struct Plotter {
     // I think it's not the best solution, but I don't know how to do it better RN,
     // truncating it via File::set_len() doesn't work.
     wide: Arc<RwLock<File>>,
+    mood_check_in: Arc<RwLock<File>>,
     aws_client: Client,
     config: Config,
 }
@@ -31,31 +33,12 @@ pub struct Plotter {
 impl Plotter {
     #[tracing::instrument(skip_all, err)]
     pub async fn new(txn: &mut PgTransaction<'_>, config: Config) -> Result<Self> {
-        let wide_path = &config.graph.wide_how_was_your_day_path;
-
-        let file_ = OpenOptions::new()
-            .read(true)
-            .write(true)
-            .open(wide_path)
-            .await;
-
-        let wide = match file_ {
-            Ok(file_) => file_,
-            Err(e) => match e.kind() {
-                std::io::ErrorKind::NotFound => {
-                    info!("not found wide, creating");
-
-                    OpenOptions::new()
-                        .write(true)
-                        .read(true)
-                        .create_new(true)
-                        .open(wide_path)
-                        .await?
-                }
-                _ => bail!(e),
-            },
-        };
-        let wide = Arc::new(RwLock::new(wide));
+        let wide = Arc::new(RwLock::new(
+            open_or_create(&config.graph.wide_how_was_your_day_path).await?,
+        ));
+        let mood_check_in = Arc::new(RwLock::new(
+            open_or_create(&config.graph.wide_mood_check_in_path).await?,
+        ));
 
         let shared_config = aws_config::from_env()
             .endpoint_url(&config.s3.endpoint)
@@ -67,6 +50,7 @@ impl Plotter {
 
         let this = Self {
             wide,
+            mood_check_in,
             aws_client,
             config,
         };
@@ -96,26 +80,16 @@ impl Plotter {
             return Ok(false);
         }
         let wide_table = WideHowWasYourDay::collect(txn).await?;
+        *wide_file = export_csv(&self.config.graph.wide_how_was_your_day_path, wide_table).await?;
 
-        // clear contents of wide export
-        *wide_file = OpenOptions::new()
-            .read(true)
-            .write(true)
-            .truncate(true)
-            .open(&self.config.graph.wide_how_was_your_day_path)
-            .await?;
-
-        // export data anew
-        let mut writer = csv_async::AsyncWriterBuilder::new()
-            .terminator(Terminator::CRLF)
-            .has_headers(true)
-            .create_serializer(&mut *wide_file);
-
-        for line in wide_table {
-            writer.serialize(line).await?;
-        }
-        drop(writer);
-        wide_file.sync_all().await?;
+        // check-ins are refreshed together with the wide table
+        let mut mood_check_in_file = self.mood_check_in.write().await;
+        let mood_check_in_table = WideMoodCheckIn::collect(txn).await?;
+        *mood_check_in_file = export_csv(
+            &self.config.graph.wide_mood_check_in_path,
+            mood_check_in_table,
+        )
+        .await?;
 
         info!("wide table updated");
 
@@ -144,14 +118,17 @@ impl Plotter {
     async fn plot(&self, user_tg_id: i64) -> Result<String> {
         let script_path = &self.config.graph.plotly_python_code_file;
         let wide_path = &self.config.graph.wide_how_was_your_day_path;
+        let mood_check_in_path = &self.config.graph.wide_mood_check_in_path;
 
         let _wide = self.wide.read().await;
+        let _mood_check_in = self.mood_check_in.read().await;
 
         let mut command = Command::new("python3.11");
 
         command
             .arg(script_path)
             .arg(wide_path)
+            .arg(mood_check_in_path)
             .arg(user_tg_id.to_string())
             .args([
                 self.config.graph.start_date.to_string(),
@@ -195,3 +172,51 @@ impl Plotter {
         Ok(Url::parse(&format!("{static_hostname}/{key}"))?)
     }
 }
+
+#[tracing::instrument(err)]
+async fn open_or_create(path: &Path) -> Result<File> {
+    let file_ = OpenOptions::new().read(true).write(true).open(path).await;
+
+    Ok(match file_ {
+        Ok(file_) => file_,
+        Err(e) => match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                info!(?path, "not found export file, creating");
+
+                OpenOptions::new()
+                    .write(true)
+                    .read(true)
+                    .create_new(true)
+                    .open(path)
+                    .await?
+            }
+            _ => bail!(e),
+        },
+    })
+}
+
+/// Replaces contents of the file with CSV export of `rows`
+#[tracing::instrument(skip(rows), err)]
+async fn export_csv(path: &Path, rows: Vec<impl Serialize>) -> Result<File> {
+    // clear contents of the export
+    let mut file_ = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .truncate(true)
+        .open(path)
+        .await?;
+
+    // export data anew
+    let mut writer = csv_async::AsyncWriterBuilder::new()
+        .terminator(Terminator::CRLF)
+        .has_headers(true)
+        .create_serializer(&mut file_);
+
+    for line in rows {
+        writer.serialize(line).await?;
+    }
+    drop(writer);
+    file_.sync_all().await?;
+
+    Ok(file_)
+}